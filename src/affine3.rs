@@ -0,0 +1,166 @@
+use crate::approx::impl_approx_eq;
+use crate::pose3::Pose;
+use crate::rotation3::Rot3;
+use crate::scalar::Float;
+use crate::utils::matrix::{Mat3, Mat4};
+use crate::vectors::Vec3;
+use core::ops;
+
+/// An affine transform in 3d: a linear part, which may scale, shear or mirror as well as rotate,
+/// followed by a translation. Unlike [Pose3](crate::pose3::Pose3) it needn't be rigid.
+///
+/// Transforms compose like matrices, so `a * b` applies `b` first.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::affine3::Affine3;
+/// use YetAnotherGeometryLibrary::approx::Tolerance;
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// // Millimetres to metres, then mirror in the yz plane and move into place
+/// let to_metres = Affine3::from_scale(Vector3::new(0.001, 0.001, 0.001));
+/// let mirror = Affine3::from_scale(Vector3::new(-1.0, 1.0, 1.0));
+/// let place = Affine3::from_translation(Vector3::new(5.0, 0.0, 0.0));
+/// let t = place * mirror * to_metres;
+/// assert_eq!(t.transform_point(Vector3::new(1000.0, 2000.0, 0.0)), Vector3::new(4.0, 2.0, 0.0));
+/// assert_eq!(t.transform_direction(Vector3::new(1000.0, 0.0, 0.0)), Vector3::new(-1.0, 0.0, 0.0));
+/// assert!(t.is_mirroring());
+/// assert!(Tolerance::default().approx_eq(&(t.inverse().unwrap() * t), &Affine3::identity()));
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Affine<T: Float> {
+    pub linear: Mat3<T>,
+    pub translation: Vec3<T>,
+}
+pub type Affine3 = Affine<f64>;
+pub type Affine3f = Affine<f32>;
+impl_approx_eq!(<T> Affine<T>, linear, translation);
+impl From<Affine3f> for Affine3 {
+    fn from(a: Affine3f) -> Self {
+        a.cast()
+    }
+}
+
+/// The transform that takes points from a pose's frame to the frame it is given in, so that composing
+/// poses with `+` matches composing their transforms with `*`
+impl<T: Float> From<Pose<T>> for Affine<T> {
+    fn from(pose: Pose<T>) -> Self {
+        Affine::new(pose.orientation.to_matrix(), pose.position)
+    }
+}
+
+impl<T: Float> From<Rot3<T>> for Affine<T> {
+    fn from(rotation: Rot3<T>) -> Self {
+        Affine::new(rotation.to_matrix(), Vec3::zero())
+    }
+}
+
+impl<T: Float> ops::Mul<Affine<T>> for Affine<T> {
+    type Output = Affine<T>;
+    fn mul(self, rhs: Affine<T>) -> Affine<T> {
+        Affine::new(
+            self.linear * rhs.linear,
+            self.transform_point(rhs.translation),
+        )
+    }
+}
+
+impl<T: Float> Affine<T> {
+    pub fn new(linear: Mat3<T>, translation: Vec3<T>) -> Self {
+        Self {
+            linear,
+            translation,
+        }
+    }
+    pub fn identity() -> Self {
+        Self::new(Mat3::identity(), Vec3::zero())
+    }
+    pub fn from_translation(translation: Vec3<T>) -> Self {
+        Self::new(Mat3::identity(), translation)
+    }
+    /// Scales along each axis, where a negative scale mirrors
+    pub fn from_scale(scale: Vec3<T>) -> Self {
+        let o = T::ZERO;
+        Self::new(
+            Mat3::new([[scale.x, o, o], [o, scale.y, o], [o, o, scale.z]]),
+            Vec3::zero(),
+        )
+    }
+    /// Reads a transform from a 4x4 matrix acting on homogeneous coordinates
+    ///
+    /// returns: Result<Affine3, String>, which is an error if the bottom row isn't `0 0 0 1`, as it
+    /// would then be a projection
+    pub fn from_matrix4(matrix: &Mat4<T>) -> Result<Self, String> {
+        let (o, l) = (T::ZERO, T::ONE);
+        if matrix.data[3] != [o, o, o, l] {
+            return Err("The bottom row of an affine transform must be 0 0 0 1".to_owned());
+        }
+        let m = matrix.data;
+        Ok(Self::new(
+            Mat3::new([
+                [m[0][0], m[0][1], m[0][2]],
+                [m[1][0], m[1][1], m[1][2]],
+                [m[2][0], m[2][1], m[2][2]],
+            ]),
+            Vec3::new(m[0][3], m[1][3], m[2][3]),
+        ))
+    }
+    /// Gets the 4x4 matrix that applies this transform to homogeneous coordinates
+    pub fn to_matrix4(&self) -> Mat4<T> {
+        let (l, t) = (self.linear.data, self.translation);
+        let (o, one) = (T::ZERO, T::ONE);
+        Mat4::new([
+            [l[0][0], l[0][1], l[0][2], t.x],
+            [l[1][0], l[1][1], l[1][2], t.y],
+            [l[2][0], l[2][1], l[2][2], t.z],
+            [o, o, o, one],
+        ])
+    }
+    /// Transforms a point, which is moved by the translation
+    pub fn transform_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.linear * point + self.translation
+    }
+    /// Transforms a direction or offset, which the translation doesn't affect
+    pub fn transform_direction(&self, direction: Vec3<T>) -> Vec3<T> {
+        self.linear * direction
+    }
+    /// Transforms a surface normal, which must stay perpendicular to the surface rather than move with
+    /// it, so under a non-uniform scale it turns the other way to a direction. The result is not
+    /// normalized.
+    ///
+    /// returns: Option<Vector3>, which is `None` if the transform flattens space
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::affine3::Affine3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let squash = Affine3::from_scale(Vector3::new(1.0, 0.5, 1.0));
+    /// let slope = Vector3::new(1.0, 1.0, 0.0);
+    /// let normal = Vector3::new(1.0, -1.0, 0.0);
+    /// let squashed = squash.transform_direction(slope);
+    /// assert_eq!(squashed.dot(&squash.transform_normal(normal).unwrap()), 0.0);
+    /// ```
+    pub fn transform_normal(&self, normal: Vec3<T>) -> Option<Vec3<T>> {
+        Some(self.linear.inverse()?.transpose() * normal)
+    }
+    /// Gets the transform that undoes this one
+    ///
+    /// returns: Option<Affine3>, which is `None` if the transform flattens space
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear.inverse()?;
+        Some(Self::new(linear, -(linear * self.translation)))
+    }
+    /// How much the transform scales volumes, which is negative if it mirrors
+    pub fn determinant(&self) -> T {
+        self.linear.determinant()
+    }
+    /// Determines if the transform mirrors, which turns counterclockwise faces clockwise
+    pub fn is_mirroring(&self) -> bool {
+        self.determinant() < T::ZERO
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Affine<U> {
+        Affine::new(self.linear.cast(), self.translation.cast())
+    }
+}
@@ -0,0 +1,186 @@
+//! Approximate equality with an explicit tolerance. The `PartialEq` implementations on vectors and
+//! quaternions compare with a fixed absolute threshold, which is too loose for tiny values and too
+//! strict for huge ones; [ApproxEq] lets the caller choose.
+
+/// Comparison of values that may differ by rounding error. Composite values such as vectors compare
+/// each of their components, and are equal only if all of them are.
+pub trait ApproxEq {
+    /// Equal if no component differs by more than `epsilon`
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool;
+    /// Equal if no component differs by more than `epsilon`, or by more than `max_relative` times the
+    /// larger of the two
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool;
+    /// Equal if no component differs by more than `epsilon`, or by more than `max_ulps` representable
+    /// floats
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool;
+}
+
+/// How close two values must be to count as the same, with an absolute part for values near zero and
+/// a relative part that scales with the values compared
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::approx::Tolerance;
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// let tolerance = Tolerance::new(1E-12, 1E-9);
+/// let far = Vector3::new(1E6, 0.0, 0.0);
+/// assert!(tolerance.approx_eq(&far, &(far + Vector3::new(1E-4, 0.0, 0.0))));
+/// assert!(!tolerance.approx_eq(&Vector3::zero(), &Vector3::new(1E-4, 0.0, 0.0)));
+/// assert!(tolerance.is_zero(1E-4, 1E6));
+/// assert!(!Tolerance::EXACT.is_zero(1E-300, 1.0));
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    /// An absolute tolerance of `1E-12` and a relative tolerance of about a thousand rounding errors
+    fn default() -> Self {
+        Self::new(1E-12, 1000.0 * f64::EPSILON)
+    }
+}
+
+impl Tolerance {
+    /// No tolerance at all, so only exactly equal values are equal
+    pub const EXACT: Tolerance = Tolerance {
+        absolute: 0.0,
+        relative: 0.0,
+    };
+    pub fn new(absolute: f64, relative: f64) -> Self {
+        Self { absolute, relative }
+    }
+    pub fn absolute(absolute: f64) -> Self {
+        Self::new(absolute, 0.0)
+    }
+    pub fn relative(relative: f64) -> Self {
+        Self::new(0.0, relative)
+    }
+    /// Compares two values with [ApproxEq::relative_eq]
+    pub fn approx_eq<T: ApproxEq + ?Sized>(&self, a: &T, b: &T) -> bool {
+        a.relative_eq(b, self.absolute, self.relative)
+    }
+    /// Determines if a value computed from quantities of size `scale` is zero up to rounding, for example
+    /// a distance from a plane computed from coordinates of that size
+    pub fn is_zero(&self, value: f64, scale: f64) -> bool {
+        value.abs() <= self.absolute + self.relative * scale.abs()
+    }
+}
+
+impl ApproxEq for f64 {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self == other || (self - other).abs() <= epsilon
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        if self.is_infinite() || other.is_infinite() {
+            return false;
+        }
+        (self - other).abs() <= max_relative * self.abs().max(other.abs())
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+            return false;
+        }
+        self.to_bits().abs_diff(other.to_bits()) <= max_ulps as u64
+    }
+}
+
+/// Compared in `f64`, which holds every `f32` exactly, except that ulps count `f32` steps
+impl ApproxEq for f32 {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (*self as f64).abs_diff_eq(&(*other as f64), epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        (*self as f64).relative_eq(&(*other as f64), epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+            return false;
+        }
+        self.to_bits().abs_diff(other.to_bits()) <= max_ulps
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+    }
+}
+
+impl<T: ApproxEq, const N: usize> ApproxEq for [T; N] {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self[..].abs_diff_eq(&other[..], epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self[..].relative_eq(&other[..], epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self[..].ulps_eq(&other[..], epsilon, max_ulps)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self[..].abs_diff_eq(&other[..], epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self[..].relative_eq(&other[..], epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self[..].ulps_eq(&other[..], epsilon, max_ulps)
+    }
+}
+
+/// Implements [ApproxEq] for a struct by comparing each of the listed fields. A struct generic over its
+/// scalar is written `<T> Name<T>`.
+macro_rules! impl_approx_eq {
+    (<$param:ident> $type:ty, $($field:ident),+) => {
+        $crate::approx::impl_approx_eq!(@impl [$param: $crate::scalar::Float] $type, $($field),+);
+    };
+    ($type:ty, $($field:ident),+) => {
+        $crate::approx::impl_approx_eq!(@impl [] $type, $($field),+);
+    };
+    (@impl [$($generics:tt)*] $type:ty, $($field:ident),+) => {
+        impl<$($generics)*> $crate::approx::ApproxEq for $type {
+            fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+                $($crate::approx::ApproxEq::abs_diff_eq(&self.$field, &other.$field, epsilon))&&+
+            }
+            fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+                $($crate::approx::ApproxEq::relative_eq(&self.$field, &other.$field, epsilon, max_relative))&&+
+            }
+            fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+                $($crate::approx::ApproxEq::ulps_eq(&self.$field, &other.$field, epsilon, max_ulps))&&+
+            }
+        }
+    };
+}
+pub(crate) use impl_approx_eq;
@@ -0,0 +1,593 @@
+use crate::gon::{PolygonWithHoles, Triangle};
+use crate::predicates::{incircle, orient2d};
+use crate::vectors::Vector2;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Stands in for the vertex at infinity shared by the ghost triangles outside the hull while building
+const GHOST: usize = usize::MAX;
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// A triangulation of a set of points, storing which triangles are next to each other
+#[derive(Debug, Clone)]
+pub struct Triangulation {
+    points: Vec<Vector2>,
+    triangles: Vec<(usize, usize, usize)>,
+    neighbors: Vec<[Option<usize>; 3]>,
+    constraints: HashSet<(usize, usize)>,
+}
+
+impl Triangulation {
+    /// Computes the Delaunay triangulation of a set of points with the Bowyer-Watson algorithm, so that
+    /// no point lies inside the circumcircle of any triangle.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The points to triangulate. Repeated points are only used once.
+    ///
+    /// returns: Result<Triangulation, String>, an error if there are fewer than 3 distinct points, they
+    /// are all collinear, or any is not finite
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::delaunay::Triangulation;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let points = vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(2.0, 0.0),
+    ///     Vector2::new(2.0, 2.0),
+    ///     Vector2::new(0.0, 2.0),
+    ///     Vector2::new(1.0, 0.5),
+    /// ];
+    /// let tri = Triangulation::delaunay(&points).unwrap();
+    /// assert_eq!(tri.get_triangles().len(), 4);
+    /// let area: f64 = (0..4).map(|t| tri.triangle(t).area()).sum();
+    /// assert_eq!(area, 4.0);
+    /// // Each triangle touches the middle point, and two others
+    /// assert!(tri.get_triangles().iter().all(|&(a, b, c)| a == 4 || b == 4 || c == 4));
+    /// assert!(tri.get_neighbors().iter().all(|n| n.iter().flatten().count() == 2));
+    /// ```
+    pub fn delaunay(points: &[Vector2]) -> Result<Self, String> {
+        let (builder, _) = Builder::build(points)?;
+        Ok(builder.finish(|_| true))
+    }
+    /// Computes the constrained Delaunay triangulation of a set of points, which contains the given
+    /// edges and is otherwise as close to Delaunay as they allow. Edges passing through other points
+    /// are split at them.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The points to triangulate
+    /// * `edges`: Pairs of indices into `points` which must be joined by edges
+    ///
+    /// returns: Result<Triangulation, String>, an error if the points can't be triangulated, an edge
+    /// refers to a point that doesn't exist or joins a point to itself, or two edges cross
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::delaunay::Triangulation;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// // A flat diamond, whose Delaunay triangulation uses the short diagonal
+    /// let points = vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(2.0, -0.5),
+    ///     Vector2::new(4.0, 0.0),
+    ///     Vector2::new(2.0, 0.5),
+    /// ];
+    /// assert!(!Triangulation::delaunay(&points).unwrap().has_edge(0, 2));
+    /// let tri = Triangulation::constrained(&points, &[(0, 2)]).unwrap();
+    /// assert!(tri.has_edge(0, 2));
+    /// assert!(tri.is_constrained(2, 0));
+    /// assert!(Triangulation::constrained(&points, &[(0, 2), (1, 3)]).is_err());
+    /// ```
+    pub fn constrained(points: &[Vector2], edges: &[(usize, usize)]) -> Result<Self, String> {
+        let (mut builder, canonical) = Builder::build(points)?;
+        builder.insert_constraints(edges, &canonical)?;
+        Ok(builder.finish(|_| true))
+    }
+    /// Triangulates a region with the constrained Delaunay triangulation of its corners, keeping its
+    /// edges and dropping the triangles outside it or in its holes.
+    ///
+    /// # Arguments
+    ///
+    /// * `region`: The region to triangulate. Its points are numbered outer ring first, then each hole.
+    ///
+    /// returns: Result<Triangulation, String>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::delaunay::Triangulation;
+    /// use YetAnotherGeometryLibrary::gon::{Polygon, PolygonWithHoles};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let outer = Polygon::rectangle(Vector2::new(0.0, 0.0), Vector2::new(3.0, 3.0));
+    /// let hole = Polygon::rectangle(Vector2::new(1.0, 1.0), Vector2::new(2.0, 2.0));
+    /// let frame = PolygonWithHoles::new(outer, vec![hole]).unwrap();
+    /// let tri = Triangulation::from_polygon(&frame).unwrap();
+    /// assert_eq!(tri.get_triangles().len(), 8);
+    /// let area: f64 = (0..8).map(|t| tri.triangle(t).area()).sum();
+    /// assert_eq!(area, 8.0);
+    /// ```
+    pub fn from_polygon(region: &PolygonWithHoles) -> Result<Self, String> {
+        let mut points = Vec::new();
+        let mut edges = Vec::new();
+        for ring in std::iter::once(region.get_outer()).chain(region.get_holes()) {
+            let start = points.len();
+            let n = ring.get_points().len();
+            points.extend_from_slice(ring.get_points());
+            edges.extend((0..n).map(|i| (start + i, start + (i + 1) % n)));
+        }
+        let (mut builder, canonical) = Builder::build(&points)?;
+        builder.insert_constraints(&edges, &canonical)?;
+        let inside = builder.inside_constraints();
+        Ok(builder.finish(|t| inside[t]))
+    }
+    pub fn get_points(&self) -> &[Vector2] {
+        &self.points
+    }
+    /// The corners of each triangle as indices into the points, counterclockwise
+    pub fn get_triangles(&self) -> &[(usize, usize, usize)] {
+        &self.triangles
+    }
+    /// The triangles across each edge of each triangle, if any. Entry `i` is across the edge opposite
+    /// corner `i`, so for the triangle `(a, b, c)` entry 0 is across `b`-`c`.
+    pub fn get_neighbors(&self) -> &[[Option<usize>; 3]] {
+        &self.neighbors
+    }
+    pub fn triangle(&self, index: usize) -> Triangle {
+        let (a, b, c) = self.triangles[index];
+        Triangle::new(self.points[a], self.points[b], self.points[c])
+    }
+    /// Determines if two points are joined by an edge of the triangulation
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.triangles.iter().any(|&(x, y, z)| {
+            [(x, y), (y, z), (z, x)]
+                .iter()
+                .any(|&(u, v)| edge_key(u, v) == edge_key(a, b))
+        })
+    }
+    /// Determines if the edge between two points was required by the triangulation's constraints
+    pub fn is_constrained(&self, a: usize, b: usize) -> bool {
+        self.constraints.contains(&edge_key(a, b))
+    }
+    /// Finds a triangle containing a point, inside or on its boundary
+    pub fn find_triangle(&self, pt: Vector2) -> Option<usize> {
+        (0..self.triangles.len()).find(|&t| self.triangle(t).point_intersects(pt))
+    }
+}
+
+/// The triangulation while it is being built, including ghost triangles joining each hull edge to
+/// [GHOST] so that every triangle has three neighbours
+struct Builder<'a> {
+    points: &'a [Vector2],
+    tris: Vec<[usize; 3]>,
+    adj: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+    /// A live triangle touching each vertex
+    vertex_tri: Vec<usize>,
+    constraints: HashSet<(usize, usize)>,
+    /// Where the next point location starts
+    last: usize,
+}
+
+impl<'a> Builder<'a> {
+    /// Triangulates the distinct points, also returning the index of the first copy of each point
+    fn build(points: &'a [Vector2]) -> Result<(Self, Vec<usize>), String> {
+        let mut first_copy: HashMap<(u64, u64), usize> = HashMap::new();
+        let mut canonical = Vec::with_capacity(points.len());
+        let mut unique = Vec::new();
+        for (i, pt) in points.iter().enumerate() {
+            if !pt.x.is_finite() || !pt.y.is_finite() {
+                return Err(format!("point {i} is not finite"));
+            }
+            // Adding zero turns -0.0 into 0.0
+            let key = ((pt.x + 0.0).to_bits(), (pt.y + 0.0).to_bits());
+            let first = *first_copy.entry(key).or_insert(i);
+            if first == i {
+                unique.push(i);
+            }
+            canonical.push(first);
+        }
+        if unique.len() < 3 {
+            return Err("fewer than 3 distinct points".to_string());
+        }
+        let (a, mut b) = (unique[0], unique[1]);
+        let Some(mut c) = unique[2..]
+            .iter()
+            .copied()
+            .find(|&c| orient2d(points[a], points[b], points[c]) != 0.0)
+        else {
+            return Err("all points are collinear".to_string());
+        };
+        if orient2d(points[a], points[b], points[c]) < 0.0 {
+            std::mem::swap(&mut b, &mut c);
+        }
+        let mut builder = Builder {
+            points,
+            tris: vec![[a, b, c], [c, b, GHOST], [a, c, GHOST], [b, a, GHOST]],
+            adj: vec![[1, 2, 3], [3, 2, 0], [1, 3, 0], [2, 1, 0]],
+            alive: vec![true; 4],
+            free: Vec::new(),
+            vertex_tri: vec![0; points.len()],
+            constraints: HashSet::new(),
+            last: 0,
+        };
+        for &p in &unique {
+            if p != a && p != b && p != c {
+                builder.insert(p);
+            }
+        }
+        Ok((builder, canonical))
+    }
+    fn pt(&self, index: usize) -> Vector2 {
+        self.points[index]
+    }
+    fn is_ghost(&self, t: usize) -> bool {
+        self.tris[t].contains(&GHOST)
+    }
+    /// The edge opposite corner `i` of triangle `t`, in the triangle's direction
+    fn edge(&self, t: usize, i: usize) -> (usize, usize) {
+        (self.tris[t][(i + 1) % 3], self.tris[t][(i + 2) % 3])
+    }
+    /// The corner of triangle `t` opposite its edge from `u` to `v`
+    fn opposite(&self, t: usize, u: usize, v: usize) -> usize {
+        (0..3)
+            .find(|&i| self.edge(t, i) == (u, v))
+            .expect("triangles are not linked")
+    }
+    /// Whether point `p` breaks the Delaunay condition of triangle `t`. For a ghost triangle that means
+    /// `p` can see its hull edge from outside, or lies on it.
+    fn conflicts(&self, t: usize, p: usize) -> bool {
+        let p = self.pt(p);
+        if let Some(i) = self.tris[t].iter().position(|&v| v == GHOST) {
+            let (u, v) = self.edge(t, i);
+            let (u, v) = (self.pt(u), self.pt(v));
+            let side = orient2d(u, v, p);
+            side > 0.0 || (side == 0.0 && (u - p).dot(&(v - p)) < 0.0)
+        } else {
+            let [a, b, c] = self.tris[t];
+            incircle(self.pt(a), self.pt(b), self.pt(c), p) > 0.0
+        }
+    }
+    /// Finds a triangle that point `p` conflicts with by walking towards it
+    fn locate(&self, p: usize) -> usize {
+        let pt = self.pt(p);
+        let mut t = self.last;
+        for _ in 0..self.tris.len() {
+            if self.is_ghost(t) {
+                break;
+            }
+            let Some(i) = (0..3).find(|&i| {
+                let (u, v) = self.edge(t, i);
+                orient2d(self.pt(u), self.pt(v), pt) < 0.0
+            }) else {
+                return t;
+            };
+            t = self.adj[t][i];
+        }
+        if self.alive[t] && self.conflicts(t, p) {
+            return t;
+        }
+        (0..self.tris.len())
+            .find(|&t| self.alive[t] && self.conflicts(t, p))
+            .expect("every point conflicts with some triangle")
+    }
+    fn add_triangle(&mut self, corners: [usize; 3]) -> usize {
+        match self.free.pop() {
+            Some(t) => {
+                self.tris[t] = corners;
+                self.alive[t] = true;
+                t
+            }
+            None => {
+                self.tris.push(corners);
+                self.adj.push([0; 3]);
+                self.alive.push(true);
+                self.tris.len() - 1
+            }
+        }
+    }
+    /// Points the edge from `u` to `v` of triangle `t` at `neighbor`
+    fn link(&mut self, t: usize, u: usize, v: usize, neighbor: usize) {
+        let i = self.opposite(t, u, v);
+        self.adj[t][i] = neighbor;
+    }
+    /// Adds a point by removing every triangle it conflicts with and joining it to the boundary of the
+    /// hole they leave
+    fn insert(&mut self, p: usize) {
+        let start = self.locate(p);
+        let mut cavity = HashSet::from([start]);
+        let mut stack = vec![start];
+        let mut boundary = Vec::new();
+        while let Some(t) = stack.pop() {
+            for i in 0..3 {
+                let n = self.adj[t][i];
+                if cavity.contains(&n) {
+                    continue;
+                }
+                if self.conflicts(n, p) {
+                    cavity.insert(n);
+                    stack.push(n);
+                } else {
+                    let (u, v) = self.edge(t, i);
+                    boundary.push((u, v, n));
+                }
+            }
+        }
+        for &t in &cavity {
+            self.alive[t] = false;
+            self.free.push(t);
+        }
+        let mut by_start = HashMap::new();
+        let mut by_end = HashMap::new();
+        let mut created = Vec::new();
+        for &(u, v, outside) in &boundary {
+            let t = self.add_triangle([u, v, p]);
+            self.adj[t][2] = outside;
+            self.link(outside, v, u, t);
+            by_start.insert(u, t);
+            by_end.insert(v, t);
+            created.push(t);
+        }
+        for &t in &created {
+            let [u, v, _] = self.tris[t];
+            self.adj[t][0] = by_start[&v];
+            self.adj[t][1] = by_end[&u];
+            for corner in self.tris[t] {
+                if corner != GHOST {
+                    self.vertex_tri[corner] = t;
+                }
+            }
+            if !self.is_ghost(t) {
+                self.last = t;
+            }
+        }
+    }
+    /// Replaces the edge opposite corner `i` of triangle `t` with the other diagonal of the quadrilateral
+    /// formed with its neighbour
+    fn flip(&mut self, t: usize, i: usize) {
+        let a = self.tris[t][i];
+        let (b, c) = self.edge(t, i);
+        let n = self.adj[t][i];
+        let j = self.opposite(n, c, b);
+        let d = self.tris[n][j];
+        let across_ca = self.adj[t][(i + 1) % 3];
+        let across_ab = self.adj[t][(i + 2) % 3];
+        let across_bd = self.adj[n][(j + 1) % 3];
+        let across_dc = self.adj[n][(j + 2) % 3];
+        self.tris[t] = [a, b, d];
+        self.adj[t] = [across_bd, n, across_ab];
+        self.tris[n] = [d, c, a];
+        self.adj[n] = [across_ca, t, across_dc];
+        self.link(across_bd, d, b, t);
+        self.link(across_ca, a, c, n);
+        for (corner, tri) in [(a, t), (b, t), (d, t), (c, n)] {
+            if corner != GHOST {
+                self.vertex_tri[corner] = tri;
+            }
+        }
+    }
+    /// The triangle with an edge from `u` to `v`, and the corner opposite it
+    fn find_edge(&self, u: usize, v: usize) -> Option<(usize, usize)> {
+        let start = self.vertex_tri[u];
+        let mut t = start;
+        for _ in 0..self.tris.len() {
+            let k = self.tris[t].iter().position(|&x| x == u)?;
+            if self.tris[t][(k + 1) % 3] == v {
+                return Some((t, (k + 2) % 3));
+            }
+            t = self.adj[t][(k + 1) % 3];
+            if t == start {
+                break;
+            }
+        }
+        None
+    }
+    /// Whether the quadrilateral around the edge opposite corner `i` of triangle `t` is strictly convex,
+    /// returning the corners opposite the edge if so
+    fn flippable(&self, t: usize, i: usize) -> Option<(usize, usize)> {
+        let (u, v) = self.edge(t, i);
+        let n = self.adj[t][i];
+        let p = self.tris[t][i];
+        let q = self.tris[n][self.opposite(n, v, u)];
+        if p == GHOST || q == GHOST {
+            return None;
+        }
+        let side_u = orient2d(self.pt(p), self.pt(q), self.pt(u));
+        let side_v = orient2d(self.pt(p), self.pt(q), self.pt(v));
+        ((side_u > 0.0 && side_v < 0.0) || (side_u < 0.0 && side_v > 0.0)).then_some((p, q))
+    }
+    fn insert_constraints(
+        &mut self,
+        edges: &[(usize, usize)],
+        canonical: &[usize],
+    ) -> Result<(), String> {
+        for (k, &(a, b)) in edges.iter().enumerate() {
+            if a >= canonical.len() || b >= canonical.len() {
+                return Err(format!("edge {k} refers to a point that doesn't exist"));
+            }
+            let (mut a, b) = (canonical[a], canonical[b]);
+            if a == b {
+                return Err(format!("edge {k} joins a point to itself"));
+            }
+            while a != b {
+                a = self.insert_constraint(a, b)?;
+            }
+        }
+        Ok(())
+    }
+    /// Makes the segment from `a` towards `b` an edge, as far as the first point on it, which is returned
+    fn insert_constraint(&mut self, a: usize, b: usize) -> Result<usize, String> {
+        let (pa, pb) = (self.pt(a), self.pt(b));
+        let on_segment = |this: &Self, x: usize| {
+            orient2d(pa, pb, this.pt(x)) == 0.0 && (this.pt(x) - pa).dot(&(pb - pa)) > 0.0
+        };
+        // Turn around `a` to find the edge the segment leaves through, unless it follows an existing edge
+        let start = self.vertex_tri[a];
+        let mut t = start;
+        let mut exit = None;
+        for _ in 0..self.tris.len() {
+            let k = self.tris[t]
+                .iter()
+                .position(|&x| x == a)
+                .expect("vertex_tri is stale");
+            let (x, y) = self.edge(t, k);
+            for end in [x, y] {
+                if end != GHOST && (end == b || on_segment(self, end)) {
+                    self.constraints.insert(edge_key(a, end));
+                    return Ok(end);
+                }
+            }
+            if x != GHOST
+                && y != GHOST
+                && orient2d(pa, pb, self.pt(x)) < 0.0
+                && orient2d(pa, pb, self.pt(y)) > 0.0
+            {
+                exit = Some((t, k));
+                break;
+            }
+            t = self.adj[t][(k + 1) % 3];
+            if t == start {
+                break;
+            }
+        }
+        let (mut t, mut i) = exit.ok_or("the triangulation around a constrained edge is broken")?;
+        // Walk along the segment collecting the edges it crosses, each with its right end first
+        let mut crossings = VecDeque::new();
+        let end = loop {
+            let (x, y) = self.edge(t, i);
+            if self.constraints.contains(&edge_key(x, y)) {
+                return Err(format!("constrained edges {a}-{b} and {x}-{y} cross"));
+            }
+            crossings.push_back((x, y));
+            let n = self.adj[t][i];
+            let j = self.opposite(n, y, x);
+            let w = self.tris[n][j];
+            if w == b || on_segment(self, w) {
+                break w;
+            }
+            i = if orient2d(pa, pb, self.pt(w)) < 0.0 {
+                (j + 2) % 3
+            } else {
+                (j + 1) % 3
+            };
+            t = n;
+        };
+        let pe = self.pt(end);
+        let crosses = |this: &Self, p: usize, q: usize| {
+            let (sp, sq) = (orient2d(pa, pe, this.pt(p)), orient2d(pa, pe, this.pt(q)));
+            (sp > 0.0 && sq < 0.0) || (sp < 0.0 && sq > 0.0)
+        };
+        // Flip crossing edges until none are left, skipping those whose quadrilateral isn't convex yet
+        let mut created = Vec::new();
+        let mut stalled = 0;
+        while let Some((u, v)) = crossings.pop_front() {
+            let (t, i) = self.find_edge(u, v).ok_or("lost track of an edge")?;
+            match self.flippable(t, i) {
+                Some((p, q)) => {
+                    self.flip(t, i);
+                    stalled = 0;
+                    if crosses(self, p, q) {
+                        crossings.push_back((p, q));
+                    } else {
+                        created.push((p, q));
+                    }
+                }
+                None => {
+                    crossings.push_back((u, v));
+                    stalled += 1;
+                    if stalled > crossings.len() {
+                        return Err(format!("could not insert constrained edge {a}-{end}"));
+                    }
+                }
+            }
+        }
+        self.constraints.insert(edge_key(a, end));
+        // Flip the new edges back towards Delaunay wherever the constraint allows
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for edge in created.iter_mut() {
+                let (u, v) = *edge;
+                if edge_key(u, v) == edge_key(a, end) {
+                    continue;
+                }
+                let Some((t, i)) = self.find_edge(u, v) else {
+                    continue;
+                };
+                let n = self.adj[t][i];
+                let q = self.tris[n][self.opposite(n, v, u)];
+                let p = self.tris[t][i];
+                if q != GHOST
+                    && incircle(self.pt(p), self.pt(u), self.pt(v), self.pt(q)) > 0.0
+                    && self.flippable(t, i).is_some()
+                {
+                    self.flip(t, i);
+                    *edge = (p, q);
+                    changed = true;
+                }
+            }
+        }
+        Ok(end)
+    }
+    /// Marks the triangles enclosed by an odd number of constrained edges, found by flooding in from
+    /// the ghost triangles and counting the constrained edges crossed
+    fn inside_constraints(&self) -> Vec<bool> {
+        let mut depth: Vec<Option<usize>> = vec![None; self.tris.len()];
+        let mut queue = VecDeque::new();
+        for (t, d) in depth.iter_mut().enumerate() {
+            if self.alive[t] && self.is_ghost(t) {
+                *d = Some(0);
+                queue.push_back(t);
+            }
+        }
+        // Crossing an unconstrained edge costs nothing, so those neighbours jump the queue
+        while let Some(t) = queue.pop_front() {
+            let d = depth[t].unwrap();
+            for i in 0..3 {
+                let n = self.adj[t][i];
+                let (u, v) = self.edge(t, i);
+                let step = usize::from(self.constraints.contains(&edge_key(u, v)));
+                if depth[n].is_none_or(|old| old > d + step) {
+                    depth[n] = Some(d + step);
+                    if step == 0 {
+                        queue.push_front(n);
+                    } else {
+                        queue.push_back(n);
+                    }
+                }
+            }
+        }
+        depth
+            .iter()
+            .map(|d| d.is_some_and(|d| d % 2 == 1))
+            .collect()
+    }
+    /// Drops the ghost and removed triangles, and those `keep` rejects, renumbering the rest
+    fn finish(self, keep: impl Fn(usize) -> bool) -> Triangulation {
+        let mut renumber = vec![None; self.tris.len()];
+        let mut triangles = Vec::new();
+        for (t, number) in renumber.iter_mut().enumerate() {
+            if self.alive[t] && !self.is_ghost(t) && keep(t) {
+                *number = Some(triangles.len());
+                let [a, b, c] = self.tris[t];
+                triangles.push((a, b, c));
+            }
+        }
+        let neighbors = (0..self.tris.len())
+            .filter(|&t| renumber[t].is_some())
+            .map(|t| self.adj[t].map(|n| renumber[n]))
+            .collect();
+        Triangulation {
+            points: self.points.to_vec(),
+            triangles,
+            neighbors,
+            constraints: self.constraints,
+        }
+    }
+}
@@ -1,26 +1,110 @@
+use crate::approx::{Tolerance, impl_approx_eq};
+use crate::clipping::{BooleanOp, boolean_op};
+use crate::line::LineSegment2;
+use crate::offset::{JoinStyle, offset};
+use crate::predicates::orient2d;
 use crate::vectors::Vector2;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-struct Triangle {
-    a: Vector2,
-    b: Vector2,
-    c: Vector2,
+pub struct Triangle {
+    pub a: Vector2,
+    pub b: Vector2,
+    pub c: Vector2,
 }
 
+impl_approx_eq!(Triangle, a, b, c);
+
 impl Triangle {
     pub fn new(a: Vector2, b: Vector2, c: Vector2) -> Self {
         Self { a, b, c }
     }
+    /// The signed area of the triangle, positive if `a`, `b`, `c` are counterclockwise
     pub fn area(&self) -> f64 {
         (self.b - self.a).cross(&(self.c - self.b)) / 2.0
     }
+    /// Determines if a point is inside or on the edge of the triangle, whichever way it is wound
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Triangle;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let tri = Triangle::new(Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0), Vector2::new(4.0, 1.0));
+    /// assert!(tri.point_intersects(Vector2::new(3.0, 0.5)));
+    /// assert!(tri.point_intersects(Vector2::new(2.0, 0.0)));
+    /// assert!(!tri.point_intersects(Vector2::new(1.0, 0.5)));
+    /// ```
     pub fn point_intersects(&self, pt: Vector2) -> bool {
-        let adj_pt = pt - self.a;
-        let x = (self.b - self.a) / (self.b - self.a).dot(&(self.b - self.a));
-        let y = (self.c - self.a) / (self.c - self.a).dot(&(self.c - self.a));
-        let lcs_pt = Vector2::new(x.dot(&adj_pt), y.dot(&adj_pt));
-        (lcs_pt.x >= 0.0) && (lcs_pt.y >= 0.0) && (lcs_pt.x + lcs_pt.y <= 1.0)
+        let ab = orient2d(self.a, self.b, pt);
+        let bc = orient2d(self.b, self.c, pt);
+        let ca = orient2d(self.c, self.a, pt);
+        (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+    }
+}
+
+/// Ear clips the corners of a counterclockwise polygon into counterclockwise triangles, see
+/// [Polygon::triangulate_indices]
+pub(crate) fn ear_clip(pts: &[Vector2]) -> Result<Vec<(usize, usize, usize)>, String> {
+    let mut remaining: Vec<usize> = (0..pts.len()).collect();
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corners = |k: usize| {
+            (
+                remaining[(k + n - 1) % n],
+                remaining[k],
+                remaining[(k + 1) % n],
+            )
+        };
+        let turn = |k: usize| {
+            let (prev, cur, next) = corners(k);
+            orient2d(pts[prev], pts[cur], pts[next])
+        };
+        let is_ear = |k: usize| {
+            let (prev, cur, next) = corners(k);
+            let tri = Triangle::new(pts[prev], pts[cur], pts[next]);
+            turn(k) > 0.0
+                && remaining.iter().all(|other| {
+                    // Points that coincide with a corner (like the two ends of a bridge to a hole)
+                    // can't block the ear
+                    let p = pts[*other];
+                    p == tri.a || p == tri.b || p == tri.c || !tri.point_intersects(p)
+                })
+        };
+        // A flat corner only adds a triangle with no area, so it is clipped once no proper ear is left
+        let k = (0..n)
+            .find(|k| is_ear(*k))
+            .or_else(|| (0..n).find(|k| turn(*k) == 0.0))
+            .ok_or_else(|| {
+                format!(
+                    "None of the corners {remaining:?} can be clipped, so the polygon is not simple"
+                )
+            })?;
+        triangles.push(corners(k));
+        remaining.remove(k);
     }
+    if remaining.len() == 3 {
+        triangles.push((remaining[0], remaining[1], remaining[2]));
+    }
+    Ok(triangles)
+}
+
+/// The average of the corners of the rings, or the origin if there are none
+fn corner_mean<'a>(rings: impl Iterator<Item = &'a Polygon>) -> Vector2 {
+    let (sum, count) = rings
+        .flat_map(|ring| ring.points.iter())
+        .fold((Vector2::zero(), 0), |(sum, count), p| {
+            (sum + *p, count + 1)
+        });
+    sum / count.max(1) as f64
+}
+
+/// Where a point is relative to a region, like [crate::simple_plane::Intersection] for containment tests
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Containment {
+    Inside,
+    OnBoundary,
+    Outside,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -28,16 +112,676 @@ pub struct Polygon {
     points: Vec<Vector2>,
 }
 
+impl_approx_eq!(Polygon, points);
+
 impl Polygon {
     // TODO: add a second constructor for dxf-style inputs (points, order of pts)
+    /// Creates a polygon from its corners, keeping them in the order given, see
+    /// [Polygon::new_counterclockwise] to normalize the winding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let clockwise = vec![Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0), Vector2::new(1.0, 0.0)];
+    /// let poly = Polygon::new(clockwise.clone());
+    /// assert_eq!(poly.get_points(), &clockwise[..]);
+    /// assert_eq!(poly.signed_area(), -0.5);
+    /// assert_eq!(poly.area(), 0.5);
+    /// assert!(!poly.is_counterclockwise());
+    /// ```
     pub fn new(points: Vec<Vector2>) -> Self {
-        Self { points } // TODO: add safeguards to make sure it is CCW
+        Self { points }
+    }
+    /// Creates a polygon from its corners, reversing them if they are clockwise so that the polygon is
+    /// counterclockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let clockwise = vec![Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0), Vector2::new(1.0, 0.0)];
+    /// assert_eq!(Polygon::signed_area_of(&clockwise), -0.5);
+    /// let poly = Polygon::new_counterclockwise(clockwise);
+    /// assert_eq!(poly.signed_area(), 0.5);
+    /// assert!(poly.is_counterclockwise());
+    /// assert_eq!(poly.get_points()[0], Vector2::new(1.0, 0.0));
+    /// ```
+    pub fn new_counterclockwise(mut points: Vec<Vector2>) -> Self {
+        if Self::signed_area_of(&points) < 0.0 {
+            points.reverse();
+        }
+        Self { points }
+    }
+    /// Creates an axis-aligned rectangle from its lower left and upper right corners.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let rect = Polygon::rectangle(Vector2::new(1.0, 2.0), Vector2::new(4.0, 4.0));
+    /// assert_eq!(rect.get_points().len(), 4);
+    /// assert_eq!(rect.area(), 6.0);
+    /// ```
+    pub fn rectangle(min: Vector2, max: Vector2) -> Self {
+        Self::new(vec![
+            min,
+            Vector2::new(max.x, min.y),
+            max,
+            Vector2::new(min.x, max.y),
+        ])
     }
+    /// Computes the area enclosed by a ring of points with the shoelace formula. It is positive if the
+    /// points are counterclockwise and negative if they are clockwise.
+    pub fn signed_area_of(points: &[Vector2]) -> f64 {
+        let mut sum = 0.0;
+        for i in 0..points.len() {
+            sum += points[i].cross(&points[(i + 1) % points.len()]);
+        }
+        sum / 2.0
+    }
+    pub fn signed_area(&self) -> f64 {
+        Self::signed_area_of(&self.points)
+    }
+    /// Determines if the corners run counterclockwise, which [Polygon::new] doesn't enforce
+    pub fn is_counterclockwise(&self) -> bool {
+        self.signed_area() >= 0.0
+    }
+    /// Computes the convex hull of a set of points with Andrew's monotone chain algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The points to enclose
+    /// * `keep_collinear`: Whether points lying on the hull between two corners are kept
+    ///
+    /// returns: Polygon, counterclockwise, starting from the lowest-leftmost point. If all the points are
+    /// collinear the polygon is degenerate, going from one end of the line to the other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let points = vec![
+    ///     Vector2::new(2.0, 2.0),
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(1.0, 0.0),
+    ///     Vector2::new(2.0, 0.0),
+    ///     Vector2::new(1.0, 1.0),
+    ///     Vector2::new(0.0, 2.0),
+    ///     Vector2::new(0.0, 0.0),
+    /// ];
+    /// let hull = Polygon::convex_hull(&points, false);
+    /// assert_eq!(hull.get_points(), &[Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0), Vector2::new(2.0, 2.0), Vector2::new(0.0, 2.0)]);
+    /// assert_eq!(hull.area(), 4.0);
+    /// assert_eq!(Polygon::convex_hull(&points, true).get_points().len(), 5);
+    /// ```
+    pub fn convex_hull(points: &[Vector2], keep_collinear: bool) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        sorted.dedup_by(|a, b| a.x == b.x && a.y == b.y);
+        if sorted.len() < 3 {
+            return Self::new(sorted);
+        }
+        // Pops the last point while it doesn't make a left turn (or, keeping collinear points, a right turn)
+        let turns_wrong = |chain: &[Vector2], pt: Vector2| {
+            let n = chain.len();
+            let turn = orient2d(chain[n - 2], chain[n - 1], pt);
+            if keep_collinear {
+                turn < 0.0
+            } else {
+                turn <= 0.0
+            }
+        };
+        let mut lower: Vec<Vector2> = Vec::new();
+        for pt in &sorted {
+            while lower.len() >= 2 && turns_wrong(&lower, *pt) {
+                lower.pop();
+            }
+            lower.push(*pt);
+        }
+        let mut upper: Vec<Vector2> = Vec::new();
+        for pt in sorted.iter().rev() {
+            while upper.len() >= 2 && turns_wrong(&upper, *pt) {
+                upper.pop();
+            }
+            upper.push(*pt);
+        }
+        // With collinear points kept, a straight line makes both chains the whole input
+        if lower.len() == sorted.len() && upper.len() == sorted.len() && keep_collinear {
+            return Self::new(vec![sorted[0], sorted[sorted.len() - 1]]);
+        }
+        lower.pop();
+        upper.pop();
+        lower.append(&mut upper);
+        Self::new(lower)
+    }
+    pub fn get_points(&self) -> &[Vector2] {
+        &self.points
+    }
+    /// The area enclosed by the polygon, positive whichever way it is wound
     pub fn area(&self) -> f64 {
         let mut sum = 0.0;
-        for i in 1..self.points.len() - 1 {
+        for i in 1..self.points.len().saturating_sub(1) {
             sum += Triangle::new(self.points[0], self.points[i], self.points[i + 1]).area();
         }
-        sum
+        sum.abs()
+    }
+    /// Counts how many times the boundary winds counterclockwise around a point. Points on the boundary
+    /// give an unspecified result; use [Polygon::contains] to tell them apart.
+    pub fn winding_number(&self, pt: Vector2) -> i32 {
+        let mut winding = 0;
+        for i in 0..self.points.len() {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % self.points.len()];
+            let side = orient2d(a, b, pt);
+            if a.y <= pt.y {
+                // An upward crossing with the point on its left
+                if b.y > pt.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= pt.y && side < 0.0 {
+                // A downward crossing with the point on its right
+                winding -= 1;
+            }
+        }
+        winding
+    }
+    /// Determines whether a point is inside the polygon, on its boundary, or outside it, using the
+    /// nonzero winding rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `pt`: The point to test
+    ///
+    /// returns: Containment
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::{Containment, Polygon};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// // An L shape
+    /// let poly = Polygon::new(vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(2.0, 0.0),
+    ///     Vector2::new(2.0, 1.0),
+    ///     Vector2::new(1.0, 1.0),
+    ///     Vector2::new(1.0, 2.0),
+    ///     Vector2::new(0.0, 2.0),
+    /// ]);
+    /// assert_eq!(poly.contains(Vector2::new(0.5, 1.5)), Containment::Inside);
+    /// assert_eq!(poly.contains(Vector2::new(1.5, 1.5)), Containment::Outside);
+    /// assert_eq!(poly.contains(Vector2::new(1.5, 1.0)), Containment::OnBoundary);
+    /// assert_eq!(poly.contains(Vector2::new(2.0, 0.0)), Containment::OnBoundary);
+    /// ```
+    pub fn contains(&self, pt: Vector2) -> Containment {
+        self.contains_within(pt, Tolerance::EXACT)
+    }
+    /// Like [Polygon::contains], but counting points within the tolerance of an edge as on the
+    /// boundary, see [LineSegment2::point_intersects_within]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::gon::{Containment, Polygon};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let square = Polygon::new(vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(1.0, 0.0),
+    ///     Vector2::new(1.0, 1.0),
+    ///     Vector2::new(0.0, 1.0),
+    /// ]);
+    /// let near_edge = Vector2::new(0.5, 1E-10);
+    /// assert_eq!(square.contains(near_edge), Containment::Inside);
+    /// assert_eq!(square.contains_within(near_edge, Tolerance::absolute(1E-9)), Containment::OnBoundary);
+    /// ```
+    pub fn contains_within(&self, pt: Vector2, tolerance: Tolerance) -> Containment {
+        if self
+            .edges()
+            .iter()
+            .any(|e| e.point_intersects_within(pt, tolerance))
+        {
+            return Containment::OnBoundary;
+        }
+        if self.winding_number(pt) != 0 {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+    /// Splits a simple (possibly concave) polygon into triangles by ear clipping. Collinear corners
+    /// are clipped as flat triangles once no proper ear is left, so that every corner is used.
+    ///
+    /// returns: Result<Vec<(usize, usize, usize)>, String>, counterclockwise triples of indices into the
+    /// polygon's points, or an error if the boundary crosses itself so that no corner can be clipped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// // An arrowhead, where fanning from the first corner would cover area outside the polygon
+    /// let poly = Polygon::new(vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(2.0, 1.0),
+    ///     Vector2::new(0.0, 2.0),
+    ///     Vector2::new(1.0, 1.0),
+    /// ]);
+    /// let tris = poly.triangulate_indices().unwrap();
+    /// assert_eq!(tris.len(), 2);
+    /// assert!(!tris.contains(&(0, 1, 2)));
+    /// let total: f64 = poly.triangulate().unwrap().iter().map(|t| t.area()).sum();
+    /// assert_eq!(total, poly.area());
+    ///
+    /// // This boundary crosses itself, and every corner that turns left has another corner inside it
+    /// let tangle = Polygon::new(vec![
+    ///     Vector2::new(0.0, 2.0),
+    ///     Vector2::new(0.0, 4.0),
+    ///     Vector2::new(3.0, 0.0),
+    ///     Vector2::new(2.0, 4.0),
+    ///     Vector2::new(1.0, 2.0),
+    /// ]);
+    /// assert!(tangle.triangulate_indices().is_err());
+    /// ```
+    pub fn triangulate_indices(&self) -> Result<Vec<(usize, usize, usize)>, String> {
+        if self.is_counterclockwise() {
+            return ear_clip(&self.points);
+        }
+        // Ear clipping needs counterclockwise corners, so clip them in reverse and number them back
+        let n = self.points.len();
+        let reversed: Vec<Vector2> = self.points.iter().rev().copied().collect();
+        Ok(ear_clip(&reversed)?
+            .into_iter()
+            .map(|(a, b, c)| (n - 1 - a, n - 1 - b, n - 1 - c))
+            .collect())
+    }
+    /// Splits a simple (possibly concave) polygon into counterclockwise triangles by ear clipping, see
+    /// [Polygon::triangulate_indices]
+    pub fn triangulate(&self) -> Result<Vec<Triangle>, String> {
+        Ok(self
+            .triangulate_indices()?
+            .iter()
+            .map(|t| Triangle::new(self.points[t.0], self.points[t.1], self.points[t.2]))
+            .collect())
+    }
+    /// The edges of the polygon, with edge `i` running from point `i` to point `i + 1`
+    pub fn edges(&self) -> Vec<LineSegment2> {
+        (0..self.points.len())
+            .map(|i| LineSegment2::new(self.points[i], self.points[(i + 1) % self.points.len()]))
+            .collect()
+    }
+    pub fn perimeter(&self) -> f64 {
+        self.edges().iter().map(|e| e.length()).sum()
+    }
+    /// The centre of mass of the enclosed area, or the average of the corners if it has no area
+    pub fn centroid(&self) -> Vector2 {
+        let area = self.signed_area();
+        if area == 0.0 {
+            let sum = self.points.iter().fold(Vector2::zero(), |sum, p| sum + *p);
+            return sum / self.points.len().max(1) as f64;
+        }
+        let mut sum = Vector2::zero();
+        for i in 0..self.points.len() {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % self.points.len()];
+            sum += (a + b) * a.cross(&b);
+        }
+        sum / (6.0 * area)
+    }
+    /// Finds two edges that touch anywhere other than the corner between consecutive edges
+    fn self_intersection(&self) -> Option<(usize, usize)> {
+        let edges = self.edges();
+        let n = edges.len();
+        for i in 0..n {
+            for j in i + 1..n {
+                let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+                let touches = if !adjacent {
+                    edges[i].intersects(&edges[j])
+                } else if j == i + 1 {
+                    // Consecutive edges share a corner, so they only intersect if they fold back
+                    edges[i].point_intersects(edges[j].b) || edges[j].point_intersects(edges[i].a)
+                } else {
+                    edges[j].point_intersects(edges[i].b) || edges[i].point_intersects(edges[j].a)
+                };
+                if touches || edges[i].length() == 0.0 {
+                    return Some((i, j));
+                }
+            }
+        }
+        None
+    }
+    /// Determines if the polygon has at least 3 corners and its boundary never touches itself
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::Polygon;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let square = vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(0.0, 1.0)];
+    /// assert!(Polygon::new(square.clone()).is_simple());
+    /// let bowtie = vec![square[0], square[1], square[3], square[2]];
+    /// assert!(!Polygon::new(bowtie).is_simple());
+    /// ```
+    pub fn is_simple(&self) -> bool {
+        self.points.len() >= 3 && self.self_intersection().is_none()
+    }
+    /// Grows (or, with a negative distance, shrinks) the polygon by a distance, see [offset]. Shrinking
+    /// can split it into several pieces or make it vanish, and growing a concave polygon can enclose
+    /// holes, so the result is a [MultiPolygon].
+    pub fn offset(&self, distance: f64, join: JoinStyle) -> MultiPolygon {
+        offset(&self.clone().into(), distance, join)
+    }
+    /// Checks that the ring is usable as part of a region, naming it `name` in the error
+    fn validate_ring(&self, name: &str) -> Result<(), String> {
+        if self.points.len() < 3 {
+            return Err(format!("{name} has fewer than 3 corners"));
+        }
+        if let Some((i, j)) = self.self_intersection() {
+            return Err(format!("{name} intersects itself at edges {i} and {j}"));
+        }
+        Ok(())
+    }
+    /// Drops a final point that repeats the first, since rings are implicitly closed
+    fn without_closing_point(mut self) -> Self {
+        if self.points.len() > 1 && self.points[0] == self.points[self.points.len() - 1] {
+            self.points.pop();
+        }
+        self
+    }
+    fn touches_boundary_of(&self, other: &Polygon) -> bool {
+        let theirs = other.edges();
+        self.edges()
+            .iter()
+            .any(|e| theirs.iter().any(|o| e.intersects(o)))
+    }
+}
+
+/// A polygon with polygonal holes cut out of it
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::gon::{Containment, Polygon, PolygonWithHoles};
+/// use YetAnotherGeometryLibrary::vectors::Vector2;
+/// let outer = Polygon::rectangle(Vector2::new(0.0, 0.0), Vector2::new(10.0, 10.0));
+/// let hole = Polygon::rectangle(Vector2::new(1.0, 1.0), Vector2::new(3.0, 3.0));
+/// let field = PolygonWithHoles::new(outer.clone(), vec![hole]).unwrap();
+/// assert_eq!(field.area(), 96.0);
+/// assert_eq!(field.perimeter(), 48.0);
+/// assert_eq!(field.centroid(), Vector2::new(5.125, 5.125));
+/// assert_eq!(field.contains(Vector2::new(2.0, 2.0)), Containment::Outside);
+/// assert_eq!(field.contains(Vector2::new(3.0, 2.0)), Containment::OnBoundary);
+/// assert_eq!(field.contains(Vector2::new(5.0, 5.0)), Containment::Inside);
+/// // Holes must be inside the outer ring
+/// let overhanging = Polygon::rectangle(Vector2::new(9.0, 9.0), Vector2::new(11.0, 11.0));
+/// assert!(PolygonWithHoles::new(outer, vec![overhanging]).is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct PolygonWithHoles {
+    outer: Polygon,
+    holes: Vec<Polygon>,
+}
+
+impl_approx_eq!(PolygonWithHoles, outer, holes);
+
+impl PolygonWithHoles {
+    /// Creates a polygon with holes, checking that every ring is simple, that the holes are strictly
+    /// inside the outer ring, and that no two holes overlap or touch. All rings are stored
+    /// counterclockwise, and a final point repeating the first is dropped.
+    pub fn new(outer: Polygon, holes: Vec<Polygon>) -> Result<Self, String> {
+        let outer = outer.without_closing_point();
+        let holes: Vec<Polygon> = holes
+            .into_iter()
+            .map(|h| h.without_closing_point())
+            .collect();
+        outer.validate_ring("the outer ring")?;
+        for (i, hole) in holes.iter().enumerate() {
+            hole.validate_ring(format!("hole {i}").as_str())?;
+            if hole.touches_boundary_of(&outer)
+                || outer.contains(hole.points[0]) != Containment::Inside
+            {
+                return Err(format!("hole {i} is not strictly inside the outer ring"));
+            }
+            for (j, other) in holes.iter().enumerate().take(i) {
+                if hole.touches_boundary_of(other)
+                    || other.contains(hole.points[0]) != Containment::Outside
+                    || hole.contains(other.points[0]) != Containment::Outside
+                {
+                    return Err(format!("holes {j} and {i} overlap"));
+                }
+            }
+        }
+        Ok(Self::new_unchecked(outer, holes))
+    }
+    /// Creates a polygon with holes without checking that the rings are valid. The rings are still
+    /// stored counterclockwise.
+    pub fn new_unchecked(outer: Polygon, holes: Vec<Polygon>) -> Self {
+        Self {
+            outer: Polygon::new_counterclockwise(outer.points),
+            holes: holes
+                .into_iter()
+                .map(|h| Polygon::new_counterclockwise(h.points))
+                .collect(),
+        }
+    }
+    pub fn get_outer(&self) -> &Polygon {
+        &self.outer
+    }
+    pub fn get_holes(&self) -> &[Polygon] {
+        &self.holes
+    }
+    pub fn area(&self) -> f64 {
+        self.outer.area() - self.holes.iter().map(|h| h.area()).sum::<f64>()
+    }
+    /// The combined length of the outer ring and every hole
+    pub fn perimeter(&self) -> f64 {
+        self.outer.perimeter() + self.holes.iter().map(|h| h.perimeter()).sum::<f64>()
+    }
+    /// The centre of mass of the area left once the holes are cut out, or the average of the corners of
+    /// every ring if no area is left, like [Polygon::centroid]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::{Polygon, PolygonWithHoles};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let square = Polygon::new(vec![
+    ///     Vector2::new(0.0, 0.0),
+    ///     Vector2::new(2.0, 0.0),
+    ///     Vector2::new(2.0, 2.0),
+    ///     Vector2::new(0.0, 2.0),
+    /// ]);
+    /// let emptied = PolygonWithHoles::new_unchecked(square.clone(), vec![square]);
+    /// assert_eq!(emptied.area(), 0.0);
+    /// assert_eq!(emptied.centroid(), Vector2::new(1.0, 1.0));
+    /// ```
+    pub fn centroid(&self) -> Vector2 {
+        let area = self.area();
+        if area == 0.0 {
+            return corner_mean(self.rings());
+        }
+        let mut moment = self.outer.centroid() * self.outer.area();
+        for hole in &self.holes {
+            moment -= hole.centroid() * hole.area();
+        }
+        moment / area
+    }
+    /// Determines whether a point is inside the region, on the boundary of any ring, or outside it
+    /// (which includes being inside a hole)
+    pub fn contains(&self, pt: Vector2) -> Containment {
+        match self.outer.contains(pt) {
+            Containment::Inside => {
+                for hole in &self.holes {
+                    match hole.contains(pt) {
+                        Containment::Inside => return Containment::Outside,
+                        Containment::OnBoundary => return Containment::OnBoundary,
+                        Containment::Outside => {}
+                    }
+                }
+                Containment::Inside
+            }
+            other => other,
+        }
+    }
+    fn rings(&self) -> impl Iterator<Item = &Polygon> {
+        std::iter::once(&self.outer).chain(self.holes.iter())
+    }
+}
+
+impl From<Polygon> for PolygonWithHoles {
+    fn from(outer: Polygon) -> Self {
+        Self::new_unchecked(outer, Vec::new())
+    }
+}
+
+/// A collection of polygons (possibly with holes) that don't overlap
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiPolygon {
+    polygons: Vec<PolygonWithHoles>,
+}
+
+impl_approx_eq!(MultiPolygon, polygons);
+
+impl MultiPolygon {
+    /// Creates a multipolygon, checking that no two of its polygons overlap or touch. A polygon may sit
+    /// inside a hole of another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::{Containment, MultiPolygon, Polygon, PolygonWithHoles};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let outer = Polygon::rectangle(Vector2::new(0.0, 0.0), Vector2::new(6.0, 6.0));
+    /// let ring = PolygonWithHoles::new(outer, vec![Polygon::rectangle(Vector2::new(2.0, 2.0), Vector2::new(4.0, 4.0))]).unwrap();
+    /// let island = PolygonWithHoles::from(Polygon::rectangle(Vector2::new(2.5, 2.5), Vector2::new(3.5, 3.5)));
+    /// let both = MultiPolygon::new(vec![ring.clone(), island]).unwrap();
+    /// assert_eq!(both.area(), 33.0);
+    /// assert_eq!(both.contains(Vector2::new(3.0, 3.0)), Containment::Inside);
+    /// assert_eq!(both.contains(Vector2::new(2.2, 2.2)), Containment::Outside);
+    /// let overlapping = PolygonWithHoles::from(Polygon::rectangle(Vector2::new(5.0, 5.0), Vector2::new(7.0, 7.0)));
+    /// assert!(MultiPolygon::new(vec![ring, overlapping]).is_err());
+    /// ```
+    pub fn new(polygons: Vec<PolygonWithHoles>) -> Result<Self, String> {
+        for i in 0..polygons.len() {
+            for j in 0..i {
+                let touching = polygons[i]
+                    .rings()
+                    .any(|a| polygons[j].rings().any(|b| a.touches_boundary_of(b)));
+                if touching
+                    || polygons[j].contains(polygons[i].outer.points[0]) != Containment::Outside
+                    || polygons[i].contains(polygons[j].outer.points[0]) != Containment::Outside
+                {
+                    return Err(format!("polygons {j} and {i} overlap"));
+                }
+            }
+        }
+        Ok(Self { polygons })
+    }
+    /// Creates a multipolygon without checking that its polygons are disjoint
+    pub fn new_unchecked(polygons: Vec<PolygonWithHoles>) -> Self {
+        Self { polygons }
+    }
+    pub fn get_polygons(&self) -> &[PolygonWithHoles] {
+        &self.polygons
+    }
+    pub fn area(&self) -> f64 {
+        self.polygons.iter().map(|p| p.area()).sum()
+    }
+    pub fn perimeter(&self) -> f64 {
+        self.polygons.iter().map(|p| p.perimeter()).sum()
+    }
+    /// The centre of mass of all the polygons together, or the average of the corners of every ring if
+    /// they have no area (and the origin if there are no polygons)
+    pub fn centroid(&self) -> Vector2 {
+        let area = self.area();
+        if area == 0.0 {
+            return corner_mean(self.polygons.iter().flat_map(|p| p.rings()));
+        }
+        let moment = self
+            .polygons
+            .iter()
+            .fold(Vector2::zero(), |sum, p| sum + p.centroid() * p.area());
+        moment / area
+    }
+    /// Everything in either region, see [boolean_op]
+    pub fn union(&self, other: &MultiPolygon) -> MultiPolygon {
+        boolean_op(self, other, BooleanOp::Union)
+    }
+    /// Everything in both regions, see [boolean_op]
+    pub fn intersection(&self, other: &MultiPolygon) -> MultiPolygon {
+        boolean_op(self, other, BooleanOp::Intersection)
+    }
+    /// Everything in this region but not the other, see [boolean_op]
+    pub fn difference(&self, other: &MultiPolygon) -> MultiPolygon {
+        boolean_op(self, other, BooleanOp::Difference)
+    }
+    /// Everything in exactly one of the regions, see [boolean_op]
+    pub fn xor(&self, other: &MultiPolygon) -> MultiPolygon {
+        boolean_op(self, other, BooleanOp::Xor)
+    }
+    /// Merges any number of possibly overlapping regions into one. The regions are merged in pairs,
+    /// then the results in pairs and so on, so each [boolean_op] works on parts of similar size and the
+    /// large result isn't rebuilt for every region added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::gon::{MultiPolygon, Polygon};
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// // Three overlapping obstacles and one on its own
+    /// let obstacles = [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0), (10.0, 10.0)]
+    ///     .map(|(x, y)| Polygon::rectangle(Vector2::new(x, y), Vector2::new(x + 2.0, y + 2.0)));
+    /// let keep_out = MultiPolygon::union_all(obstacles);
+    /// assert_eq!(keep_out.get_polygons().len(), 2);
+    /// assert_eq!(keep_out.area(), 14.0);
+    /// ```
+    pub fn union_all<T: Into<MultiPolygon>>(regions: impl IntoIterator<Item = T>) -> MultiPolygon {
+        // Merging in pairs keeps the intermediate results small
+        let mut layer: Vec<MultiPolygon> = regions.into_iter().map(|r| r.into()).collect();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a.union(b),
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        layer
+            .pop()
+            .unwrap_or(MultiPolygon::new_unchecked(Vec::new()))
+    }
+    /// Grows (or, with a negative distance, shrinks) the region by a distance, see [offset]
+    pub fn offset(&self, distance: f64, join: JoinStyle) -> MultiPolygon {
+        offset(self, distance, join)
+    }
+    pub fn contains(&self, pt: Vector2) -> Containment {
+        let mut result = Containment::Outside;
+        for polygon in &self.polygons {
+            match polygon.contains(pt) {
+                Containment::Inside => return Containment::Inside,
+                Containment::OnBoundary => result = Containment::OnBoundary,
+                Containment::Outside => {}
+            }
+        }
+        result
+    }
+}
+
+impl From<PolygonWithHoles> for MultiPolygon {
+    fn from(polygon: PolygonWithHoles) -> Self {
+        Self::new_unchecked(vec![polygon])
+    }
+}
+
+impl From<Polygon> for MultiPolygon {
+    fn from(polygon: Polygon) -> Self {
+        Self::new_unchecked(vec![polygon.into()])
     }
 }
@@ -0,0 +1,544 @@
+use crate::hedron::Polyhedron;
+use crate::vectors::Vector3;
+use std::collections::HashMap;
+
+/// One side of an edge, running from `origin` to the origin of `next`, with `face` on its left
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct HalfEdge {
+    pub origin: usize,
+    /// The half-edge running the other way along the same edge, or `None` on a boundary
+    pub twin: Option<usize>,
+    pub next: usize,
+    pub prev: usize,
+    pub face: usize,
+    removed: bool,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Vertex {
+    pub position: Vector3,
+    /// An outgoing half-edge. On a boundary vertex this is always the outgoing boundary half-edge.
+    pub half_edge: Option<usize>,
+    removed: bool,
+}
+
+/// A triangle mesh with half-edge connectivity, so adjacency queries and local edits don't need to scan
+/// every face. Vertices, half-edges and faces are referred to by index; edits mark elements as removed
+/// rather than shifting indices, and [HalfEdgeMesh::to_polyhedron] compacts them again.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::half_edge::HalfEdgeMesh;
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// let mesh = HalfEdgeMesh::from_polyhedron(&Polyhedron::cube()).unwrap();
+/// let mut ring: Vec<usize> = mesh.vertex_neighbors(0).collect();
+/// ring.sort();
+/// assert_eq!(ring, vec![1, 2, 3]);
+/// assert_eq!(mesh.face_neighbors(0).count(), 3);
+/// assert!(mesh.boundary_loops().is_empty());
+/// assert_eq!(mesh.to_polyhedron(), Polyhedron::cube());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct HalfEdgeMesh {
+    vertices: Vec<Vertex>,
+    half_edges: Vec<HalfEdge>,
+    /// One half-edge of each face, or `None` once the face is removed
+    faces: Vec<Option<usize>>,
+}
+
+impl HalfEdgeMesh {
+    /// Builds the connectivity of a polyhedron. The faces must be consistently oriented, every edge may
+    /// be shared by at most two faces and the faces around each point must form a single fan, but the
+    /// surface does not need to be closed.
+    pub fn from_polyhedron(hedron: &Polyhedron) -> Result<Self, String> {
+        let points = hedron.get_points();
+        let mut vertices: Vec<Vertex> = points
+            .iter()
+            .map(|p| Vertex {
+                position: *p,
+                half_edge: None,
+                removed: false,
+            })
+            .collect();
+        let mut half_edges = Vec::new();
+        let mut faces = Vec::new();
+        let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+        for (i, f) in hedron.get_face_indices().iter().enumerate() {
+            let corners = [f.0, f.1, f.2];
+            if corners.iter().any(|c| *c >= points.len()) {
+                return Err(format!("face {i} references a point that does not exist"));
+            }
+            if f.0 == f.1 || f.1 == f.2 || f.2 == f.0 {
+                return Err(format!("face {i} uses the same point more than once"));
+            }
+            let first = half_edges.len();
+            for k in 0..3 {
+                let edge = (corners[k], corners[(k + 1) % 3]);
+                if directed.insert(edge, first + k).is_some() {
+                    return Err(format!(
+                        "edge ({}, {}) is traversed in the same direction by more than one face",
+                        edge.0, edge.1
+                    ));
+                }
+                half_edges.push(HalfEdge {
+                    origin: corners[k],
+                    twin: None,
+                    next: first + (k + 1) % 3,
+                    prev: first + (k + 2) % 3,
+                    face: i,
+                    removed: false,
+                });
+                vertices[corners[k]].half_edge = Some(first + k);
+            }
+            faces.push(Some(first));
+        }
+        for (&(a, b), &h) in &directed {
+            half_edges[h].twin = directed.get(&(b, a)).copied();
+        }
+        let mut mesh = Self {
+            vertices,
+            half_edges,
+            faces,
+        };
+        let mut outgoing = vec![0; mesh.vertices.len()];
+        for edge in &mesh.half_edges {
+            outgoing[edge.origin] += 1;
+        }
+        for (v, count) in outgoing.into_iter().enumerate() {
+            if let Some(h) = mesh.vertices[v].half_edge {
+                mesh.vertices[v].half_edge = Some(mesh.boundary_start(h)?);
+                // Edits only keep fans intact if each vertex starts with a single one
+                if mesh.outgoing_half_edges(v).count() != count {
+                    return Err(format!(
+                        "the faces around point {v} do not form a single fan"
+                    ));
+                }
+            }
+        }
+        Ok(mesh)
+    }
+    /// Converts back to a polyhedron, dropping removed vertices and faces
+    pub fn to_polyhedron(&self) -> Polyhedron {
+        let mut new_index = vec![0; self.vertices.len()];
+        let mut points = Vec::new();
+        for (i, v) in self.vertices.iter().enumerate() {
+            if !v.removed {
+                new_index[i] = points.len();
+                points.push(v.position);
+            }
+        }
+        let faces = self
+            .faces()
+            .filter_map(|f| self.face_vertices(f))
+            .map(|[a, b, c]| (new_index[a], new_index[b], new_index[c]))
+            .collect();
+        Polyhedron::new_unchecked(points, faces)
+    }
+    /// Rotates backwards around the origin of `h` until hitting a boundary, so that iterating forwards
+    /// from the result visits every face around the vertex
+    fn boundary_start(&self, h: usize) -> Result<usize, String> {
+        let mut current = h;
+        for _ in 0..self.half_edges.len() {
+            match self.half_edges[current].twin {
+                None => return Ok(current),
+                Some(t) => current = self.half_edges[t].next,
+            }
+            if current == h {
+                return Ok(h);
+            }
+        }
+        Err(format!(
+            "the faces around point {} do not form a single fan",
+            self.half_edges[h].origin
+        ))
+    }
+    pub fn vertex(&self, v: usize) -> &Vertex {
+        &self.vertices[v]
+    }
+    pub fn half_edge(&self, h: usize) -> &HalfEdge {
+        &self.half_edges[h]
+    }
+    /// The vertex a half-edge points to
+    pub fn destination(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].next].origin
+    }
+    /// Iterates over the indices of the vertices that have not been removed
+    pub fn vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.vertices.len()).filter(|v| !self.vertices[*v].removed)
+    }
+    /// Iterates over the indices of the faces that have not been removed
+    pub fn faces(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.faces.len()).filter(|f| self.faces[*f].is_some())
+    }
+    /// The three half-edges of a face, in order, or `None` if the face has been removed
+    pub fn face_half_edges(&self, f: usize) -> Option<[usize; 3]> {
+        self.faces[f].map(|h| self.triangle_from(h))
+    }
+    /// The half-edges of the face containing `h`, starting from `h`
+    fn triangle_from(&self, h: usize) -> [usize; 3] {
+        let h1 = self.half_edges[h].next;
+        [h, h1, self.half_edges[h1].next]
+    }
+    /// The three vertices of a face, counterclockwise when viewed from outside, or `None` if the face
+    /// has been removed
+    pub fn face_vertices(&self, f: usize) -> Option<[usize; 3]> {
+        self.face_half_edges(f)
+            .map(|edges| edges.map(|h| self.half_edges[h].origin))
+    }
+    /// Iterates over the faces that share an edge with `f`, which is none if `f` has been removed
+    pub fn face_neighbors(&self, f: usize) -> impl Iterator<Item = usize> + '_ {
+        self.face_half_edges(f)
+            .into_iter()
+            .flatten()
+            .filter_map(|h| self.half_edges[h].twin)
+            .map(|t| self.half_edges[t].face)
+    }
+    /// The faces on either side of the edge containing `h`. The second is `None` on a boundary.
+    pub fn edge_faces(&self, h: usize) -> (usize, Option<usize>) {
+        let edge = &self.half_edges[h];
+        (edge.face, edge.twin.map(|t| self.half_edges[t].face))
+    }
+    /// Iterates over the half-edges leaving `v`, clockwise when viewed from outside
+    pub fn outgoing_half_edges(&self, v: usize) -> OutgoingHalfEdges<'_> {
+        OutgoingHalfEdges {
+            mesh: self,
+            start: self.vertices[v].half_edge,
+            current: self.vertices[v].half_edge,
+        }
+    }
+    /// Iterates over the one-ring of `v`: every vertex that shares an edge with it
+    pub fn vertex_neighbors(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let last_incoming = self.vertices[v].half_edge.and_then(|h| {
+            // On a boundary the final neighbour is only reachable through an incoming edge
+            self.half_edges[h].twin.is_none().then(|| {
+                let mut last = h;
+                for out in self.outgoing_half_edges(v) {
+                    last = out;
+                }
+                self.half_edges[self.half_edges[last].prev].origin
+            })
+        });
+        self.outgoing_half_edges(v)
+            .map(|h| self.destination(h))
+            .chain(last_incoming)
+    }
+    /// Iterates over the faces touching `v`
+    pub fn vertex_faces(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing_half_edges(v).map(|h| self.half_edges[h].face)
+    }
+    /// Finds the half-edge running from `a` to `b`, if there is one
+    pub fn find_half_edge(&self, a: usize, b: usize) -> Option<usize> {
+        if let Some(h) = self
+            .outgoing_half_edges(a)
+            .find(|h| self.destination(*h) == b)
+        {
+            return Some(h);
+        }
+        // On a boundary the edge may only exist as an incoming half-edge of a
+        self.outgoing_half_edges(b)
+            .find(|h| self.destination(*h) == a)
+            .and_then(|h| self.half_edges[h].twin)
+    }
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertices[v]
+            .half_edge
+            .is_some_and(|h| self.half_edges[h].twin.is_none())
+    }
+    /// Finds every hole in the surface, each as a list of vertices in the order the boundary
+    /// half-edges traverse them
+    pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.half_edges.len()];
+        let mut loops = Vec::new();
+        for start in 0..self.half_edges.len() {
+            let edge = &self.half_edges[start];
+            if edge.removed || edge.twin.is_some() || visited[start] {
+                continue;
+            }
+            let mut boundary = Vec::new();
+            let mut current = start;
+            while !visited[current] {
+                visited[current] = true;
+                boundary.push(self.half_edges[current].origin);
+                // The next boundary half-edge leaves from where this one ends
+                let Some(next) = self.vertices[self.destination(current)].half_edge else {
+                    break;
+                };
+                current = next;
+            }
+            loops.push(boundary);
+        }
+        loops
+    }
+    fn link(&mut self, a: usize, b: usize, c: usize, face: usize) {
+        for (h, next, prev) in [(a, b, c), (b, c, a), (c, a, b)] {
+            self.half_edges[h].next = next;
+            self.half_edges[h].prev = prev;
+            self.half_edges[h].face = face;
+        }
+        self.faces[face] = Some(a);
+    }
+    fn set_twins(&mut self, a: Option<usize>, b: Option<usize>) {
+        if let Some(a) = a {
+            self.half_edges[a].twin = b;
+        }
+        if let Some(b) = b {
+            self.half_edges[b].twin = a;
+        }
+    }
+    fn new_half_edge(&mut self, origin: usize) -> usize {
+        self.half_edges.push(HalfEdge {
+            origin,
+            twin: None,
+            next: 0,
+            prev: 0,
+            face: 0,
+            removed: false,
+        });
+        self.half_edges.len() - 1
+    }
+    fn new_face(&mut self) -> usize {
+        self.faces.push(None);
+        self.faces.len() - 1
+    }
+    /// Checks that `h` is still part of the mesh, so that edits refuse stale handles before changing
+    /// anything
+    fn live_half_edge(&self, h: usize) -> Result<(), String> {
+        match self.half_edges.get(h) {
+            None => Err(format!("half-edge {h} does not exist")),
+            Some(edge) if edge.removed => Err(format!("half-edge {h} has been removed")),
+            Some(_) => Ok(()),
+        }
+    }
+    fn refresh_vertex(&mut self, v: usize, candidate: usize) {
+        // Edits only start once their checks pass, and they keep the faces around every vertex in a
+        // single fan, so its start is always found
+        let start = self
+            .boundary_start(candidate)
+            .expect("the faces around a vertex no longer form a single fan");
+        self.vertices[v].half_edge = Some(start);
+    }
+    /// Replaces the edge containing `h` with the other diagonal of the two triangles beside it. Fails
+    /// without changing the mesh if `h` has been removed, is on a boundary, or the other diagonal is
+    /// already an edge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::half_edge::HalfEdgeMesh;
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// let mut mesh = HalfEdgeMesh::from_polyhedron(&Polyhedron::cube()).unwrap();
+    /// let h = mesh.find_half_edge(1, 2).unwrap();
+    /// mesh.flip_edge(h).unwrap();
+    /// assert!(mesh.find_half_edge(1, 2).is_none());
+    /// assert!(mesh.find_half_edge(0, 6).is_some());
+    /// assert!((mesh.to_polyhedron().get_volume() - 1.0).abs() < 1E-12);
+    /// ```
+    pub fn flip_edge(&mut self, h: usize) -> Result<(), String> {
+        self.live_half_edge(h)?;
+        let t = self.half_edges[h]
+            .twin
+            .ok_or("cannot flip a boundary edge".to_owned())?;
+        let [_, h1, h2] = self.triangle_from(h);
+        let [_, t1, t2] = self.triangle_from(t);
+        let a = self.half_edges[h].origin;
+        let b = self.half_edges[t].origin;
+        let c = self.half_edges[h2].origin;
+        let d = self.half_edges[t2].origin;
+        if c == d || self.find_half_edge(c, d).is_some() {
+            return Err(format!(
+                "flipping edge ({a}, {b}) would duplicate edge ({c}, {d})"
+            ));
+        }
+        let (f0, f1) = (self.half_edges[h].face, self.half_edges[t].face);
+        self.half_edges[h].origin = d;
+        self.half_edges[t].origin = c;
+        self.link(h2, t1, h, f0);
+        self.link(t2, h1, t, f1);
+        self.refresh_vertex(a, t1);
+        self.refresh_vertex(b, h1);
+        self.refresh_vertex(c, h2);
+        self.refresh_vertex(d, t2);
+        Ok(())
+    }
+    /// Inserts a new vertex at `position` on the edge containing `h`, splitting each triangle beside
+    /// the edge in two.
+    ///
+    /// returns: Result<usize, String>, the index of the new vertex, or an error without changing the mesh
+    /// if `h` has been removed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::half_edge::HalfEdgeMesh;
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let mut mesh = HalfEdgeMesh::from_polyhedron(&Polyhedron::cube()).unwrap();
+    /// let h = mesh.find_half_edge(1, 2).unwrap();
+    /// let m = mesh.split_edge(h, Vector3::new(0.5, 0.5, 0.0)).unwrap();
+    /// assert_eq!(mesh.vertex_neighbors(m).count(), 4);
+    /// assert_eq!(mesh.faces().count(), 14);
+    /// ```
+    pub fn split_edge(&mut self, h: usize, position: Vector3) -> Result<usize, String> {
+        self.live_half_edge(h)?;
+        let m = self.vertices.len();
+        self.vertices.push(Vertex {
+            position,
+            half_edge: None,
+            removed: false,
+        });
+        let a = self.half_edges[h].origin;
+        let b = self.destination(h);
+        let twin = self.half_edges[h].twin;
+
+        // Face a,b,c becomes a,m,c and m,b,c
+        let f0 = self.half_edges[h].face;
+        let [_, h1, h2] = self.triangle_from(h);
+        let c = self.half_edges[h2].origin;
+        let m_to_b = self.new_half_edge(m);
+        let c_to_m = self.new_half_edge(c);
+        let m_to_c = self.new_half_edge(m);
+        self.set_twins(Some(c_to_m), Some(m_to_c));
+        let f2 = self.new_face();
+        self.link(h, m_to_c, h2, f0);
+        self.link(m_to_b, h1, c_to_m, f2);
+
+        // The twin face b,a,d becomes b,m,d and m,a,d
+        let (m_to_a, b_to_m) = match twin {
+            Some(t) => {
+                let f1 = self.half_edges[t].face;
+                let [_, t1, t2] = self.triangle_from(t);
+                let d = self.half_edges[t2].origin;
+                let m_to_a = self.new_half_edge(m);
+                let d_to_m = self.new_half_edge(d);
+                let m_to_d = self.new_half_edge(m);
+                self.set_twins(Some(d_to_m), Some(m_to_d));
+                let f3 = self.new_face();
+                self.link(t, m_to_d, t2, f1);
+                self.link(m_to_a, t1, d_to_m, f3);
+                self.refresh_vertex(d, t2);
+                (Some(m_to_a), Some(t))
+            }
+            None => (None, None),
+        };
+        self.set_twins(Some(h), m_to_a);
+        self.set_twins(Some(m_to_b), b_to_m);
+        self.refresh_vertex(m, m_to_b);
+        self.refresh_vertex(a, h);
+        self.refresh_vertex(b, h1);
+        self.refresh_vertex(c, h2);
+        Ok(m)
+    }
+    /// Merges the two ends of the edge containing `h` into a single vertex at `position`, removing the
+    /// triangles beside the edge. Fails without changing the mesh if `h` has been removed or the
+    /// collapse would make the surface non-manifold.
+    ///
+    /// returns: The index of the surviving vertex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::half_edge::HalfEdgeMesh;
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let mut mesh = HalfEdgeMesh::from_polyhedron(&Polyhedron::cube()).unwrap();
+    /// let h = mesh.find_half_edge(1, 2).unwrap();
+    /// let m = mesh.split_edge(h, Vector3::new(0.5, 0.5, 0.0)).unwrap();
+    /// let h = mesh.find_half_edge(m, 2).unwrap();
+    /// mesh.collapse_edge(h, Vector3::new(0.0, 1.0, 0.0)).unwrap();
+    /// assert_eq!(mesh.faces().count(), 12);
+    /// assert_eq!(mesh.vertices().count(), 8);
+    /// ```
+    pub fn collapse_edge(&mut self, h: usize, position: Vector3) -> Result<usize, String> {
+        self.live_half_edge(h)?;
+        let a = self.half_edges[h].origin;
+        let b = self.destination(h);
+        let twin = self.half_edges[h].twin;
+        let [_, _, h2] = self.triangle_from(h);
+        let c = self.half_edges[h2].origin;
+        let opposite = match twin {
+            Some(t) => {
+                let [_, _, t2] = self.triangle_from(t);
+                vec![c, self.half_edges[t2].origin]
+            }
+            None => vec![c],
+        };
+        // Link condition: the only vertices adjacent to both ends are the ones opposite the edge
+        let a_ring: Vec<usize> = self.vertex_neighbors(a).collect();
+        for n in self.vertex_neighbors(b) {
+            if n != a && a_ring.contains(&n) && !opposite.contains(&n) {
+                return Err(format!(
+                    "collapsing edge ({a}, {b}) would pinch the surface at vertex {n}"
+                ));
+            }
+        }
+        if twin.is_some() && self.is_boundary_vertex(a) && self.is_boundary_vertex(b) {
+            return Err(format!(
+                "collapsing interior edge ({a}, {b}) would join two boundaries"
+            ));
+        }
+        if self.faces().count() <= 4 {
+            return Err("collapsing any edge of a tetrahedron leaves no volume".to_owned());
+        }
+
+        let b_edges: Vec<usize> = self.outgoing_half_edges(b).collect();
+        let mut touched = Vec::new();
+        let mut remove_face = |mesh: &mut Self, e: usize| {
+            let [e0, e1, e2] = mesh.triangle_from(e);
+            let outer1 = mesh.half_edges[e1].twin;
+            let outer2 = mesh.half_edges[e2].twin;
+            mesh.set_twins(outer1, outer2);
+            touched.extend(outer1);
+            touched.extend(outer2);
+            mesh.faces[mesh.half_edges[e].face] = None;
+            for removed in [e0, e1, e2] {
+                mesh.half_edges[removed].removed = true;
+            }
+        };
+        remove_face(self, h);
+        if let Some(t) = twin {
+            remove_face(self, t);
+        }
+        for e in b_edges {
+            if !self.half_edges[e].removed {
+                self.half_edges[e].origin = a;
+            }
+        }
+        for e in self.half_edges.iter_mut() {
+            if e.removed {
+                e.twin = None;
+            }
+        }
+        self.vertices[b].removed = true;
+        self.vertices[b].half_edge = None;
+        self.vertices[a].position = position;
+        touched.retain(|e| !self.half_edges[*e].removed);
+        for e in touched {
+            let origin = self.half_edges[e].origin;
+            self.refresh_vertex(origin, e);
+            let other = self.destination(e);
+            let other_out = self.half_edges[e].next;
+            self.refresh_vertex(other, other_out);
+        }
+        Ok(a)
+    }
+}
+
+/// Iterator over the half-edges leaving a vertex, created by [HalfEdgeMesh::outgoing_half_edges]
+pub struct OutgoingHalfEdges<'a> {
+    mesh: &'a HalfEdgeMesh,
+    start: Option<usize>,
+    current: Option<usize>,
+}
+
+impl Iterator for OutgoingHalfEdges<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        let h = self.current?;
+        let incoming = self.mesh.half_edges[h].prev;
+        self.current = self.mesh.half_edges[incoming]
+            .twin
+            .filter(|next| Some(*next) != self.start);
+        Some(h)
+    }
+}
@@ -1,5 +1,9 @@
+use crate::approx::impl_approx_eq;
+use crate::predicates::orient3d;
 use crate::simple_tri::SimpleTriangle;
 use crate::vectors::Vector3;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct Tetrahedron {
@@ -9,6 +13,8 @@ pub struct Tetrahedron {
     pub c: Vector3,
 }
 
+impl_approx_eq!(Tetrahedron, origin, a, b, c);
+
 impl Tetrahedron {
     pub fn new(origin: Vector3, a: Vector3, b: Vector3, c: Vector3) -> Self {
         Tetrahedron { origin, a, b, c }
@@ -66,6 +72,131 @@ impl Tetrahedron {
         sf[0].area() + sf[1].area() + sf[2].area() + sf[3].area()
     }
 }
+/// Describes the changes [Polyhedron::new_autofix] made. All indices refer to the input points and faces.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RepairReport {
+    /// Pairs of (point, point it was merged into)
+    pub welded_points: Vec<(usize, usize)>,
+    /// Points that no remaining face referenced
+    pub unused_points: Vec<usize>,
+    /// Faces that had zero area after welding
+    pub degenerate_faces: Vec<usize>,
+    /// Faces that used the same points as an earlier face
+    pub duplicate_faces: Vec<usize>,
+    /// Faces whose winding was reversed
+    pub flipped_faces: Vec<usize>,
+}
+
+impl RepairReport {
+    pub fn is_empty(&self) -> bool {
+        self.welded_points.is_empty()
+            && self.unused_points.is_empty()
+            && self.degenerate_faces.is_empty()
+            && self.duplicate_faces.is_empty()
+            && self.flipped_faces.is_empty()
+    }
+}
+
+impl fmt::Display for RepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no repairs needed");
+        }
+        let mut parts = Vec::new();
+        if !self.welded_points.is_empty() {
+            parts.push(format!("welded {} points", self.welded_points.len()));
+        }
+        if !self.unused_points.is_empty() {
+            parts.push(format!(
+                "removed {} unused points",
+                self.unused_points.len()
+            ));
+        }
+        if !self.degenerate_faces.is_empty() {
+            parts.push(format!(
+                "removed degenerate faces {:?}",
+                self.degenerate_faces
+            ));
+        }
+        if !self.duplicate_faces.is_empty() {
+            parts.push(format!(
+                "removed duplicate faces {:?}",
+                self.duplicate_faces
+            ));
+        }
+        if !self.flipped_faces.is_empty() {
+            parts.push(format!("flipped faces {:?}", self.flipped_faces));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// The number of times a closed surface winds around a point, found by summing the solid angle each
+/// face subtends (Van Oosterom and Strackee). It is 1 inside a shell with outward normals, -1 inside one
+/// with inward normals and 0 outside.
+fn winding_number(points: &[Vector3], faces: &[(usize, usize, usize)], pt: Vector3) -> f64 {
+    let solid_angle: f64 = faces
+        .iter()
+        .map(|f| {
+            let (a, b, c) = (points[f.0] - pt, points[f.1] - pt, points[f.2] - pt);
+            let (la, lb, lc) = (a.magnitude(), b.magnitude(), c.magnitude());
+            let numerator = a.dot(&b.cross(&c));
+            let denominator = la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+            2.0 * numerator.atan2(denominator)
+        })
+        .sum();
+    solid_angle / (4.0 * std::f64::consts::PI)
+}
+
+/// Maps every point to the index of the first point within `tolerance` of it, so points that should be
+/// the same vertex share an index. A tolerance of zero only merges exactly equal points.
+pub(crate) fn weld_points(points: &[Vector3], tolerance: f64) -> Vec<usize> {
+    let cell = |p: &Vector3| -> (i64, i64, i64) {
+        if tolerance > 0.0 {
+            (
+                (p.x / tolerance).floor() as i64,
+                (p.y / tolerance).floor() as i64,
+                (p.z / tolerance).floor() as i64,
+            )
+        } else {
+            (
+                p.x.to_bits() as i64,
+                p.y.to_bits() as i64,
+                p.z.to_bits() as i64,
+            )
+        }
+    };
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    let mut representative = Vec::with_capacity(points.len());
+    for (i, p) in points.iter().enumerate() {
+        let (cx, cy, cz) = cell(p);
+        let reach = if tolerance > 0.0 { 1 } else { 0 };
+        let mut found = None;
+        'search: for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                for dz in -reach..=reach {
+                    if let Some(candidates) = grid.get(&(cx + dx, cy + dy, cz + dz))
+                        && let Some(c) = candidates
+                            .iter()
+                            .find(|c| points[**c].dist_to(p) <= tolerance)
+                    {
+                        found = Some(*c);
+                        break 'search;
+                    }
+                }
+            }
+        }
+        match found {
+            Some(c) => representative.push(c),
+            None => {
+                grid.entry((cx, cy, cz)).or_default().push(i);
+                representative.push(i);
+            }
+        }
+    }
+    representative
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Polyhedron {
     points: Vec<Vector3>,
@@ -73,24 +204,349 @@ pub struct Polyhedron {
 }
 
 impl Polyhedron {
-    //TODO:Impl
-    fn check_homeomorphism(points: &Vec<Vector3>, faces: &Vec<(usize, usize, usize)>) -> bool {
-        true
+    /// Checks that the faces form a closed, edge-manifold surface: every face references valid, distinct
+    /// points, no face is repeated, and every edge is shared by exactly two faces.
+    fn check_homeomorphism(
+        points: &[Vector3],
+        faces: &[(usize, usize, usize)],
+    ) -> Result<(), String> {
+        if faces.len() < 4 {
+            return Err(format!(
+                "A closed polyhedron needs at least 4 faces, but only {} were given",
+                faces.len()
+            ));
+        }
+        let mut problems = Vec::new();
+        for (i, f) in faces.iter().enumerate() {
+            for idx in [f.0, f.1, f.2] {
+                if idx >= points.len() {
+                    problems.push(format!(
+                        "face {i} references point {idx}, but there are only {} points",
+                        points.len()
+                    ));
+                }
+            }
+            if f.0 == f.1 || f.1 == f.2 || f.2 == f.0 {
+                problems.push(format!("face {i} uses the same point more than once"));
+            }
+        }
+        // Edge checks below index into points, so stop here if any face is malformed
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+        let mut seen_faces: BTreeMap<[usize; 3], usize> = BTreeMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            let mut key = [f.0, f.1, f.2];
+            key.sort();
+            if let Some(first) = seen_faces.get(&key) {
+                problems.push(format!("face {i} is a duplicate of face {first}"));
+            } else {
+                seen_faces.insert(key, i);
+            }
+        }
+        for ((a, b), users) in Self::undirected_edges(faces) {
+            if users.len() == 1 {
+                problems.push(format!(
+                    "edge ({a}, {b}) is only used by face {}, so the surface is not closed",
+                    users[0]
+                ));
+            } else if users.len() > 2 {
+                problems.push(format!(
+                    "edge ({a}, {b}) is shared by faces {users:?}, so the surface is not manifold"
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
     }
-    //TODO:Impl
-    fn check_normals(points: &Vec<Vector3>, faces: &Vec<(usize, usize, usize)>) -> bool {
-        true
+    /// Checks that every edge is traversed in opposite directions by its two faces, and that the
+    /// resulting normals point outward. Assumes [Polyhedron::check_homeomorphism] has passed.
+    fn check_normals(points: &[Vector3], faces: &[(usize, usize, usize)]) -> Result<(), String> {
+        let mut directed: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            for edge in [(f.0, f.1), (f.1, f.2), (f.2, f.0)] {
+                directed.entry(edge).or_default().push(i);
+            }
+        }
+        let problems: Vec<String> = directed
+            .iter()
+            .filter(|(_, users)| users.len() > 1)
+            .map(|((a, b), users)| {
+                format!(
+                    "edge ({a}, {b}) is traversed in the same direction by faces {users:?}, so they are inconsistently oriented"
+                )
+            })
+            .collect();
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+        let volume = Self::new_unchecked(points.to_vec(), faces.to_vec()).get_volume();
+        if volume <= 0.0 {
+            return Err(format!(
+                "the faces are consistently oriented, but their normals point inward (volume is {volume})"
+            ));
+        }
+        Ok(())
     }
-    //TODO:Impl
+    /// Maps every undirected edge (smallest point index first) to the faces that use it.
+    fn undirected_edges(faces: &[(usize, usize, usize)]) -> BTreeMap<(usize, usize), Vec<usize>> {
+        let mut edges: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            for (a, b) in [(f.0, f.1), (f.1, f.2), (f.2, f.0)] {
+                edges.entry((a.min(b), a.max(b))).or_default().push(i);
+            }
+        }
+        edges
+    }
+    /// Creates a polyhedron, verifying that the faces form a closed, edge-manifold surface with
+    /// consistent, outward facing normals (counterclockwise when viewed from outside).
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The vertices of the polyhedron
+    /// * `faces`: Triples of indices into `points`
+    ///
+    /// returns: Result<Polyhedron, String>, where the error lists every offending face and edge
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let points = vec![Vector3::zero(), Vector3::i_hat(), Vector3::j_hat(), Vector3::k_hat()];
+    /// let tetrahedron = Polyhedron::new(points.clone(), vec![(0, 2, 1), (0, 1, 3), (0, 3, 2), (1, 2, 3)]);
+    /// assert!(tetrahedron.is_ok());
+    /// // Flipping every face makes the normals point inward
+    /// let inward = Polyhedron::new(points.clone(), vec![(0, 1, 2), (0, 3, 1), (0, 2, 3), (1, 3, 2)]);
+    /// assert!(inward.unwrap_err().contains("inward"));
+    /// // Missing a face leaves three edges open
+    /// let open = Polyhedron::new(points, vec![(0, 2, 1), (0, 1, 3), (0, 3, 2)]);
+    /// assert!(open.is_err());
+    /// ```
     pub fn new(points: Vec<Vector3>, faces: Vec<(usize, usize, usize)>) -> Result<Self, String> {
-        Err("NOIMPL".to_owned())
+        Self::check_homeomorphism(&points, &faces)?;
+        Self::check_normals(&points, &faces)?;
+        Ok(Self { points, faces })
     }
-    //TODO:Impl
+    /// Creates a polyhedron from possibly messy triangle soup, repairing it before validating it with
+    /// [Polyhedron::new]. Points closer than `1E-9` times the bounding box diagonal are welded,
+    /// zero-area and duplicate faces are dropped, unused points are removed, and faces are flipped so
+    /// every connected shell is consistently oriented. Shells face outward, except those nested inside
+    /// an odd number of other shells, which bound cavities and so face inward.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The vertices of the polyhedron
+    /// * `faces`: Triples of indices into `points`
+    ///
+    /// returns: Result<(Polyhedron, RepairReport), String>, where the report refers to the input indices
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// // A tetrahedron with inward normals, a duplicated corner and a duplicated face
+    /// let points = vec![
+    ///     Vector3::zero(),
+    ///     Vector3::i_hat(),
+    ///     Vector3::j_hat(),
+    ///     Vector3::k_hat(),
+    ///     Vector3::new(1.0 + 1E-12, 0.0, 0.0),
+    /// ];
+    /// let faces = vec![(0, 1, 2), (0, 3, 4), (0, 2, 3), (1, 3, 2), (0, 1, 2)];
+    /// let (hedron, report) = Polyhedron::new_autofix(points, faces).unwrap();
+    /// assert!((hedron.get_volume() - 1.0 / 6.0).abs() < 1E-9);
+    /// assert_eq!(report.welded_points, vec![(4, 1)]);
+    /// assert_eq!(report.duplicate_faces, vec![4]);
+    /// assert_eq!(report.flipped_faces, vec![0, 1, 2, 3]);
+    /// ```
     pub fn new_autofix(
         points: Vec<Vector3>,
         faces: Vec<(usize, usize, usize)>,
-    ) -> Result<Self, String> {
-        Err("NOIMPL".to_owned())
+    ) -> Result<(Self, RepairReport), String> {
+        let mut report = RepairReport::default();
+        for (i, f) in faces.iter().enumerate() {
+            for idx in [f.0, f.1, f.2] {
+                if idx >= points.len() {
+                    return Err(format!(
+                        "face {i} references point {idx}, but there are only {} points",
+                        points.len()
+                    ));
+                }
+            }
+        }
+        let tolerance = Self::bounding_diagonal(&points) * 1E-9;
+        let representative = weld_points(&points, tolerance);
+        for (i, rep) in representative.iter().enumerate() {
+            if *rep != i {
+                report.welded_points.push((i, *rep));
+            }
+        }
+
+        // Drop degenerate and duplicate faces, remembering which input face each survivor came from
+        let mut kept: Vec<(usize, (usize, usize, usize))> = Vec::new();
+        let mut seen_faces: BTreeMap<[usize; 3], usize> = BTreeMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            let welded = (
+                representative[f.0],
+                representative[f.1],
+                representative[f.2],
+            );
+            let tri = SimpleTriangle::new(points[welded.0], points[welded.1], points[welded.2]);
+            let longest_edge = (tri.b - tri.a)
+                .magnitude()
+                .max((tri.c - tri.b).magnitude())
+                .max((tri.a - tri.c).magnitude());
+            if welded.0 == welded.1
+                || welded.1 == welded.2
+                || welded.2 == welded.0
+                || tri.area() <= tolerance * longest_edge
+            {
+                report.degenerate_faces.push(i);
+                continue;
+            }
+            let mut key = [welded.0, welded.1, welded.2];
+            key.sort();
+            if seen_faces.contains_key(&key) {
+                report.duplicate_faces.push(i);
+                continue;
+            }
+            seen_faces.insert(key, i);
+            kept.push((i, welded));
+        }
+
+        // Flip faces so that neighbours traverse their shared edge in opposite directions
+        let kept_faces: Vec<(usize, usize, usize)> = kept.iter().map(|(_, f)| *f).collect();
+        let edges = Self::undirected_edges(&kept_faces);
+        let mut flip: Vec<Option<bool>> = vec![None; kept_faces.len()];
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for seed in 0..kept_faces.len() {
+            if flip[seed].is_some() {
+                continue;
+            }
+            flip[seed] = Some(false);
+            let mut component = vec![seed];
+            let mut queue = VecDeque::from([seed]);
+            while let Some(current) = queue.pop_front() {
+                let f = kept_faces[current];
+                for (a, b) in [(f.0, f.1), (f.1, f.2), (f.2, f.0)] {
+                    let users = &edges[&(a.min(b), a.max(b))];
+                    // Propagating across non-manifold edges is ambiguous, so leave those to validation
+                    if users.len() != 2 {
+                        continue;
+                    }
+                    let neighbour = if users[0] == current {
+                        users[1]
+                    } else {
+                        users[0]
+                    };
+                    if flip[neighbour].is_some() {
+                        continue;
+                    }
+                    let g = kept_faces[neighbour];
+                    let same_direction = [(g.0, g.1), (g.1, g.2), (g.2, g.0)].contains(&(a, b));
+                    flip[neighbour] = Some(flip[current] != Some(same_direction));
+                    component.push(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+            let oriented = Self::oriented_faces(&kept_faces, &flip, &component);
+            if Self::new_unchecked(points.clone(), oriented).get_volume() < 0.0 {
+                for i in &component {
+                    flip[*i] = flip[*i].map(|x| !x);
+                }
+            }
+            components.push(component);
+        }
+        // Every shell now faces outward. A shell nested inside an odd number of others bounds a
+        // cavity, so it has to face inward instead.
+        let shells: Vec<Vec<(usize, usize, usize)>> = components
+            .iter()
+            .map(|component| Self::oriented_faces(&kept_faces, &flip, component))
+            .collect();
+        for (i, shell) in shells.iter().enumerate() {
+            let depth = shells
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| {
+                    // Test with a corner the other shell doesn't touch, so it can't lie on its surface
+                    let used: BTreeSet<usize> =
+                        other.iter().flat_map(|f| [f.0, f.1, f.2]).collect();
+                    let corner = shell
+                        .iter()
+                        .flat_map(|f| [f.0, f.1, f.2])
+                        .find(|c| !used.contains(c));
+                    *j != i
+                        && corner.is_some_and(|c| winding_number(&points, other, points[c]) > 0.5)
+                })
+                .count();
+            if depth % 2 == 1 {
+                for k in &components[i] {
+                    flip[*k] = flip[*k].map(|x| !x);
+                }
+            }
+        }
+
+        // Compact the remaining points and apply the flips
+        let mut new_index: Vec<Option<usize>> = vec![None; points.len()];
+        let mut new_points = Vec::new();
+        let mut new_faces = Vec::new();
+        for (k, (original, f)) in kept.iter().enumerate() {
+            let f = if flip[k] == Some(true) {
+                report.flipped_faces.push(*original);
+                (f.0, f.2, f.1)
+            } else {
+                *f
+            };
+            let mut remap = |idx: usize| {
+                *new_index[idx].get_or_insert_with(|| {
+                    new_points.push(points[idx]);
+                    new_points.len() - 1
+                })
+            };
+            new_faces.push((remap(f.0), remap(f.1), remap(f.2)));
+        }
+        report.unused_points = (0..points.len())
+            .filter(|i| representative[*i] == *i && new_index[*i].is_none())
+            .collect();
+        match Self::new(new_points, new_faces) {
+            Ok(hedron) => Ok((hedron, report)),
+            Err(e) => Err(format!("Could not repair polyhedron: {e}")),
+        }
+    }
+    /// The faces of `component`, each flipped if `flip` says so
+    fn oriented_faces(
+        faces: &[(usize, usize, usize)],
+        flip: &[Option<bool>],
+        component: &[usize],
+    ) -> Vec<(usize, usize, usize)> {
+        component
+            .iter()
+            .map(|i| {
+                let f = faces[*i];
+                if flip[*i] == Some(true) {
+                    (f.0, f.2, f.1)
+                } else {
+                    f
+                }
+            })
+            .collect()
+    }
+    fn bounding_diagonal(points: &[Vector3]) -> f64 {
+        if points.is_empty() {
+            return 0.0;
+        }
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in points {
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        max.dist_to(&min)
     }
     pub fn new_unchecked(points: Vec<Vector3>, faces: Vec<(usize, usize, usize)>) -> Self {
         Self { points, faces }
@@ -123,6 +579,219 @@ impl Polyhedron {
             ],
         )
     }
+    /// Computes the convex hull of a set of points with Quickhull. Points that lie on (or within
+    /// rounding error of) the hull's surface without being corners are left out, as are duplicates, so
+    /// the faces of flat sides are triangulated arbitrarily between their corners.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The points to enclose
+    ///
+    /// returns: Result<Polyhedron, String>, which is an error if the points are all coplanar
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let mut points = Polyhedron::cube().get_points().to_vec();
+    /// // Interior, duplicate and face-centre points don't change the hull
+    /// points.push(Vector3::new(0.5, 0.5, 0.5));
+    /// points.push(Vector3::new(1.0, 1.0, 1.0));
+    /// points.push(Vector3::new(0.5, 0.5, 1.0));
+    /// let hull = Polyhedron::convex_hull(&points).unwrap();
+    /// assert_eq!(hull.get_points().len(), 8);
+    /// assert_eq!(hull.get_face_indices().len(), 12);
+    /// assert!((hull.get_volume() - 1.0).abs() < 1E-12);
+    /// assert!(Polyhedron::convex_hull(&[Vector3::zero(), Vector3::i_hat(), Vector3::j_hat()]).is_err());
+    /// ```
+    pub fn convex_hull(points: &[Vector3]) -> Result<Self, String> {
+        struct HullFace {
+            corners: [usize; 3],
+            normal: Vector3,
+            outside: Vec<usize>,
+            alive: bool,
+        }
+        let distance =
+            |face: &HullFace, p: usize| face.normal.dot(&(points[p] - points[face.corners[0]]));
+        // Decided exactly, so that neighbouring faces never disagree about which side a point is on
+        let is_above = |face: &HullFace, p: usize| {
+            let [a, b, c] = face.corners;
+            orient3d(points[a], points[b], points[c], points[p]) > 0.0
+        };
+        let make_face = |corners: [usize; 3]| {
+            let normal = (points[corners[1]] - points[corners[0]])
+                .cross(&(points[corners[2]] - points[corners[0]]))
+                .hat();
+            HullFace {
+                corners,
+                normal,
+                outside: Vec::new(),
+                alive: true,
+            }
+        };
+        if points.len() < 4 {
+            return Err("A convex hull needs at least 4 points".to_owned());
+        }
+        // Points closer than this are too close to start the hull from
+        let max_coords = points.iter().fold(Vector3::zero(), |m, p| {
+            Vector3::new(m.x.max(p.x.abs()), m.y.max(p.y.abs()), m.z.max(p.z.abs()))
+        });
+        let tolerance = 3.0 * f64::EPSILON * (max_coords.x + max_coords.y + max_coords.z);
+
+        // Start with the largest tetrahedron that can be found cheaply
+        let mut extremes = [0; 6];
+        for (i, p) in points.iter().enumerate() {
+            for (axis, value) in p.as_array().iter().enumerate() {
+                if *value < points[extremes[2 * axis]].as_array()[axis] {
+                    extremes[2 * axis] = i;
+                }
+                if *value > points[extremes[2 * axis + 1]].as_array()[axis] {
+                    extremes[2 * axis + 1] = i;
+                }
+            }
+        }
+        let mut p0 = extremes[0];
+        let mut p1 = extremes[1];
+        for a in extremes {
+            for b in extremes {
+                if points[a].dist_to(&points[b]) > points[p0].dist_to(&points[p1]) {
+                    (p0, p1) = (a, b);
+                }
+            }
+        }
+        if points[p0].dist_to(&points[p1]) <= tolerance {
+            return Err("All points are coincident, so the hull has no volume".to_owned());
+        }
+        let axis = (points[p1] - points[p0]).hat();
+        let line_distance = |i: usize| (points[i] - points[p0]).cross(&axis).magnitude();
+        let p2 = (0..points.len())
+            .max_by(|a, b| line_distance(*a).total_cmp(&line_distance(*b)))
+            .unwrap();
+        if line_distance(p2) <= tolerance {
+            return Err("All points are collinear, so the hull has no volume".to_owned());
+        }
+        let base = make_face([p0, p1, p2]);
+        let p3 = (0..points.len())
+            .max_by(|a, b| {
+                distance(&base, *a)
+                    .abs()
+                    .total_cmp(&distance(&base, *b).abs())
+            })
+            .unwrap();
+        if distance(&base, p3).abs() <= tolerance {
+            return Err("All points are coplanar, so the hull has no volume".to_owned());
+        }
+        let (p1, p2) = if orient3d(points[p0], points[p1], points[p2], points[p3]) > 0.0 {
+            // p3 is above the counterclockwise face p0,p1,p2, so that face must point the other way
+            (p2, p1)
+        } else {
+            (p1, p2)
+        };
+        let mut faces = vec![
+            make_face([p0, p1, p2]),
+            make_face([p0, p3, p1]),
+            make_face([p1, p3, p2]),
+            make_face([p2, p3, p0]),
+        ];
+        let mut edge_owner: HashMap<(usize, usize), usize> = HashMap::new();
+        for (i, f) in faces.iter().enumerate() {
+            let [a, b, c] = f.corners;
+            for edge in [(a, b), (b, c), (c, a)] {
+                edge_owner.insert(edge, i);
+            }
+        }
+        let assign = |faces: &mut Vec<HullFace>, candidates: &[usize], targets: &[usize]| {
+            for p in candidates {
+                if let Some(t) = targets.iter().find(|t| is_above(&faces[**t], *p)) {
+                    faces[*t].outside.push(*p);
+                }
+            }
+        };
+        let all: Vec<usize> = (0..points.len())
+            .filter(|i| ![p0, p1, p2, p3].contains(i))
+            .collect();
+        assign(&mut faces, &all, &[0, 1, 2, 3]);
+
+        while let Some(current) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+            let eye = *faces[current]
+                .outside
+                .iter()
+                .max_by(|a, b| {
+                    distance(&faces[current], **a).total_cmp(&distance(&faces[current], **b))
+                })
+                .unwrap();
+            // Flood out from the current face to every face the eye can see
+            let mut visible = vec![current];
+            let mut is_visible: HashSet<usize> = HashSet::from([current]);
+            let mut horizon = Vec::new();
+            let mut k = 0;
+            while k < visible.len() {
+                let [a, b, c] = faces[visible[k]].corners;
+                for (from, to) in [(a, b), (b, c), (c, a)] {
+                    let neighbour = edge_owner[&(to, from)];
+                    if is_visible.contains(&neighbour) {
+                        continue;
+                    }
+                    if is_above(&faces[neighbour], eye) {
+                        is_visible.insert(neighbour);
+                        visible.push(neighbour);
+                    } else {
+                        horizon.push((from, to));
+                    }
+                }
+                k += 1;
+            }
+            let mut orphans = Vec::new();
+            for v in &visible {
+                faces[*v].alive = false;
+                orphans.append(&mut faces[*v].outside);
+                let [a, b, c] = faces[*v].corners;
+                for edge in [(a, b), (b, c), (c, a)] {
+                    edge_owner.remove(&edge);
+                }
+            }
+            // Cone the horizon to the eye
+            let mut created = Vec::new();
+            for (from, to) in horizon {
+                let i = faces.len();
+                faces.push(make_face([from, to, eye]));
+                for edge in [(from, to), (to, eye), (eye, from)] {
+                    edge_owner.insert(edge, i);
+                }
+                created.push(i);
+            }
+            orphans.retain(|p| *p != eye);
+            assign(&mut faces, &orphans, &created);
+        }
+
+        let mut used: Vec<usize> = faces
+            .iter()
+            .filter(|f| f.alive)
+            .flat_map(|f| f.corners)
+            .collect();
+        used.sort();
+        used.dedup();
+        let mut new_index = vec![0; points.len()];
+        for (i, p) in used.iter().enumerate() {
+            new_index[*p] = i;
+        }
+        let hull_faces = faces
+            .iter()
+            .filter(|f| f.alive)
+            .map(|f| {
+                let [a, b, c] = f.corners;
+                (new_index[a], new_index[b], new_index[c])
+            })
+            .collect();
+        Self::new(used.iter().map(|p| points[*p]).collect(), hull_faces)
+    }
+    pub fn get_points(&self) -> &[Vector3] {
+        &self.points
+    }
+    pub fn get_face_indices(&self) -> &[(usize, usize, usize)] {
+        &self.faces
+    }
     pub fn get_faces(&self) -> Vec<SimpleTriangle> {
         self.faces
             .iter()
@@ -1,22 +1,53 @@
+#![allow(non_snake_case)]
+
+pub mod affine3;
+pub mod approx;
+pub mod clipping;
+pub mod delaunay;
 pub mod gon;
+pub mod half_edge;
 pub mod hedron;
 pub mod line;
+pub mod mesh_io;
+pub mod offset;
 pub mod pose3;
+pub mod predicates;
 pub mod quaternion;
 pub mod ray;
 pub mod rotation3;
+pub mod scalar;
 pub mod simple_plane;
 pub mod simple_tri;
 pub mod utils;
 pub mod vectors;
+pub mod voronoi;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::hedron::Tetrahedron;
-    use crate::vectors::{Vector2, Vector3};
-    use gon::Polygon;
+    use crate::delaunay::Triangulation;
+    use crate::half_edge::HalfEdgeMesh;
+    use crate::hedron::{Polyhedron, Tetrahedron};
+    use crate::vectors::{Vector2, Vector2f, Vector3};
+    use gon::{Containment, MultiPolygon, Polygon};
 
+    /// A repeatable stream of uniformly distributed numbers in `[min, max)`, so randomized tests
+    /// check the same cases on every run
+    fn seeded_random(mut seed: u64, min: f64, max: f64) -> impl FnMut() -> f64 {
+        move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            min + (seed >> 11) as f64 / (1u64 << 53) as f64 * (max - min)
+        }
+    }
+    /// The axis-aligned rectangle from `(x0, y0)` to `(x1, y1)` as a region
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> MultiPolygon {
+        MultiPolygon::from(Polygon::rectangle(
+            Vector2::new(x0, y0),
+            Vector2::new(x1, y1),
+        ))
+    }
     #[test]
     fn test_vectors() {
         let i = Vector3 {
@@ -55,7 +86,54 @@ mod tests {
         assert_eq!(j.dot(&k), 0.0);
         assert_eq!(i.cross(&j), k);
         assert_eq!(j.cross(&i), -k);
-        assert_eq!(i.cross(&(j * 2.0)), k * 2.0)
+        assert_eq!(i.cross(&(j * 2.0)), k * 2.0);
+
+        // Operators and helpers that consumers used to write themselves
+        let mut v = Vector3::new(1.0, -2.0, 3.0);
+        assert_eq!(2.0 * v, v * 2.0);
+        assert_eq!(0.5f32 * Vector2f::new(2.0, 4.0), Vector2f::new(1.0, 2.0));
+        v += i;
+        v -= k * 3.0;
+        v *= 2.0;
+        v /= 4.0;
+        assert_eq!(v, Vector3::new(1.0, -1.0, 0.0));
+        v[2] = 5.0;
+        assert_eq!((v[0], v[1], v[2]), (1.0, -1.0, 5.0));
+        assert_eq!(v.as_array(), [v[0], v[1], v[2]]);
+        let corners = [i, j, k, -i];
+        assert_eq!(corners.iter().sum::<Vector3>(), j + k);
+        assert_eq!(corners.into_iter().sum::<Vector3>(), j + k);
+        assert_eq!(
+            v.component_mul(&Vector3::new(2.0, 3.0, 0.5)),
+            Vector3::new(2.0, -3.0, 2.5)
+        );
+        assert_eq!(v.component_mul(&v).component_div(&v), v);
+        assert_eq!(i.lerp(&j, 0.25), Vector3::new(0.75, 0.25, 0.0));
+        assert_eq!(v.abs(), Vector3::new(1.0, 1.0, 5.0));
+        assert_eq!(v.min(&Vector3::zero()), Vector3::new(0.0, -1.0, 0.0));
+        assert_eq!(v.max(&Vector3::zero()), Vector3::new(1.0, 0.0, 5.0));
+        let unit = Vector3::new(1.0, 1.0, 1.0);
+        assert_eq!(v.clamp(&-unit, &unit), Vector3::new(1.0, -1.0, 1.0));
+        // Projection and rejection split a vector, and reflecting twice gives it back
+        let n = Vector3::new(1.0, 2.0, 2.0);
+        assert_eq!(v.project_onto(&n) + v.reject_from(&n), v);
+        assert!(v.reject_from(&n).dot(&n).abs() < 1E-12);
+        assert_eq!(v.reflect(&n).reflect(&n), v);
+        assert_eq!(v.reflect(&n).magnitude(), v.magnitude());
+        // The angle from atan2 stays accurate where the arccosine of the cosine loses it all
+        let tiny = Vector3::new(1.0, 1E-10, 0.0);
+        assert!((i.angle_between(&tiny) - 1E-10).abs() < 1E-20);
+        assert_eq!(i.angle_cosine(&tiny).acos(), 0.0);
+
+        let p = Vector2::new(3.0, 4.0);
+        assert_eq!(p.magnitude(), 5.0);
+        assert_eq!(p.with_magnitude(10.0), Vector2::new(6.0, 8.0));
+        assert_eq!(p.project_onto(&Vector2::i_hat()), Vector2::new(3.0, 0.0));
+        assert_eq!(p.reflect(&Vector2::new(0.0, -2.0)), Vector2::new(3.0, -4.0));
+        assert_eq!(
+            p.signed_angle_to(&p.reflect(&Vector2::j_hat())),
+            -p.angle_between(&Vector2::new(3.0, -4.0))
+        );
     }
     #[test]
     fn test_hedron() {
@@ -68,6 +146,416 @@ mod tests {
         assert_eq!(hedr.volume(), -4.5);
     }
     #[test]
+    fn test_polyhedron_validation() {
+        let cube = Polyhedron::cube();
+        assert!(
+            Polyhedron::new(cube.get_points().to_vec(), cube.get_face_indices().to_vec()).is_ok()
+        );
+        let mut flipped = cube.get_face_indices().to_vec();
+        flipped[4] = (flipped[4].0, flipped[4].2, flipped[4].1);
+        let err = Polyhedron::new(cube.get_points().to_vec(), flipped).unwrap_err();
+        assert!(err.contains("inconsistently oriented"));
+        let mut duplicated = cube.get_face_indices().to_vec();
+        duplicated.push(cube.get_face_indices()[0]);
+        let err = Polyhedron::new(cube.get_points().to_vec(), duplicated).unwrap_err();
+        assert!(err.contains("face 12 is a duplicate of face 0"));
+    }
+    #[test]
+    fn test_polyhedron_autofix() {
+        let cube = Polyhedron::cube();
+        let mut faces: Vec<(usize, usize, usize)> = cube
+            .get_face_indices()
+            .iter()
+            .map(|f| (f.0, f.2, f.1))
+            .collect();
+        faces[3] = cube.get_face_indices()[3];
+        faces.push((0, 1, 1));
+        let (fixed, report) = Polyhedron::new_autofix(cube.get_points().to_vec(), faces).unwrap();
+        assert!((fixed.get_volume() - 1.0).abs() < 1E-12);
+        assert_eq!(report.degenerate_faces, vec![12]);
+        assert_eq!(report.flipped_faces.len(), 11);
+        assert!(!report.flipped_faces.contains(&3));
+        let (_, report) =
+            Polyhedron::new_autofix(cube.get_points().to_vec(), cube.get_face_indices().to_vec())
+                .unwrap();
+        assert!(report.is_empty());
+        // A cube with a cubic cavity, both given facing outward, and a separate cube beside them
+        let mut points = cube.get_points().to_vec();
+        let mut faces = cube.get_face_indices().to_vec();
+        for (offset, scale, shift) in [(8, 0.5, 0.25), (16, 1.0, 3.0)] {
+            points.extend(
+                cube.get_points()
+                    .iter()
+                    .map(|p| *p * scale + Vector3::new(shift, shift, shift)),
+            );
+            faces.extend(
+                cube.get_face_indices()
+                    .iter()
+                    .map(|f| (f.0 + offset, f.1 + offset, f.2 + offset)),
+            );
+        }
+        let (hollow, report) = Polyhedron::new_autofix(points, faces).unwrap();
+        assert!((hollow.get_volume() - 1.875).abs() < 1E-12);
+        assert_eq!(report.flipped_faces, (12..24).collect::<Vec<_>>());
+    }
+    #[test]
+    fn test_half_edge_edits() {
+        let cube = Polyhedron::cube();
+        let mut faces = cube.get_face_indices().to_vec();
+        faces.remove(0);
+        let open = Polyhedron::new_unchecked(cube.get_points().to_vec(), faces);
+        let mut mesh = HalfEdgeMesh::from_polyhedron(&open).unwrap();
+        assert_eq!(mesh.boundary_loops(), vec![vec![0, 1, 2]]);
+        assert!(mesh.is_boundary_vertex(0));
+        assert!(!mesh.is_boundary_vertex(7));
+        let mut ring: Vec<usize> = mesh.vertex_neighbors(0).collect();
+        ring.sort();
+        assert_eq!(ring, vec![1, 2, 3]);
+
+        let boundary = mesh.find_half_edge(0, 1).unwrap();
+        assert_eq!(mesh.edge_faces(boundary).1, None);
+        let m = mesh
+            .split_edge(boundary, Vector3::new(0.5, 0.0, 0.0))
+            .unwrap();
+        assert_eq!(mesh.boundary_loops(), vec![vec![0, m, 1, 2]]);
+        let interior = mesh.find_half_edge(4, 7).unwrap();
+        let m2 = mesh
+            .split_edge(interior, Vector3::new(0.5, 1.0, 1.0))
+            .unwrap();
+        let edge = mesh.find_half_edge(m2, 7).unwrap();
+        assert_eq!(
+            mesh.collapse_edge(edge, Vector3::new(1.0, 1.0, 1.0)),
+            Ok(m2)
+        );
+        // Stale handles and refused edits leave the mesh as it was
+        let before = mesh.clone();
+        let err = mesh.collapse_edge(edge, Vector3::zero()).unwrap_err();
+        assert!(err.contains("has been removed"));
+        assert!(mesh.flip_edge(edge).is_err());
+        assert!(mesh.split_edge(edge, Vector3::zero()).is_err());
+        assert!(mesh.split_edge(usize::MAX, Vector3::zero()).is_err());
+        let err = mesh
+            .flip_edge(mesh.find_half_edge(0, m).unwrap())
+            .unwrap_err();
+        assert!(err.contains("boundary"));
+        assert_eq!(mesh, before);
+        let h = mesh.find_half_edge(4, 5).unwrap();
+        mesh.flip_edge(h).unwrap();
+
+        let edited = mesh.to_polyhedron();
+        let mut closed_faces = edited.get_face_indices().to_vec();
+        let boundary_loop = HalfEdgeMesh::from_polyhedron(&edited)
+            .unwrap()
+            .boundary_loops();
+        let hole = &boundary_loop[0];
+        closed_faces.push((hole[0], hole[3], hole[2]));
+        closed_faces.push((hole[0], hole[2], hole[1]));
+        let closed =
+            Polyhedron::new(edited.get_points().to_vec(), closed_faces).expect("mesh is valid");
+        assert!((closed.get_volume() - 1.0).abs() < 1E-12);
+
+        // Two tetrahedra sharing only a corner meet in two fans there, which edits can't keep intact
+        let points = vec![
+            Vector3::zero(),
+            Vector3::i_hat(),
+            Vector3::j_hat(),
+            Vector3::k_hat(),
+            -Vector3::i_hat(),
+            -Vector3::j_hat(),
+            -Vector3::k_hat(),
+        ];
+        let tetrahedron = [(0, 2, 1), (0, 1, 3), (0, 3, 2), (1, 2, 3)];
+        let mut faces = tetrahedron.to_vec();
+        faces.extend(
+            tetrahedron
+                .map(|(a, b, c)| (a, c + 3, b + 3))
+                .map(|(a, b, c)| if a == 0 { (a, b, c) } else { (a + 3, b, c) }),
+        );
+        let bowtie = Polyhedron::new_unchecked(points, faces);
+        let err = HalfEdgeMesh::from_polyhedron(&bowtie).unwrap_err();
+        assert!(err.contains("point 0 do not form a single fan"));
+
+        // A long run of edits keeps every fan intact, so the mesh stays a closed surface of genus 0
+        let mut mesh = HalfEdgeMesh::from_polyhedron(&Polyhedron::cube()).unwrap();
+        for step in 0..60 {
+            let faces: Vec<usize> = mesh.faces().collect();
+            let [h, _, _] = mesh.face_half_edges(faces[step * 7 % faces.len()]).unwrap();
+            let (a, b) = (mesh.half_edge(h).origin, mesh.destination(h));
+            let midpoint = (mesh.vertex(a).position + mesh.vertex(b).position) * 0.5;
+            match step % 3 {
+                0 => {
+                    mesh.split_edge(h, midpoint).unwrap();
+                }
+                1 => {
+                    // Refusing a flip or collapse is fine, as long as the mesh is left unchanged
+                    let before = mesh.clone();
+                    if mesh.flip_edge(h).is_err() {
+                        assert_eq!(mesh, before, "step {step}");
+                    }
+                }
+                _ => {
+                    let before = mesh.clone();
+                    if mesh.collapse_edge(h, midpoint).is_err() {
+                        assert_eq!(mesh, before, "step {step}");
+                    }
+                }
+            }
+            let hedron = mesh.to_polyhedron();
+            let faces = hedron.get_face_indices();
+            let used: std::collections::BTreeSet<usize> =
+                faces.iter().flat_map(|&(a, b, c)| [a, b, c]).collect();
+            assert_eq!(
+                used.len() + faces.len() - faces.len() * 3 / 2,
+                2,
+                "step {step}"
+            );
+            assert!(HalfEdgeMesh::from_polyhedron(&hedron).is_ok());
+            assert!(mesh.boundary_loops().is_empty());
+        }
+        let removed = (0..24).find(|f| mesh.face_vertices(*f).is_none());
+        if let Some(f) = removed {
+            assert_eq!(mesh.face_neighbors(f).count(), 0);
+        }
+    }
+    #[test]
+    fn test_obj() {
+        use crate::mesh_io::obj::{ObjErrorKind, read_obj, read_obj_groups, read_obj_objects};
+        let kind = |text: &str| read_obj(text).unwrap_err().kind;
+        let triangle = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+        assert_eq!(kind("v 1 2\n"), ObjErrorKind::MissingCoordinates);
+        assert_eq!(
+            kind("v 1 x 3\n"),
+            ObjErrorKind::InvalidNumber("x".to_owned())
+        );
+        assert_eq!(
+            kind(&format!("{triangle}f 1 2\n")),
+            ObjErrorKind::TooFewCorners(2)
+        );
+        assert_eq!(
+            kind(&format!("{triangle}f 1 a 3\n")),
+            ObjErrorKind::InvalidIndex("a".to_owned())
+        );
+        assert_eq!(
+            kind(&format!("{triangle}f /1/1 2 3\n")),
+            ObjErrorKind::InvalidIndex("/1/1".to_owned())
+        );
+        assert_eq!(
+            kind(&format!("{triangle}f 0 1 2\n")),
+            ObjErrorKind::ZeroIndex
+        );
+        assert_eq!(
+            kind(&format!("{triangle}f 1 2 -4\n")),
+            ObjErrorKind::IndexOutOfRange(-4)
+        );
+        // A face can only use vertices defined above it
+        assert_eq!(
+            kind("v 0 0 0\nf 1 2 3\nv 1 0 0\nv 0 1 0\n"),
+            ObjErrorKind::IndexOutOfRange(2)
+        );
+        // Errors on a continued line are reported at the line it starts on
+        let err = read_obj(&format!("{triangle}f 1 \\\n 2 \\\n 9\nf 1 2 3\n")).unwrap_err();
+        assert_eq!((err.line, err.kind), (4, ObjErrorKind::IndexOutOfRange(9)));
+        assert!(read_obj(&format!("{triangle}f 1 \\\n 2 \\\n 3\n")).is_ok());
+
+        // Negative indices count back from the last vertex defined so far
+        let text = format!("{triangle}f -3 -2 -1\nv 1 1 0\nf -3 -1 -2\n");
+        assert_eq!(
+            read_obj(&text).unwrap().get_face_indices(),
+            &[(0, 1, 2), (1, 3, 2)]
+        );
+
+        // Texture coordinates and normals are dropped, however they are written
+        for face in [
+            "f 1/1/1 2/2/2 3/3/3",
+            "f 1//1 2//1 3//1",
+            "f 1/1 2/2 3/3",
+            "f -3/1 -2//1 -1/1/1",
+        ] {
+            let mesh = read_obj(&format!("{triangle}vt 0 0\nvn 0 0 1\n{face}\n")).unwrap();
+            assert_eq!(mesh.get_face_indices(), &[(0, 1, 2)], "{face}");
+        }
+
+        // A concave face in a tilted plane keeps its winding, whichever way round it is written
+        let arrow = [(0.0, 0.0), (2.0, 1.0), (0.0, 2.0), (1.0, 1.0)];
+        let facing = Vector3::new(0.0, -0.8, 0.6);
+        for reversed in [false, true] {
+            let mut corners: Vec<&str> = vec!["1", "2", "3", "4"];
+            if reversed {
+                corners.reverse();
+            }
+            let mut text: String = arrow
+                .iter()
+                .map(|(x, y)| format!("v {} {} {}\n", x + 3.0, y * 0.6 - 1.0, y * 0.8 + 2.0))
+                .collect();
+            text.push_str(&format!("f {}\n", corners.join(" ")));
+            let mesh = read_obj(&text).unwrap();
+            let points = mesh.get_points();
+            let mut area = 0.0;
+            for &(a, b, c) in mesh.get_face_indices() {
+                let normal = (points[b] - points[a]).cross(&(points[c] - points[a]));
+                let side = if reversed { -1.0 } else { 1.0 };
+                assert!(normal.dot(&facing) * side > 0.0);
+                area += normal.magnitude() / 2.0;
+            }
+            assert!((area - 1.0).abs() < 1E-12);
+        }
+
+        // Groups can be reopened and overlap, unlike objects
+        let text = format!(
+            "{triangle}v 0 0 1\nf 1 3 2\no tip\ng side front\nf 1 2 4\ng side\nf 2 3 4\ng\nf 3 1 4\n"
+        );
+        let groups = read_obj_groups(&text).unwrap();
+        let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["", "side", "front"]);
+        let counts: Vec<usize> = groups
+            .iter()
+            .map(|g| g.mesh.get_face_indices().len())
+            .collect();
+        assert_eq!(counts, [2, 2, 1]);
+        let objects = read_obj_objects(&text).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[1].mesh.get_face_indices().len(), 3);
+        let whole = read_obj(&text).unwrap();
+        assert!(
+            Polyhedron::new(
+                whole.get_points().to_vec(),
+                whole.get_face_indices().to_vec()
+            )
+            .is_ok()
+        );
+    }
+    #[test]
+    fn test_off_ply() {
+        use crate::mesh_io::off::{read_off, write_off};
+        use crate::mesh_io::ply::{PlyError, read_ply};
+        let cube = Polyhedron::cube();
+        let off = write_off(&cube);
+        // ModelNet glues the vertex count to the keyword
+        assert_eq!(read_off(&off.replacen("OFF\n", "OFF", 1)).unwrap(), cube);
+        for keyword in ["COFF", "NOFF", "STOFF", "CNOFF"] {
+            assert_eq!(
+                read_off(&off.replacen("OFF", keyword, 1)).unwrap(),
+                cube,
+                "{keyword}"
+            );
+        }
+        assert!(read_off(&off.replacen("OFF", "SOFF", 1)).is_err());
+        let message = |text: &str| read_off(text).unwrap_err().message;
+        let triangle = "OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n";
+        assert_eq!(
+            message(&format!("{triangle}2 0 1\n")),
+            "a face needs at least 3 corners, found 2"
+        );
+        assert_eq!(
+            message(&format!("{triangle}4 0 1 2\n")),
+            "expected 4 corners, found 3"
+        );
+        assert_eq!(
+            message(&format!("{triangle}3 0 1 3\n")),
+            "a corner is not a valid vertex index"
+        );
+        let err = read_off("OFF\n99999999999999 99999999999999 0\n0 0 0\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (3, "expected 99999999999999 vertices, found 1")
+        );
+
+        let header = |list: &str| {
+            format!(
+                "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+                 property float z\nelement face 1\nproperty list {list} vertex_indices\nend_header\n\
+                 0 0 0\n1 0 0\n0 1 0\n"
+            )
+        };
+        let read =
+            |list: &str, face: &str| read_ply(format!("{}{face}\n", header(list)).as_bytes());
+        assert_eq!(
+            read("uchar int", "3 0 1 2")
+                .unwrap()
+                .mesh
+                .get_face_indices(),
+            &[(0, 1, 2)]
+        );
+        for (list, face) in [
+            ("uchar int", "3 0 -1 2"),
+            ("uchar float", "3 0 1.5 2"),
+            ("uchar int", "3 0 1 3"),
+            ("char int", "-3 0 1 2"),
+            ("uchar int", "2.5 0 1 2"),
+            ("uchar int", "2 0 1"),
+        ] {
+            assert!(matches!(read(list, face), Err(PlyError::Data(_))), "{face}");
+        }
+        // A huge list length runs out of data rather than memory
+        assert_eq!(
+            read("uint int", "4294967295 0 1 2"),
+            Err(PlyError::UnexpectedEnd)
+        );
+        // An element with nothing in it is skipped however many of it there are said to be
+        let hollow = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\n\
+                      property float z\nelement marker 999999999999999999\nend_header\n1 2 3\n";
+        assert_eq!(
+            read_ply(hollow.as_bytes()).unwrap().mesh.get_points().len(),
+            1
+        );
+        // List vertex properties are dropped without losing the rest of the vertex
+        let listed = "ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\n\
+                      property list uchar float tags\nproperty float y\nproperty float z\n\
+                      property uchar red\nend_header\n1 2 0.5 0.25 2 3 9\n4 0 5 6 7\n";
+        let ply = read_ply(listed.as_bytes()).unwrap();
+        assert_eq!(
+            ply.mesh.get_points(),
+            &[Vector3::new(1.0, 2.0, 3.0), Vector3::new(4.0, 5.0, 6.0)]
+        );
+        assert_eq!(ply.vertex_properties.len(), 1);
+        assert_eq!(ply.vertex_values, vec![vec![9.0], vec![7.0]]);
+    }
+    #[test]
+    fn test_write_ply_mismatched_rows() {
+        use crate::mesh_io::ply::{PlyError, PlyFormat, PlyMesh, PlyProperty, PlyType, write_ply};
+        let mut ply = PlyMesh::new(Polyhedron::cube());
+        ply.vertex_properties.push(PlyProperty {
+            name: "confidence".to_owned(),
+            data_type: PlyType::Float,
+        });
+        ply.vertex_values[0].push(1.0);
+        ply.vertex_values[1].push(1.0);
+        assert!(matches!(
+            write_ply(&ply, PlyFormat::Ascii),
+            Err(PlyError::Data(_))
+        ));
+        for row in ply.vertex_values.iter_mut().skip(2) {
+            row.push(0.5);
+        }
+        assert!(write_ply(&ply, PlyFormat::BinaryLittleEndian).is_ok());
+        ply.vertex_values.pop();
+        assert!(write_ply(&ply, PlyFormat::Ascii).is_err());
+    }
+    #[test]
+    fn test_convex_hull_3d() {
+        // A grid has many coplanar and collinear points on every side of its hull
+        let mut grid = Vec::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..5 {
+                    grid.push(Vector3::new(x as f64, y as f64, z as f64) * 0.25);
+                }
+            }
+        }
+        let hull = Polyhedron::convex_hull(&grid).unwrap();
+        assert_eq!(hull.get_points().len(), 8);
+        assert!((hull.get_volume() - 1.0).abs() < 1E-12);
+
+        let mut random = seeded_random(12345, -1.0, 1.0);
+        let sphere: Vec<Vector3> = (0..500)
+            .map(|_| Vector3::new(random(), random(), random()).hat() * 3.0)
+            .collect();
+        let hull = Polyhedron::convex_hull(&sphere).unwrap();
+        assert_eq!(hull.get_points().len(), 500);
+        let volume = hull.get_volume();
+        assert!(volume > 0.9 * 36.0 * std::f64::consts::PI && volume < 36.0 * std::f64::consts::PI);
+    }
+    #[test]
     fn test_triangle() {
         let poly = Polygon::new(vec![
             Vector2::new(1.0, 0.0),
@@ -77,4 +565,1163 @@ mod tests {
         ]);
         assert_eq!(poly.area(), 1.0);
     }
+    #[test]
+    fn test_boolean_identities() {
+        let mut random = seeded_random(987654321, 0.0, 1.0);
+        // Random star-shaped polygons are concave and intersect each other in many places
+        let mut star = |cx: f64, cy: f64| {
+            let points: Vec<Vector2> = (0..12)
+                .map(|i| {
+                    let angle = i as f64 / 12.0 * std::f64::consts::TAU;
+                    let radius = 0.5 + random();
+                    Vector2::new(cx + radius * angle.cos(), cy + radius * angle.sin())
+                })
+                .collect();
+            MultiPolygon::from(Polygon::new(points))
+        };
+        for _ in 0..20 {
+            let a = star(0.0, 0.0);
+            let b = star(0.7, 0.3);
+            let union = a.union(&b).area();
+            let intersection = a.intersection(&b).area();
+            let difference = a.difference(&b).area();
+            let xor = a.xor(&b).area();
+            assert!((union + intersection - a.area() - b.area()).abs() < 1E-9);
+            assert!((difference - (a.area() - intersection)).abs() < 1E-9);
+            assert!((xor - (union - intersection)).abs() < 1E-9);
+            assert!(
+                a.union(&b)
+                    .get_polygons()
+                    .iter()
+                    .all(|p| p.get_outer().is_simple())
+            );
+            // Nothing is left, which has no centre of mass
+            let nothing = a.difference(&a);
+            assert_eq!(nothing.area(), 0.0);
+            assert!(nothing.centroid().x.is_finite() && nothing.centroid().y.is_finite());
+        }
+    }
+    #[test]
+    fn test_boolean_shared_edges() {
+        // Side by side squares merge into one rectangle with no leftover corners on the seam
+        let merged = rect(0.0, 0.0, 1.0, 1.0).union(&rect(1.0, 0.0, 2.0, 1.0));
+        assert_eq!(merged.get_polygons().len(), 1);
+        assert_eq!(merged.get_polygons()[0].get_outer().get_points().len(), 4);
+        // Identical inputs
+        let same = rect(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(same.intersection(&same).area(), 1.0);
+        assert_eq!(same.difference(&same).get_polygons().len(), 0);
+        // A bar across a field splits it in two
+        let field = rect(0.0, 0.0, 10.0, 10.0);
+        let free = field.difference(&rect(-1.0, 4.0, 11.0, 6.0));
+        assert_eq!(free.get_polygons().len(), 2);
+        assert_eq!(free.area(), 80.0);
+        // Subtracting from a region with a hole keeps the hole
+        let holed = field.difference(&rect(2.0, 2.0, 3.0, 3.0));
+        let both = holed.difference(&rect(8.0, 8.0, 12.0, 12.0));
+        assert_eq!(both.get_polygons()[0].get_holes().len(), 1);
+        assert_eq!(both.area(), 95.0);
+        assert_eq!(both.contains(Vector2::new(2.5, 2.5)), Containment::Outside);
+        // A grid of overlapping tiles, with many edges that only meet their neighbours
+        let tiles = (0..64).map(|i| {
+            let (x, y) = ((i % 8) as f64 * 0.5, (i / 8) as f64 * 0.5);
+            rect(x, y, x + 1.0, y + 1.0)
+        });
+        let floor = MultiPolygon::union_all(tiles);
+        assert_eq!(floor.get_polygons().len(), 1);
+        assert!(floor.get_polygons()[0].get_holes().is_empty());
+        assert!((floor.area() - 20.25).abs() < 1E-9);
+    }
+    #[test]
+    fn test_ear_clipping() {
+        // A comb with five teeth, which has many reflex corners
+        let mut pts = vec![Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0)];
+        for tooth in (0..5).rev() {
+            let x = tooth as f64 * 2.0;
+            pts.push(Vector2::new(x + 2.0, 5.0));
+            pts.push(Vector2::new(x + 1.5, 5.0));
+            pts.push(Vector2::new(x + 1.5, 1.0));
+            pts.push(Vector2::new(x, 1.0));
+        }
+        let comb = Polygon::new(pts);
+        let tris = comb.triangulate().unwrap();
+        assert_eq!(tris.len(), comb.get_points().len() - 2);
+        assert!(tris.iter().all(|t| t.area() > 0.0));
+        let total: f64 = tris.iter().map(|t| t.area()).sum();
+        assert!((total - comb.area()).abs() < 1E-12);
+        assert!((comb.area() - 20.0).abs() < 1E-12);
+
+        // Corners in the middle of an edge still get used, by triangles with no area
+        let dotted = Polygon::new(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(2.0, 2.0),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(0.0, 2.0),
+            Vector2::new(0.0, 1.0),
+        ]);
+        let tris = dotted.triangulate_indices().unwrap();
+        assert_eq!(tris.len(), 5);
+        let used: std::collections::BTreeSet<usize> =
+            tris.iter().flat_map(|&(a, b, c)| [a, b, c]).collect();
+        assert_eq!(used.len(), 7);
+        let total: f64 = dotted.triangulate().unwrap().iter().map(|t| t.area()).sum();
+        assert!((total - 4.0).abs() < 1E-12);
+
+        // Clockwise corners keep their order, and still give counterclockwise triangles
+        let backwards = Polygon::new(comb.get_points().iter().rev().copied().collect());
+        assert!(!backwards.is_counterclockwise());
+        assert_eq!(backwards.get_points()[0], Vector2::new(0.0, 1.0));
+        assert!((backwards.area() - 20.0).abs() < 1E-12);
+        let tris = backwards.triangulate().unwrap();
+        assert_eq!(tris.len(), comb.get_points().len() - 2);
+        assert!(tris.iter().all(|t| t.area() > 0.0));
+        // Regions store every ring counterclockwise
+        let region = gon::PolygonWithHoles::from(backwards);
+        assert!(region.get_outer().is_counterclockwise());
+        assert_eq!(region.get_outer(), &comb);
+    }
+
+    #[test]
+    fn test_offset() {
+        use crate::offset::JoinStyle;
+        // An L made of three unit squares
+        let ell = Polygon::new(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(2.0, 1.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(1.0, 2.0),
+            Vector2::new(0.0, 2.0),
+        ]);
+        let grown = ell.offset(0.5, JoinStyle::Miter { limit: 2.0 });
+        // 5 convex corners get full miters, the reflex corner needs no join
+        assert!((grown.area() - (3.0 + 0.5 * 8.0 + 5.0 * 0.25 - 0.25)).abs() < 1E-9);
+        assert_eq!(grown.get_polygons().len(), 1);
+        // Repeated corners, including one that closes the ring, don't change the result
+        let mut repeated = ell.get_points().to_vec();
+        repeated.insert(2, repeated[2]);
+        repeated.insert(0, repeated[0]);
+        repeated.push(repeated[0]);
+        let repeated = Polygon::new(repeated);
+        for distance in [0.5, -0.25] {
+            let join = JoinStyle::Miter { limit: 2.0 };
+            let expected = ell.offset(distance, join).area();
+            assert!((repeated.offset(distance, join).area() - expected).abs() < 1E-9);
+        }
+        // Shrinking joins the reflex corner instead of the convex ones
+        let mitered = ell.offset(-0.25, JoinStyle::Miter { limit: 2.0 });
+        assert!((mitered.area() - 1.25).abs() < 1E-9);
+        let shrunk = ell.offset(
+            -0.25,
+            JoinStyle::Round {
+                arc_tolerance: 1E-4,
+            },
+        );
+        let expected = 1.25 + 0.25 * 0.25 * (1.0 - std::f64::consts::PI / 4.0);
+        assert!((shrunk.area() - expected).abs() < 1E-4);
+        assert_eq!(
+            shrunk.contains(Vector2::new(0.1, 0.1)),
+            Containment::Outside
+        );
+        assert_eq!(shrunk.contains(Vector2::new(0.5, 0.5)), Containment::Inside);
+        // Two squares grown until they merge, enclosing nothing
+        let pair = MultiPolygon::new(vec![
+            Polygon::rectangle(Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0)).into(),
+            Polygon::rectangle(Vector2::new(1.5, 0.0), Vector2::new(2.5, 1.0)).into(),
+        ])
+        .unwrap();
+        let merged = pair.offset(0.5, JoinStyle::Square);
+        assert_eq!(merged.get_polygons().len(), 1);
+        assert!(merged.get_polygons()[0].get_holes().is_empty());
+        // Shrinking the merged region back leaves the original gap closed up
+        assert_eq!(
+            merged
+                .offset(-0.5, JoinStyle::Miter { limit: 2.0 })
+                .get_polygons()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_delaunay() {
+        let mut random = seeded_random(24680, 0.0, 1.0);
+        let check = |tri: &Triangulation, covers_hull: bool| {
+            let points = tri.get_points();
+            for (t, &(a, b, c)) in tri.get_triangles().iter().enumerate() {
+                assert!(tri.triangle(t).area() > 0.0);
+                for (i, n) in tri.get_neighbors()[t].iter().enumerate() {
+                    if let Some(n) = n {
+                        assert!(tri.get_neighbors()[*n].contains(&Some(t)));
+                    } else if covers_hull {
+                        // Edges without a neighbour are on the hull, with every point on their left
+                        let (u, v) = [(b, c), (c, a), (a, b)][i];
+                        let (u, v) = (points[u], points[v]);
+                        assert!(points.iter().all(|&p| (v - u).cross(&(p - u)) >= 0.0));
+                    }
+                }
+            }
+        };
+        // No point may be strictly inside the circumcircle of a triangle
+        let empty_circles = |tri: &Triangulation| {
+            let points = tri.get_points();
+            tri.get_triangles().iter().all(|&(a, b, c)| {
+                let (a, b, c) = (points[a], points[b], points[c]);
+                points.iter().all(|&d| {
+                    let (ad, bd, cd) = (a - d, b - d, c - d);
+                    let det = ad.dot(&ad) * bd.cross(&cd)
+                        + bd.dot(&bd) * cd.cross(&ad)
+                        + cd.dot(&cd) * ad.cross(&bd);
+                    det <= 1E-12
+                })
+            })
+        };
+        let cloud: Vec<Vector2> = (0..300).map(|_| Vector2::new(random(), random())).collect();
+        let tri = Triangulation::delaunay(&cloud).unwrap();
+        check(&tri, true);
+        assert!(empty_circles(&tri));
+        let hull = Polygon::convex_hull(&cloud, false);
+        let area: f64 = (0..tri.get_triangles().len())
+            .map(|t| tri.triangle(t).area())
+            .sum();
+        assert!((area - hull.area()).abs() < 1E-12);
+        // Every point of a grid is cocircular with its neighbours, and many are collinear
+        let grid: Vec<Vector2> = (0..100)
+            .map(|i| Vector2::new((i % 10) as f64, (i / 10) as f64))
+            .collect();
+        let tri = Triangulation::delaunay(&grid).unwrap();
+        check(&tri, true);
+        assert!(empty_circles(&tri));
+        assert_eq!(tri.get_triangles().len(), 162);
+        assert!(Triangulation::delaunay(&grid[..10]).is_err());
+
+        // A random star with points scattered inside it, some of which its edges must cut between
+        let ring: Vec<Vector2> = (0..40)
+            .map(|i| {
+                let angle = i as f64 / 40.0 * std::f64::consts::TAU;
+                let radius = 0.3 + random();
+                Vector2::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        let star = Polygon::new(ring.clone());
+        let mut points = ring;
+        points.extend((0..200).map(|_| Vector2::new(random() * 2.6 - 1.3, random() * 2.6 - 1.3)));
+        let edges: Vec<(usize, usize)> = (0..40).map(|i| (i, (i + 1) % 40)).collect();
+        let tri = Triangulation::constrained(&points, &edges).unwrap();
+        check(&tri, true);
+        assert!(
+            edges
+                .iter()
+                .all(|&(a, b)| tri.has_edge(a, b) && tri.is_constrained(a, b))
+        );
+        let tri = Triangulation::from_polygon(&star.clone().into()).unwrap();
+        check(&tri, false);
+        let area: f64 = (0..tri.get_triangles().len())
+            .map(|t| tri.triangle(t).area())
+            .sum();
+        assert!((area - star.area()).abs() < 1E-12);
+        assert_eq!(tri.get_triangles().len(), 38);
+    }
+
+    #[test]
+    fn test_voronoi() {
+        let mut random = seeded_random(13579, 0.0, 1.0);
+        // A concave field, so some cells are cut in two by the notch
+        let field = Polygon::new(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(3.0, 0.0),
+            Vector2::new(3.0, 3.0),
+            Vector2::new(2.0, 3.0),
+            Vector2::new(2.0, 1.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(1.0, 3.0),
+            Vector2::new(0.0, 3.0),
+        ]);
+        let mut sites: Vec<Vector2> = (0..40)
+            .map(|_| Vector2::new(random() * 3.0, random() * 3.0))
+            .collect();
+        sites.push(sites[7]);
+        let cells = voronoi::voronoi(&sites, &field);
+        assert_eq!(cells[40], cells[7]);
+        let total: f64 = cells[..40].iter().map(|c| c.area()).sum();
+        assert!((total - field.area()).abs() < 1E-9);
+        for _ in 0..500 {
+            let pt = Vector2::new(random() * 3.0, random() * 3.0);
+            let nearest = (0..40)
+                .min_by(|&a, &b| pt.dist_to(&sites[a]).total_cmp(&pt.dist_to(&sites[b])))
+                .unwrap();
+            let expected = field.contains(pt);
+            assert!(
+                cells[nearest].contains(pt) == expected
+                    || cells[nearest].contains(pt) == Containment::OnBoundary
+            );
+        }
+        // Too few sites to triangulate
+        let pair = voronoi::voronoi(&[Vector2::new(0.5, 0.5), Vector2::new(2.5, 0.5)], &field);
+        assert!((pair[0].area() - 3.5).abs() < 1E-12);
+        assert!((pair[1].area() - 3.5).abs() < 1E-12);
+        assert_eq!(pair[0].get_polygons().len(), 1);
+    }
+
+    #[test]
+    fn test_predicates() {
+        use crate::predicates::{incircle, insphere, orient2d, orient3d};
+        use crate::ray::Ray;
+        use crate::simple_plane::Intersection;
+        use crate::simple_tri::SimpleTriangle;
+        // The float `steps` floats above a positive float
+        let next = |x: f64, steps: u64| f64::from_bits(x.to_bits() + steps);
+        let sign = |x: f64| (x > 0.0) as i32 - (x < 0.0) as i32;
+        // Sliding a point across a line one float at a time, the sign changes exactly once, where the
+        // point is exactly on the line
+        let (b, c) = (Vector2::new(12.0, 12.0), Vector2::new(24.0, 24.0));
+        let signs: Vec<i32> = (0..64)
+            .map(|k| sign(orient2d(Vector2::new(0.5, next(0.5, k)), b, c)))
+            .collect();
+        assert_eq!(signs[0], 0);
+        assert!(signs[1..].iter().all(|s| *s == 1));
+        for k in 0..64 {
+            let a = Vector2::new(next(0.5, k), 0.5);
+            let o = sign(orient2d(a, b, c));
+            assert_eq!(o, -sign(orient2d(b, a, c)));
+            assert_eq!(o, sign(orient2d(b, c, a)));
+        }
+        // The corners of a rectangle are cocircular, and the corners of a cube cospherical
+        let rect =
+            [(0.1, 0.1), (0.3, 0.1), (0.3, 0.7), (0.1, 0.7)].map(|(x, y)| Vector2::new(x, y));
+        assert_eq!(incircle(rect[0], rect[1], rect[2], rect[3]), 0.0);
+        let nudged_out = Vector2::new(rect[3].x, next(rect[3].y, 1));
+        assert!(incircle(rect[0], rect[1], rect[2], nudged_out) < 0.0);
+        let nudged_in = Vector2::new(next(rect[3].x, 1), rect[3].y);
+        assert!(incircle(rect[0], rect[1], rect[2], nudged_in) > 0.0);
+        let cube =
+            |x: f64, y: f64, z: f64| Vector3::new(x, y, z) * 3.0 + Vector3::new(0.25, 0.5, 0.75);
+        let (a, b, c, d) = (
+            cube(0.0, 0.0, 0.0),
+            cube(1.0, 0.0, 0.0),
+            cube(0.0, 1.0, 0.0),
+            cube(0.0, 0.0, 1.0),
+        );
+        assert!(orient3d(a, b, c, d) > 0.0);
+        assert_eq!(insphere(a, b, c, d, cube(1.0, 1.0, 1.0)), 0.0);
+        let corner = cube(1.0, 1.0, 1.0);
+        let outside = Vector3::new(next(corner.x, 1), corner.y, corner.z);
+        assert!(insphere(a, b, c, d, outside) < 0.0);
+        let inside = Vector3::new(corner.x, corner.y, corner.z - 1E-15);
+        assert!(insphere(a, b, c, d, inside) > 0.0);
+        assert_eq!(orient3d(a, b, c, cube(1.0, 1.0, 0.0)), 0.0);
+        assert!(orient3d(a, b, c, Vector3::new(corner.x, corner.y, next(a.z, 1))) > 0.0);
+
+        // A ray coming from underneath hits the same as one from above
+        let tri = SimpleTriangle::new(
+            Vector3::zero(),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+        );
+        let up = Vector3::new(0.0, 0.0, 1.0);
+        for (x, y, hit) in [(0.5, 0.5, 1), (1.0, 1.0, 0), (1.5, 1.5, -1), (0.0, 0.7, 0)] {
+            let result = tri.ray_intersects(Ray::new(Vector3::new(x, y, -2.0), up));
+            let expected = match hit {
+                1 => Intersection::Once(Vector3::new(x, y, 0.0)),
+                0 => Intersection::Edge(Vector3::new(x, y, 0.0)),
+                _ => Intersection::Never,
+            };
+            assert_eq!(result, expected);
+        }
+
+        // Points on a circle are all cocircular, up to rounding, which must not break the triangulation
+        let circle: Vec<Vector2> = (0..64)
+            .map(|i| {
+                let angle = i as f64 / 64.0 * std::f64::consts::TAU;
+                Vector2::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        let tri = Triangulation::delaunay(&circle).unwrap();
+        assert_eq!(tri.get_triangles().len(), 62);
+        let area: f64 = (0..62).map(|t| tri.triangle(t).area()).sum();
+        assert!((area - Polygon::new(circle.clone()).area()).abs() < 1E-12);
+    }
+
+    #[test]
+    fn test_approx() {
+        use crate::approx::{ApproxEq, Tolerance};
+        use crate::line::LineSegment2;
+        use crate::pose3::Pose3;
+        use crate::quaternion::Quaternion;
+        use crate::rotation3::Rotation3;
+        use crate::utils::matrix::Matrix3;
+        // An absolute tolerance that is right near the origin is far too strict a long way from it
+        let far = Vector3::new(1E8, -3E8, 2E8);
+        let nudged = far + Vector3::new(1E-7, 0.0, -1E-7);
+        assert!(!far.abs_diff_eq(&nudged, 1E-9));
+        assert!(far.relative_eq(&nudged, 1E-9, 1E-12));
+        assert!(!Vector3::zero().relative_eq(&Vector3::new(1E-7, 0.0, 0.0), 1E-9, 1E-12));
+        // One float apart is within one ulp, but not two apart across zero
+        let x = 0.1 + 0.2;
+        assert!(x.ulps_eq(&0.3, 0.0, 1));
+        assert!(!x.ulps_eq(&0.3, 0.0, 0));
+        assert!(!1E-300.ulps_eq(&-1E-300, 0.0, u32::MAX));
+        assert!(1E-300.ulps_eq(&-1E-300, 1E-12, 0));
+
+        // A quaternion and its negation are the same rotation, but not the same quaternion
+        let q = Quaternion::new(0.5, 0.5, -0.5, 0.5);
+        let turn = Rotation3::new(q);
+        let tol = Tolerance::default();
+        assert!(tol.approx_eq(&turn, &Rotation3::new(q * -1.0)));
+        assert!(!tol.approx_eq(&q, &(q * -1.0)));
+        let pose = Pose3::new(far, turn);
+        assert!(tol.approx_eq(&pose, &Pose3::new(nudged, Rotation3::new(q * -1.0))));
+        assert!(!tol.approx_eq(&pose, &Pose3::new(far, Rotation3::identity())));
+
+        // Compositions of rounding errors compare equal with a relative tolerance
+        let m = Matrix3::new([[2.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 4.0]]);
+        let v = Vector3::new(0.1, 0.2, 0.3);
+        assert!(tol.approx_eq(&((m * 0.1) * v), &((m * v) * 0.1)));
+        assert!(tol.approx_eq(&(m * 0.1), &(m * (1.0 - 0.9))));
+        let square = Polygon::rectangle(Vector2::new(0.0, 0.0), Vector2::new(0.1, 0.1));
+        let moved = Polygon::new(
+            square
+                .get_points()
+                .iter()
+                .map(|p| *p + Vector2::new(0.7, 0.7) - Vector2::new(0.7, 0.7))
+                .collect(),
+        );
+        assert!(tol.approx_eq(&square, &moved));
+        assert!(!Tolerance::EXACT.approx_eq(&square, &moved));
+
+        // Tolerances scale with the coordinates, so the same relative error counts at any distance
+        let near_edge = |offset: f64| {
+            let s = LineSegment2::new(Vector2::new(offset, 0.0), Vector2::new(offset + 1.0, 0.0));
+            s.point_intersects_within(Vector2::new(offset + 0.5, 1E-4), Tolerance::relative(1E-9))
+        };
+        assert!(!near_edge(0.0));
+        assert!(near_edge(1E6));
+        let big = Polygon::new(square.get_points().iter().map(|p| *p * 1E9).collect());
+        let pt = Vector2::new(5E7, 1E-6);
+        assert_eq!(big.contains(pt), Containment::Inside);
+        assert_eq!(big.contains_within(pt, tol), Containment::OnBoundary);
+    }
+
+    #[test]
+    fn test_generic_scalars() {
+        use crate::approx::Tolerance;
+        use crate::line::{LineSegment2, LineSegment2f};
+        use crate::pose3::{Pose3, Pose3f};
+        use crate::ray::{Ray, Rayf};
+        use crate::rotation3::{Rotation3, Rotation3f};
+        use crate::simple_plane::{Intersection, Intersectionf, SimplePlanef};
+        use crate::simple_tri::{SimpleTriangle, SimpleTrianglef};
+        use crate::utils::matrix::Matrix3f;
+        use crate::vectors::{Vector2f, Vector3f};
+        // The same chain of poses agrees in both precisions, up to f32 rounding
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let step = Pose3::new(
+            Vector3::new(1.0, 0.5, 0.0),
+            Rotation3::from_axis_angle(Vector3::new(0.0, 0.3, 1.0).with_magnitude(quarter)),
+        );
+        let step_f = Pose3f::new(
+            Vector3f::new(1.0, 0.5, 0.0),
+            Rotation3f::from_axis_angle(
+                Vector3f::new(0.0, 0.3, 1.0).with_magnitude(quarter as f32),
+            ),
+        );
+        let (mut pose, mut pose_f) = (Pose3::identity(), Pose3f::identity());
+        for _ in 0..7 {
+            pose = pose + step;
+            pose_f = pose_f + step_f;
+        }
+        let tolerance = Tolerance::absolute(1E-5);
+        assert!(tolerance.approx_eq(&Pose3::from(pose_f), &pose));
+        assert!(!Tolerance::absolute(1E-12).approx_eq(&Pose3::from(pose_f), &pose));
+        // Narrowing rounds each component, and widening back keeps that rounding
+        let round_trip = Pose3::from(pose.cast::<f32>());
+        assert!(Tolerance::relative(1E-7).approx_eq(&round_trip, &pose));
+        assert_eq!(round_trip.cast::<f32>(), pose.cast::<f32>());
+
+        // Widening is exact, so the predicates decide f32 shapes exactly as they would the f64 ones
+        let tri = SimpleTrianglef::new(
+            Vector3f::zero(),
+            Vector3f::new(0.3, 0.0, 0.1),
+            Vector3f::new(0.0, 0.7, 0.2),
+        );
+        let down = Vector3f::new(0.0, 0.0, -1.0);
+        let wide: SimpleTriangle = SimpleTriangle::new(tri.a.into(), tri.b.into(), tri.c.into());
+        for (x, y) in [(0.1f32, 0.1f32), (0.15, 0.35), (0.3, 0.0), (0.2, 0.6)] {
+            let hit = tri.ray_intersects(Rayf::new(Vector3f::new(x, y, 5.0), down));
+            let wide_hit =
+                wide.ray_intersects(Ray::new(Vector3f::new(x, y, 5.0).into(), down.into()));
+            match (hit, wide_hit) {
+                (Intersectionf::Once(a), Intersection::Once(b))
+                | (Intersectionf::Edge(a), Intersection::Edge(b)) => {
+                    assert!(tolerance.approx_eq(&Vector3::from(a), &b))
+                }
+                (a, b) => assert_eq!(a == Intersectionf::Never, b == Intersection::Never),
+            }
+        }
+        let s = LineSegment2f::new(Vector2f::new(0.0, 0.0), Vector2f::new(0.3, 0.9));
+        let on = Vector2f::new(0.1, 0.3);
+        let wide_s: LineSegment2 = LineSegment2::new(s.a.into(), s.b.into());
+        assert_eq!(
+            s.point_intersects(on),
+            wide_s.point_intersects(Vector2::from(on))
+        );
+        assert!(s.point_intersects_within(on, Tolerance::relative(1E-6)));
+
+        // Matrices and planes work the same way in f32
+        let m = Matrix3f::new([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 8.0]]);
+        let v = Vector3f::new(1.0, 2.0, 3.0);
+        assert_eq!(m.inverse().unwrap() * (m * v), v);
+        let plane = SimplePlanef::new(Vector3f::new(0.0, 0.0, 1.0), Vector3f::k_hat());
+        let ray = Rayf::new(Vector3f::new(2.0, 1.0, 3.0), Vector3f::new(0.0, 0.0, -0.5));
+        assert_eq!(
+            plane.ray_intersects(ray),
+            Intersectionf::Once(Vector3f::new(2.0, 1.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_vector4() {
+        use crate::quaternion::Quaternion;
+        use crate::rotation3::Rotation3;
+        use crate::vectors::Vector4;
+        // Points survive the trip through homogeneous coordinates at any scale, directions become points
+        // at infinity
+        let p = Vector3::new(2.0, -1.0, 4.0);
+        for scale in [1.0, 0.5, -3.0, 1E10] {
+            assert_eq!((p.to_homogeneous_point() * scale).to_point(), Some(p));
+        }
+        assert_eq!(p.to_homogeneous_direction().to_point(), None);
+        assert_eq!(p.to_homogeneous_direction().xyz(), p);
+        let q = Vector2::new(0.5, 3.0);
+        assert_eq!((q.to_homogeneous_point() * 4.0).to_point(), Some(q));
+        assert_eq!(q.to_homogeneous_direction().to_point(), None);
+        assert_eq!(q.to_homogeneous_point().xy(), q);
+        // A pinhole camera looking down z divides by depth, which is what w does
+        let project = |pt: Vector3| {
+            Vector4::new(pt.x, pt.y, pt.z, pt.z)
+                .to_point()
+                .unwrap()
+                .xy()
+        };
+        assert_eq!(project(p), Vector2::new(0.5, -0.25));
+        assert_eq!(project(p * 7.0), project(p));
+        // Translating moves points but not directions
+        let shift = Vector4::new(1.0, 1.0, 1.0, 0.0);
+        let moved = |v: Vector4| v + shift * v.w;
+        assert_eq!(
+            moved(p.to_homogeneous_point()).to_point(),
+            Some(p + Vector3::new(1.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            moved(p.to_homogeneous_direction()),
+            p.to_homogeneous_direction()
+        );
+
+        let mut v = Vector4::new(1.0, 2.0, 3.0, 4.0);
+        v[3] = 0.0;
+        v += Vector4::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(v.as_array(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(v.to_point(), Some(Vector3::new(1.0, 2.0, 3.0)));
+        assert_eq!(2.0 * v, v * 2.0);
+        assert_eq!([v, -v].iter().sum::<Vector4>(), Vector4::zero());
+        assert_eq!(v.magnitude(), 15f64.sqrt());
+
+        // Quaternions as 4-vectors: normalized linear interpolation between two rotations
+        let a = Rotation3::identity();
+        let b = Rotation3::from_axis_angle(Vector3::k_hat() * std::f64::consts::FRAC_PI_2);
+        let (qa, qb) = (Vector4::from(a.q), Vector4::from(b.q));
+        assert_eq!(qa, Vector4::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(qa.dot(&qb), a.q.dot(&b.q));
+        assert_eq!(Quaternion::from(qb), b.q);
+        let halfway = Rotation3::new(Quaternion::from(qa.lerp(&qb, 0.5).hat()));
+        let eighth = Rotation3::from_axis_angle(Vector3::k_hat() * std::f64::consts::FRAC_PI_4);
+        assert_eq!(halfway, eighth);
+    }
+
+    #[test]
+    fn test_pose3() {
+        use crate::pose3::Pose3;
+        use crate::rotation3::Rotation3;
+        use std::f64::consts::FRAC_PI_2;
+        // A quarter turn about z and a quarter turn about x don't commute
+        let a = Pose3::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Rotation3::from_axis_angle(Vector3::k_hat() * FRAC_PI_2),
+        );
+        let b = Pose3::new(
+            Vector3::new(-2.0, 0.5, 1.0),
+            Rotation3::from_axis_angle(Vector3::i_hat() * FRAC_PI_2),
+        );
+        let place = |pose: Pose3, v: Vector3| pose.position + pose.orientation.rotate_vector(v);
+        for v in [
+            Vector3::i_hat(),
+            Vector3::j_hat(),
+            Vector3::new(0.3, -1.0, 2.0),
+        ] {
+            // Placing in b's frame, then a's, is placing in the frame of a + b
+            assert_eq!(place(a + b, v), place(a, place(b, v)));
+            assert_eq!(
+                (a + b).orientation.rotate_vector(v),
+                a.orientation.rotate_vector(b.orientation.rotate_vector(v))
+            );
+        }
+        // The y axis goes to z under b, then stays put under a
+        assert_eq!(
+            (a + b).orientation.rotate_vector(Vector3::j_hat()),
+            Vector3::k_hat()
+        );
+        assert_ne!((a + b).orientation, (b + a).orientation);
+        // Subtraction undoes addition from either side
+        assert_eq!((a + b) - a, b);
+        assert_eq!(a + ((a + b) - a), a + b);
+        assert_eq!((a + b) + (-b), a);
+    }
+
+    #[test]
+    fn test_matrix3() {
+        use crate::approx::Tolerance;
+        use crate::utils::matrix::Matrix3;
+        let mut random = seeded_random(13579, -1.0, 1.0);
+        let mut random_matrix = || {
+            Matrix3::new([
+                [random(), random(), random()],
+                [random(), random(), random()],
+                [random(), random(), random()],
+            ])
+        };
+        let tol = Tolerance::new(1E-9, 1E-9);
+        for _ in 0..100 {
+            let (a, b) = (random_matrix(), random_matrix());
+            let x = a.row_vectors()[0] + b.row_vectors()[2];
+            // Every entry of the inverse must be right, not just the ones a diagonal matrix exercises
+            assert!(tol.approx_eq(&(a * a.inverse().unwrap()), &Matrix3::identity()));
+            assert!(tol.approx_eq(&(a.inverse().unwrap() * a), &Matrix3::identity()));
+            assert!(tol.approx_eq(&a.solve(a * x).unwrap(), &x));
+            assert!(tol.approx_eq(&a.solve(x).unwrap(), &(a.inverse().unwrap() * x)));
+            // The usual identities of the product, transpose and trace
+            assert!(tol.approx_eq(&(a * b).transpose(), &(b.transpose() * a.transpose())));
+            assert!(tol.approx_eq(&((a * b) * x), &(a * (b * x))));
+            assert!(tol.approx_eq(&(a * b).trace(), &(b * a).trace()));
+            assert!(tol.approx_eq(&(a * b).determinant(), &(a.determinant() * b.determinant())));
+            assert!(tol.approx_eq(&(a + b - b), &a));
+            assert_eq!(a * Matrix3::identity(), a);
+            assert_eq!(a.transpose().transpose(), a);
+            assert_eq!(a - a, Matrix3::zero());
+        }
+        let (u, v, w) = (
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(-1.0, 0.5, 2.0),
+            Vector3::new(0.0, 4.0, -2.0),
+        );
+        let by_columns = Matrix3::from_columns([u, v, w]);
+        assert_eq!(by_columns.column_vectors(), vec![u, v, w]);
+        assert_eq!(by_columns, Matrix3::from_rows([u, v, w]).transpose());
+        assert_eq!(by_columns * Vector3::j_hat(), v);
+        // The cross product matrix squared is the outer product minus the squared length
+        let skew = Matrix3::skew(&u);
+        assert_eq!(
+            skew * skew,
+            Matrix3::outer(&u, &u) - Matrix3::identity() * u.dot(&u)
+        );
+        assert_eq!(skew * u, Vector3::zero());
+        assert_eq!(Matrix3::outer(&u, &v).transpose(), Matrix3::outer(&v, &u));
+        assert_eq!(Matrix3::skew(&u).solve(v), None);
+
+        // NaN entries come out as NaN results rather than panics while pivoting or sorting
+        let mut poisoned = by_columns.data;
+        poisoned[1][2] = f64::NAN;
+        let poisoned = Matrix3::new(poisoned);
+        assert!(poisoned.solve(u).is_some_and(|x| x.x.is_nan()));
+        assert!(
+            poisoned
+                .symmetric_eigen()
+                .0
+                .as_array()
+                .iter()
+                .any(|e| e.is_nan())
+        );
+        assert!(poisoned.svd().1.as_array().iter().any(|s| s.is_nan()));
+        let mut poisoned4 = crate::utils::matrix::Matrix4::identity().data;
+        poisoned4[3][0] = f64::NAN;
+        let poisoned4 = crate::utils::matrix::Matrix4::new(poisoned4);
+        assert!(poisoned4.determinant().is_nan());
+        assert!(poisoned4.inverse().is_some_and(|m| m.data[3][0].is_nan()));
+    }
+
+    #[test]
+    fn test_affine() {
+        use crate::affine3::Affine3;
+        use crate::approx::Tolerance;
+        use crate::pose3::Pose3;
+        use crate::rotation3::Rotation3;
+        use crate::utils::matrix::{Matrix3, Matrix4};
+        let mut random = seeded_random(112358, -1.0, 1.0);
+        let mut random_vector = || Vector3::new(random(), random(), random());
+        let tol = Tolerance::new(1E-9, 1E-9);
+        for _ in 0..50 {
+            let a = Affine3::new(
+                Matrix3::from_rows([random_vector(), random_vector(), random_vector()]),
+                random_vector(),
+            );
+            let b = Affine3::new(
+                Matrix3::from_columns([random_vector(), random_vector(), random_vector()]),
+                random_vector(),
+            );
+            let p = random_vector();
+            // Composing is applying one after the other, and matches the product of the 4x4 matrices
+            assert!(tol.approx_eq(
+                &(a * b).transform_point(p),
+                &a.transform_point(b.transform_point(p))
+            ));
+            assert!(tol.approx_eq(&(a * b).to_matrix4(), &(a.to_matrix4() * b.to_matrix4())));
+            assert!(tol.approx_eq(
+                &a.to_matrix4().transform_point(p).unwrap(),
+                &a.transform_point(p)
+            ));
+            assert_eq!(Affine3::from_matrix4(&a.to_matrix4()), Ok(a));
+            // Inverting undoes, both for the transform and for its matrix
+            let inverse = a.inverse().unwrap();
+            assert!(tol.approx_eq(&inverse.transform_point(a.transform_point(p)), &p));
+            assert!(tol.approx_eq(&inverse.to_matrix4(), &a.to_matrix4().inverse().unwrap()));
+            assert!(tol.approx_eq(&(a * inverse), &Affine3::identity()));
+            assert!(tol.approx_eq(
+                &(a.to_matrix4() * b.to_matrix4()).determinant(),
+                &(a.determinant() * b.determinant())
+            ));
+            // Normals stay perpendicular to the directions in their surface
+            let (d, n) = (random_vector(), random_vector());
+            let in_surface = d.reject_from(&n);
+            let moved = a.transform_direction(in_surface);
+            assert!(moved.dot(&a.transform_normal(n).unwrap()).abs() < 1E-9);
+            // Mirroring turns the volume of a tetrahedron inside out
+            let corners = [
+                random_vector(),
+                random_vector(),
+                random_vector(),
+                random_vector(),
+            ];
+            let volume = |t: Affine3| {
+                let [w, x, y, z] = corners.map(|c| t.transform_point(c));
+                Tetrahedron::from_points(w, x, y, z).volume()
+            };
+            let ratio = volume(a) / volume(Affine3::identity());
+            assert!(tol.approx_eq(&ratio, &a.determinant()));
+            assert_eq!(ratio < 0.0, a.is_mirroring());
+        }
+        let projection = Matrix4::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ]);
+        assert!(Affine3::from_matrix4(&projection).is_err());
+        assert_eq!(projection.determinant(), 0.0);
+        assert_eq!(projection.inverse(), None);
+
+        // Poses are rigid affine transforms, composed the same way
+        let turn = |v: Vector3| Rotation3::from_axis_angle(v);
+        let a = Pose3::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            turn(Vector3::new(0.3, -0.2, 0.9)),
+        );
+        let b = Pose3::new(
+            Vector3::new(-2.0, 0.5, 1.0),
+            turn(Vector3::new(1.1, 0.4, 0.0)),
+        );
+        let p = Vector3::new(0.25, -4.0, 2.0);
+        let local = Pose3::new(p, Rotation3::identity());
+        assert_eq!(Affine3::from(a).transform_point(p), (a + local).position);
+        assert!(tol.approx_eq(
+            &Affine3::from(a + b),
+            &(Affine3::from(a) * Affine3::from(b))
+        ));
+        assert!(tol.approx_eq(&Affine3::from(-a), &Affine3::from(a).inverse().unwrap()));
+        assert!(tol.approx_eq(&(b + (a - b)), &a));
+        assert!(tol.approx_eq(&Affine3::from(a).determinant(), &1.0));
+        let r = a.orientation.to_matrix();
+        assert!(tol.approx_eq(&(r * r.transpose()), &Matrix3::identity()));
+        assert_eq!(r * p, a.orientation.rotate_vector(p));
+        assert_eq!(Affine3::from(a.orientation).transform_point(p), r * p);
+    }
+
+    #[test]
+    fn test_eigen_svd() {
+        use crate::approx::Tolerance;
+        use crate::rotation3::Rotation3;
+        use crate::utils::matrix::Matrix3;
+        let mut random = seeded_random(31415, -1.0, 1.0);
+        let mut random_vector = || Vector3::new(random(), random(), random());
+        let tol = Tolerance::new(1E-9, 1E-9);
+        let diagonal =
+            |d: Vector3| Matrix3::new([[d.x, 0.0, 0.0], [0.0, d.y, 0.0], [0.0, 0.0, d.z]]);
+        let check_eigen = |m: Matrix3| {
+            let (values, vectors) = m.symmetric_eigen();
+            assert!(values.x >= values.y && values.y >= values.z);
+            assert!(tol.approx_eq(&(vectors.transpose() * vectors), &Matrix3::identity()));
+            assert!(tol.approx_eq(&vectors.determinant(), &1.0));
+            assert!(tol.approx_eq(&(vectors * diagonal(values) * vectors.transpose()), &m));
+        };
+        let check_svd = |m: Matrix3| {
+            let (u, s, v) = m.svd();
+            assert!(s.x >= s.y && s.y >= s.z && s.z >= 0.0);
+            assert!(tol.approx_eq(&(u.transpose() * u), &Matrix3::identity()));
+            assert!(tol.approx_eq(&(v.transpose() * v), &Matrix3::identity()));
+            assert!(tol.approx_eq(&(u * diagonal(s) * v.transpose()), &m));
+            s
+        };
+        for _ in 0..100 {
+            let b = Matrix3::from_rows([random_vector(), random_vector(), random_vector()]);
+            check_eigen(b + b.transpose());
+            check_svd(b);
+            // Repeated and zero eigenvalues, in a random frame
+            let turn = Rotation3::from_axis_angle(random_vector() * 3.0).to_matrix();
+            for values in [
+                Vector3::new(2.0, 2.0, 5.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::zero(),
+            ] {
+                let m = turn * diagonal(values) * turn.transpose();
+                check_eigen(m);
+                let (sorted, _) = m.symmetric_eigen();
+                assert!(tol.approx_eq(
+                    &sorted.as_array().iter().sum::<f64>(),
+                    &values.as_array().iter().sum::<f64>()
+                ));
+            }
+            // Rank deficient matrices still get a complete orthonormal u
+            let (p, q) = (random_vector(), random_vector());
+            let s = check_svd(Matrix3::outer(&p, &q));
+            assert!(tol.approx_eq(&s.x, &(p.magnitude() * q.magnitude())));
+            assert!(s.y < 1E-12);
+            check_svd(Matrix3::outer(&p, &q) + Matrix3::outer(&q, &random_vector()));
+        }
+        assert_eq!(check_svd(Matrix3::zero()), Vector3::zero());
+        // Small singular values keep their relative accuracy, which squaring the matrix would lose
+        let turn = Rotation3::from_axis_angle(Vector3::new(0.3, -1.2, 0.5)).to_matrix();
+        let tiny = turn * diagonal(Vector3::new(1.0, 1E-4, 1E-9)) * turn.transpose();
+        let s = check_svd(tiny);
+        assert!((s.z / 1E-9 - 1.0).abs() < 1E-6);
+
+        // Principal axes of inertia of points spread along a tilted line lead along it
+        let axis = Vector3::new(1.0, 2.0, -2.0).hat();
+        let points: Vec<Vector3> = (0..200)
+            .map(|_| axis * (random_vector().x * 10.0) + random_vector() * 0.1)
+            .collect();
+        let centroid = points.iter().sum::<Vector3>() / points.len() as f64;
+        let spread = points
+            .iter()
+            .map(|p| Matrix3::outer(&(*p - centroid), &(*p - centroid)))
+            .fold(Matrix3::zero(), |a, b| a + b);
+        let (_, vectors) = spread.symmetric_eigen();
+        assert!(vectors.column_vectors()[0].dot(&axis).abs() > 0.9999);
+
+        // Kabsch registration recovers a rotation from matched points, even a mirrored fit is refused
+        let rotation = Rotation3::from_axis_angle(Vector3::new(0.4, 1.0, -0.7));
+        let offset = Vector3::new(3.0, -1.0, 2.0);
+        let moved: Vec<Vector3> = points
+            .iter()
+            .map(|p| rotation.rotate_vector(*p) + offset)
+            .collect();
+        let moved_centroid = moved.iter().sum::<Vector3>() / moved.len() as f64;
+        let covariance = points
+            .iter()
+            .zip(&moved)
+            .map(|(p, q)| Matrix3::outer(&(*p - centroid), &(*q - moved_centroid)))
+            .fold(Matrix3::zero(), |a, b| a + b);
+        let (u, _, v) = covariance.svd();
+        let d = (v * u.transpose()).determinant().signum();
+        let recovered = v * diagonal(Vector3::new(1.0, 1.0, d)) * u.transpose();
+        assert!(tol.approx_eq(&recovered, &rotation.to_matrix()));
+        // Polar decomposition splits a matrix into a rotation and a stretch
+        let b = Matrix3::from_rows([random_vector(), random_vector(), random_vector()]);
+        let (u, s, v) = b.svd();
+        let (orthogonal, stretch) = (u * v.transpose(), v * diagonal(s) * v.transpose());
+        assert!(tol.approx_eq(&(orthogonal * stretch), &b));
+        assert!(tol.approx_eq(&stretch, &stretch.transpose()));
+        assert!(tol.approx_eq(&(orthogonal.transpose() * orthogonal), &Matrix3::identity()));
+    }
+
+    #[test]
+    fn test_matrix_n() {
+        use crate::approx::Tolerance;
+        use crate::utils::matrix::Matrix4;
+        use crate::utils::matrix_n::{FixedMatrix, MatrixN, MatrixNf};
+        let mut random = seeded_random(271828, -1.0, 1.0);
+        let tol = Tolerance::new(1E-9, 1E-9);
+        for size in 1..7 {
+            let a = MatrixN::from_fn(size, size, |_, _| random());
+            let x: Vec<f64> = (0..size).map(|_| random()).collect();
+            let lu = a.lu().unwrap();
+            assert!(tol.approx_eq(&(&lu.get_l() * &lu.get_u()), &(&lu.get_p() * &a)));
+            assert!(tol.approx_eq(&a.solve(&a.mul_vector(&x)).unwrap(), &x));
+            assert!(tol.approx_eq(&(&a * &a.inverse().unwrap()), &MatrixN::identity(size)));
+            // Positive definite, so Cholesky and LU agree
+            let spd = &(&a.transpose() * &a) + &MatrixN::identity(size);
+            let l = spd.cholesky().unwrap().get_l();
+            assert!(tol.approx_eq(&(&l * &l.transpose()), &spd));
+            let rhs = spd.mul_vector(&x);
+            assert!(tol.approx_eq(&spd.cholesky().unwrap().solve(&rhs), &x));
+            assert!(
+                tol.approx_eq(
+                    &spd.determinant(),
+                    &l.get_data()
+                        .iter()
+                        .step_by(size + 1)
+                        .map(|d| d * d)
+                        .product()
+                )
+            );
+            // Least squares leaves a residual at right angles to every column
+            let tall = MatrixN::from_fn(size + 3, size, |_, _| random());
+            let b: Vec<f64> = (0..size + 3).map(|_| random()).collect();
+            let qr = tall.qr().unwrap();
+            assert!(qr.is_full_rank());
+            assert!(tol.approx_eq(&(&qr.get_q() * &qr.get_r()), &tall));
+            assert!(tol.approx_eq(
+                &(&qr.get_q().transpose() * &qr.get_q()),
+                &MatrixN::identity(size)
+            ));
+            let fit = tall.least_squares(&b).unwrap();
+            let residual: Vec<f64> = tall
+                .mul_vector(&fit)
+                .iter()
+                .zip(&b)
+                .map(|(p, q)| p - q)
+                .collect();
+            assert!(tol.approx_eq(&tall.transpose().mul_vector(&residual), &vec![0.0; size]));
+            let normal = &tall.transpose() * &tall;
+            assert!(tol.approx_eq(
+                &normal.solve(&tall.transpose().mul_vector(&b)).unwrap(),
+                &fit
+            ));
+        }
+        // The fixed size matrices give the same answers
+        let rows: [[f64; 4]; 4] = std::array::from_fn(|_| std::array::from_fn(|_| random()));
+        let (fixed, dynamic) = (FixedMatrix::new(rows), MatrixN::from_rows(&rows).unwrap());
+        assert_eq!(MatrixN::from(fixed), dynamic);
+        assert!(tol.approx_eq(&fixed.determinant(), &dynamic.determinant()));
+        assert!(tol.approx_eq(&fixed.determinant(), &Matrix4::new(rows).determinant()));
+        assert!(tol.approx_eq(
+            &MatrixN::from(fixed.inverse().unwrap()),
+            &dynamic.inverse().unwrap()
+        ));
+        assert!(tol.approx_eq(
+            &fixed.solve([1.0, 2.0, 3.0, 4.0]).unwrap().to_vec(),
+            &dynamic.solve(&[1.0, 2.0, 3.0, 4.0]).unwrap()
+        ));
+        let spd = fixed.transpose() * fixed + FixedMatrix::identity();
+        let l = spd.cholesky().unwrap();
+        assert!(tol.approx_eq(&(l * l.transpose()), &spd));
+        let tall: FixedMatrix<6, 3> = FixedMatrix::from_fn(|_, _| random());
+        let b = [1.0, -1.0, 2.0, 0.5, 0.0, 3.0];
+        assert!(tol.approx_eq(
+            &tall.least_squares(b).unwrap().to_vec(),
+            &MatrixN::from(tall).least_squares(&b).unwrap()
+        ));
+        assert_eq!(FixedMatrix::<2, 3>::zero().least_squares([1.0, 2.0]), None);
+
+        // Degenerate input gives None rather than a meaningless answer
+        // The third column is the sum of the other two
+        let dependent = MatrixN::from_fn(5, 3, |i, j| match j {
+            0 => (i * i) as f64,
+            1 => i as f64 + 0.5,
+            _ => (i * i + i) as f64 + 0.5,
+        });
+        assert!(!dependent.qr().unwrap().is_full_rank());
+        assert_eq!(dependent.least_squares(&[1.0; 5]), None);
+        assert!(MatrixN::zero(2, 3).qr().is_none());
+        assert!(MatrixN::zero(3, 3).least_squares(&[1.0; 3]).is_none());
+        assert!(MatrixN::zero(2, 3).lu().is_none());
+        assert_eq!(MatrixN::zero(3, 3).determinant(), 0.0);
+        assert!(
+            MatrixN::from_rows(&[[1.0, 0.0], [0.0, -1.0]])
+                .unwrap()
+                .cholesky()
+                .is_none()
+        );
+        assert!(MatrixN::from_rows(&[vec![1.0, 2.0], vec![3.0]]).is_err());
+
+        // Fitting a sphere, x² + y² + z² + dx + ey + fz + g = 0, is linear in its coefficients
+        let (center, radius) = (Vector3::new(1.0, -2.0, 3.0), 2.5);
+        let points: Vec<Vector3> = (0..30)
+            .map(|_| center + Vector3::new(random(), random(), random()).with_magnitude(radius))
+            .collect();
+        let design = MatrixN::from_fn(points.len(), 4, |i, j| match j {
+            0 => points[i].x,
+            1 => points[i].y,
+            2 => points[i].z,
+            _ => 1.0,
+        });
+        let rhs: Vec<f64> = points.iter().map(|p| -p.dot(p)).collect();
+        let fit = design.least_squares(&rhs).unwrap();
+        let found = Vector3::new(fit[0], fit[1], fit[2]) * -0.5;
+        assert!(tol.approx_eq(&found, &center));
+        assert!(tol.approx_eq(&(found.dot(&found) - fit[3]).sqrt(), &radius));
+        // And so is a quadratic surface z = ax² + bxy + cy² + dx + ey + f
+        let coefficients = [0.5, -1.0, 2.0, 3.0, 0.25, -4.0];
+        let terms = |x: f64, y: f64| [x * x, x * y, y * y, x, y, 1.0];
+        let samples: Vec<(f64, f64)> = (0..20).map(|_| (random() * 3.0, random() * 3.0)).collect();
+        let design = MatrixN::from_rows(
+            &samples
+                .iter()
+                .map(|&(x, y)| terms(x, y))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let heights: Vec<f64> = samples
+            .iter()
+            .map(|&(x, y)| {
+                terms(x, y)
+                    .iter()
+                    .zip(coefficients)
+                    .map(|(t, c)| t * c)
+                    .sum()
+            })
+            .collect();
+        assert!(tol.approx_eq(
+            &design.least_squares(&heights).unwrap(),
+            &coefficients.to_vec()
+        ));
+
+        // Single precision works the same way
+        let narrow = MatrixNf::from_rows(&[[4.0, 1.0], [1.0, 3.0]]).unwrap();
+        let solved = narrow.solve(&[5.0, 4.0]).unwrap();
+        assert!(Tolerance::absolute(1E-6).approx_eq(&solved, &vec![1.0f32, 1.0]));
+        assert_eq!(
+            MatrixN::from(narrow).solve(&[5.0, 4.0]),
+            Some(vec![1.0, 1.0])
+        );
+
+        // NaN entries come out as NaN results rather than panics while pivoting
+        let poisoned = MatrixN::from_fn(3, 3, |i, j| {
+            if (i, j) == (2, 0) {
+                f64::NAN
+            } else {
+                (i + j) as f64 + 1.0
+            }
+        });
+        assert!(poisoned.determinant().is_nan());
+        assert!(
+            poisoned
+                .solve(&[1.0, 2.0, 3.0])
+                .is_some_and(|x| x.iter().all(|v| v.is_nan()))
+        );
+        assert!(
+            poisoned
+                .inverse()
+                .is_some_and(|m| m.get_data().iter().any(|v| v.is_nan()))
+        );
+        let fixed = FixedMatrix::<3, 3>::from_fn(|i, j| poisoned.row(i)[j]);
+        assert!(fixed.determinant().is_nan());
+        assert!(fixed.solve([1.0, 2.0, 3.0]).is_some_and(|x| x[0].is_nan()));
+    }
+
+    #[test]
+    fn test_plane_fit() {
+        use crate::approx::Tolerance;
+        use crate::simple_plane::{SimplePlane, SimplePlanef};
+        use crate::vectors::Vector3f;
+        let mut random = seeded_random(161803, -1.0, 1.0);
+        let tol = Tolerance::new(1E-9, 1E-9);
+        for _ in 0..50 {
+            // Points scattered over a random plane, including vertical ones, recover it exactly
+            let normal = Vector3::new(random(), random(), random()).hat();
+            let origin = Vector3::new(random(), random(), random()) * 10.0;
+            let (u, v) = (
+                normal
+                    .cross(&Vector3::new(random(), random(), random()))
+                    .hat(),
+                normal
+                    .cross(&Vector3::new(random(), random(), random()))
+                    .hat(),
+            );
+            let on_plane: Vec<Vector3> = (0..20)
+                .map(|_| origin + u * (random() * 5.0) + v * (random() * 5.0))
+                .collect();
+            let fit = SimplePlane::fit(&on_plane).unwrap();
+            assert!(tol.approx_eq(&fit.plane.normal.dot(&normal).abs(), &1.0));
+            assert!(fit.rms < 1E-9);
+            assert!(fit.residuals.iter().all(|r| r.abs() < 1E-9));
+            // Noise along the normal shows up in the residuals
+            let offsets: Vec<f64> = (0..20).map(|_| random() * 0.01).collect();
+            let noisy: Vec<Vector3> = on_plane
+                .iter()
+                .zip(&offsets)
+                .map(|(p, d)| *p + normal * *d)
+                .collect();
+            let fit = SimplePlane::fit(&noisy).unwrap();
+            assert!(fit.plane.normal.dot(&normal).abs() > 0.999);
+            // No plane fits better than the one the points came from
+            let mean = offsets.iter().sum::<f64>() / 20.0;
+            let true_rms = (offsets.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / 20.0).sqrt();
+            assert!(fit.rms <= true_rms && fit.rms > 0.5 * true_rms);
+            let rms = (fit.residuals.iter().map(|r| r * r).sum::<f64>() / 20.0).sqrt();
+            assert!(tol.approx_eq(&fit.rms, &rms));
+            assert!(tol.approx_eq(&fit.residuals.iter().sum::<f64>(), &0.0));
+        }
+        // Points exactly on a plane with a nonzero intercept come back as that plane
+        let points: Vec<Vector3> = [(0.0, 0.0), (3.0, 1.0), (1.0, 4.0), (5.0, 5.0), (2.0, -3.0)]
+            .iter()
+            .map(|&(x, y)| Vector3::new(x, y, 0.5 * x - 2.0 * y + 7.0))
+            .collect();
+        let (plane, error) = SimplePlane::regress(&points);
+        assert!(error < 1E-18);
+        assert_eq!(plane, SimplePlane::from_mxb(0.5, -2.0, 7.0));
+        // The same plane as regress where it can represent it
+        let points: Vec<Vector3> = (0..10)
+            .map(|i| {
+                let (x, y) = (i as f64, (i * i % 7) as f64);
+                Vector3::new(x, y, 0.5 * x - 2.0 * y + 7.0)
+            })
+            .collect();
+        let fit = SimplePlane::fit(&points).unwrap();
+        let (regressed, _) = SimplePlane::regress(&points);
+        assert!(tol.approx_eq(&fit.plane.normal.dot(&regressed.normal).abs(), &1.0));
+        assert!(fit.plane.point_intersects_within(regressed.origin, tol));
+        assert!(fit.plane.normal.z > 0.0);
+        // Weights pull the fit toward the heavier points, and scaling them all changes nothing
+        let mut bent = points.clone();
+        bent.push(Vector3::new(4.0, 4.0, 100.0));
+        let mut weights = vec![1.0; 11];
+        let bent_fit = SimplePlane::fit_weighted(&bent, &weights).unwrap();
+        assert!(bent_fit.rms > 1.0);
+        weights[10] = 0.0;
+        let ignored = SimplePlane::fit_weighted(&bent, &weights).unwrap();
+        assert!(tol.approx_eq(&ignored.plane.normal, &fit.plane.normal));
+        assert!(ignored.residuals[10] > 40.0);
+        let scaled: Vec<f64> = weights.iter().map(|w| w * 1000.0).collect();
+        let same = SimplePlane::fit_weighted(&bent, &scaled).unwrap();
+        assert!(tol.approx_eq(&same.plane, &ignored.plane));
+        assert!(tol.approx_eq(&same.rms, &ignored.rms));
+
+        // Degenerate input is an error rather than a panic
+        let line: Vec<Vector3> = (0..5)
+            .map(|i| Vector3::new(1.0, 2.0, 3.0) * i as f64)
+            .collect();
+        assert!(SimplePlane::fit(&line).is_err());
+        assert!(SimplePlane::fit(&[Vector3::new(1.0, 1.0, 1.0); 4]).is_err());
+        assert!(SimplePlane::fit(&points[..2]).is_err());
+        assert!(SimplePlane::fit(&[]).is_err());
+        assert!(SimplePlane::fit_weighted(&points, &[1.0; 3]).is_err());
+        assert!(SimplePlane::fit_weighted(&points[..3], &[1.0, -1.0, 1.0]).is_err());
+        assert!(SimplePlane::fit_weighted(&points[..3], &[1.0, f64::NAN, 1.0]).is_err());
+        assert!(SimplePlane::fit_weighted(&points[..4], &[1.0, 1.0, 0.0, 0.0]).is_err());
+        let mut broken = points.clone();
+        broken[3].y = f64::INFINITY;
+        assert!(SimplePlane::fit(&broken).is_err());
+        broken[3].y = f64::NAN;
+        assert!(SimplePlane::fit(&broken).is_err());
+        let mut weights = vec![1.0; broken.len()];
+        weights[3] = 0.0;
+        let skipped = SimplePlane::fit_weighted(&broken, &weights).unwrap();
+        assert!(skipped.rms < 1E-9 && skipped.residuals[3].is_nan());
+
+        // Single precision finds a tilted plane too
+        let narrow: Vec<Vector3f> = points.iter().map(|p| p.cast()).collect();
+        let fit_narrow = SimplePlanef::fit(&narrow).unwrap();
+        let widened = Vector3::from(fit_narrow.plane.normal);
+        assert!(widened.dot(&fit.plane.normal) > 0.99999);
+    }
 }
@@ -1,28 +1,144 @@
-use crate::vectors::Vector3;
+use crate::approx::{Tolerance, impl_approx_eq};
+use crate::predicates::orient2d;
+use crate::scalar::Float;
+use crate::vectors::{Vec2, Vec3};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct Line {
-    pub origin: Vector3,
-    pub direction: Vector3,
+pub struct LineOf<T: Float> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
 }
+pub type Line = LineOf<f64>;
+pub type Linef = LineOf<f32>;
+impl_approx_eq!(<T> LineOf<T>, origin, direction);
 
-impl Line {
-    pub fn new(origin: Vector3, direction2: Vector3) -> Line {
+impl<T: Float> LineOf<T> {
+    pub fn new(origin: Vec3<T>, direction2: Vec3<T>) -> LineOf<T> {
         let direction = direction2.hat();
-        Line { origin, direction }
+        LineOf { origin, direction }
     }
-    pub fn at(&self, t: f64) -> Vector3 {
+    pub fn at(&self, t: T) -> Vec3<T> {
         self.origin + (self.direction * t)
     }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct LineSegment {
-    pub a: Vector3,
-    pub b: Vector3,
+pub struct LineSegmentOf<T: Float> {
+    pub a: Vec3<T>,
+    pub b: Vec3<T>,
 }
-impl LineSegment {
-    pub fn new(a: Vector3, b: Vector3) -> LineSegment {
-        LineSegment { a, b }
+pub type LineSegment = LineSegmentOf<f64>;
+pub type LineSegmentf = LineSegmentOf<f32>;
+impl_approx_eq!(<T> LineSegmentOf<T>, a, b);
+impl<T: Float> LineSegmentOf<T> {
+    pub fn new(a: Vec3<T>, b: Vec3<T>) -> LineSegmentOf<T> {
+        LineSegmentOf { a, b }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct LineSegment2Of<T: Float> {
+    pub a: Vec2<T>,
+    pub b: Vec2<T>,
+}
+pub type LineSegment2 = LineSegment2Of<f64>;
+pub type LineSegment2f = LineSegment2Of<f32>;
+impl_approx_eq!(<T> LineSegment2Of<T>, a, b);
+impl<T: Float> LineSegment2Of<T> {
+    pub fn new(a: Vec2<T>, b: Vec2<T>) -> LineSegment2Of<T> {
+        LineSegment2Of { a, b }
+    }
+    pub fn length(&self) -> T {
+        self.a.dist_to(&self.b)
+    }
+    /// Determines if a point lies on the segment, including its ends
+    pub fn point_intersects(&self, pt: Vec2<T>) -> bool {
+        orient2d(self.a.cast(), self.b.cast(), pt.cast()) == 0.0
+            && pt.x >= self.a.x.min(self.b.x)
+            && pt.x <= self.a.x.max(self.b.x)
+            && pt.y >= self.a.y.min(self.b.y)
+            && pt.y <= self.a.y.max(self.b.y)
+    }
+    /// The shortest distance from a point to the segment
+    pub fn distance_to(&self, pt: Vec2<T>) -> T {
+        let along = self.b - self.a;
+        let length_squared = along.dot(&along);
+        if length_squared == T::ZERO {
+            return pt.dist_to(&self.a);
+        }
+        let t = ((pt - self.a).dot(&along) / length_squared)
+            .max(T::ZERO)
+            .min(T::ONE);
+        pt.dist_to(&(self.a + along * t))
+    }
+    /// Determines if a point is on the segment, or within the tolerance of it. The relative part of
+    /// the tolerance scales with the size of the coordinates involved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::line::LineSegment2;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let s = LineSegment2::new(Vector2::new(1E6, 0.0), Vector2::new(1E6, 1.0));
+    /// let pt = Vector2::new(1E6 + 1E-7, 0.5);
+    /// assert!(!s.point_intersects(pt));
+    /// assert!(!s.point_intersects_within(pt, Tolerance::absolute(1E-9)));
+    /// assert!(s.point_intersects_within(pt, Tolerance::relative(1E-12)));
+    /// ```
+    pub fn point_intersects_within(&self, pt: Vec2<T>, tolerance: Tolerance) -> bool {
+        let scale = [self.a, self.b, pt]
+            .iter()
+            .map(|p| p.x.abs().max(p.y.abs()).to_f64())
+            .fold(0.0, f64::max);
+        self.point_intersects(pt) || tolerance.is_zero(self.distance_to(pt).to_f64(), scale)
+    }
+    /// Determines if two segments share any point, including touching at an end or overlapping
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::line::LineSegment2;
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let s = LineSegment2::new(Vector2::new(0.0, 0.0), Vector2::new(2.0, 2.0));
+    /// assert!(s.intersects(&LineSegment2::new(Vector2::new(0.0, 2.0), Vector2::new(2.0, 0.0))));
+    /// assert!(s.intersects(&LineSegment2::new(Vector2::new(2.0, 2.0), Vector2::new(3.0, 0.0))));
+    /// assert!(s.intersects(&LineSegment2::new(Vector2::new(1.0, 1.0), Vector2::new(3.0, 3.0))));
+    /// assert!(!s.intersects(&LineSegment2::new(Vector2::new(1.0, 0.0), Vector2::new(3.0, 0.0))));
+    /// ```
+    pub fn intersects(&self, other: &LineSegment2Of<T>) -> bool {
+        let (a, b) = (self.a.cast(), self.b.cast());
+        let (c, d) = (other.a.cast(), other.b.cast());
+        let d1 = orient2d(c, d, a);
+        let d2 = orient2d(c, d, b);
+        let d3 = orient2d(a, b, c);
+        let d4 = orient2d(a, b, d);
+        if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+            && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+        {
+            return true;
+        }
+        self.point_intersects(other.a)
+            || self.point_intersects(other.b)
+            || other.point_intersects(self.a)
+            || other.point_intersects(self.b)
+    }
+    /// Finds where two segments cross, if they meet at exactly one point
+    ///
+    /// returns: Option<Vector2>, which is `None` if the segments are parallel or don't meet
+    pub fn intersection(&self, other: &LineSegment2Of<T>) -> Option<Vec2<T>> {
+        let r = self.b - self.a;
+        let s = other.b - other.a;
+        let denominator = r.cross(&s);
+        if denominator == T::ZERO {
+            return None;
+        }
+        let t = (other.a - self.a).cross(&s) / denominator;
+        let u = (other.a - self.a).cross(&r) / denominator;
+        if (T::ZERO..=T::ONE).contains(&t) && (T::ZERO..=T::ONE).contains(&u) {
+            Some(self.a + r * t)
+        } else {
+            None
+        }
     }
 }
@@ -0,0 +1,62 @@
+use crate::gon::{Polygon, ear_clip};
+use crate::simple_plane::SimplePlane;
+use crate::vectors::{Vector2, Vector3};
+
+pub mod obj;
+pub mod off;
+pub mod ply;
+pub mod stl;
+
+/// Splits a face with any number of corners into triangles with the same winding. The corners are
+/// projected onto their best-fit plane and ear clipped there, so concave faces are handled. Faces
+/// that are flat lines or whose boundary crosses itself are fanned from their first corner instead.
+pub(crate) fn triangulate_face(
+    points: &[Vector3],
+    corners: &[usize],
+) -> Vec<(usize, usize, usize)> {
+    let fan = || {
+        (1..corners.len().saturating_sub(1))
+            .map(|i| (corners[0], corners[i], corners[i + 1]))
+            .collect()
+    };
+    if corners.len() <= 3 {
+        return fan();
+    }
+    let positions: Vec<Vector3> = corners.iter().map(|c| points[*c]).collect();
+    let Ok(fit) = SimplePlane::fit(&positions) else {
+        return fan();
+    };
+    let normal = fit.plane.normal;
+    // Any axis that isn't nearly parallel to the normal gives a basis for the plane
+    let axis = if normal.x.abs() < 0.5 {
+        Vector3::i_hat()
+    } else {
+        Vector3::j_hat()
+    };
+    let u = normal.cross(&axis).hat();
+    let v = normal.cross(&u);
+    let mut order: Vec<usize> = (0..corners.len()).collect();
+    let mut flat: Vec<Vector2> = positions
+        .iter()
+        .map(|p| {
+            let d = *p - fit.plane.origin;
+            Vector2::new(d.dot(&u), d.dot(&v))
+        })
+        .collect();
+    // Ear clipping needs counterclockwise corners, and the triangles are turned back afterwards
+    let clockwise = Polygon::signed_area_of(&flat) < 0.0;
+    if clockwise {
+        order.reverse();
+        flat.reverse();
+    }
+    match ear_clip(&flat) {
+        Ok(triangles) => triangles
+            .into_iter()
+            .map(|(a, b, c)| {
+                let (a, b, c) = (corners[order[a]], corners[order[b]], corners[order[c]]);
+                if clockwise { (c, b, a) } else { (a, b, c) }
+            })
+            .collect(),
+        Err(_) => fan(),
+    }
+}
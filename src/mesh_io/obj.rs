@@ -0,0 +1,309 @@
+use crate::hedron::Polyhedron;
+use crate::mesh_io::triangulate_face;
+use crate::vectors::Vector3;
+use std::fmt;
+
+/// What went wrong while reading an OBJ file
+#[derive(Debug, PartialEq, Clone)]
+pub enum ObjErrorKind {
+    /// A coordinate could not be read as a number
+    InvalidNumber(String),
+    /// A `v` line had fewer than three coordinates
+    MissingCoordinates,
+    /// A face corner could not be read as a vertex reference
+    InvalidIndex(String),
+    /// Index 0 is not valid, since OBJ indices start at 1
+    ZeroIndex,
+    /// A face referenced a vertex that has not been defined yet
+    IndexOutOfRange(i64),
+    /// A face had fewer than three corners
+    TooFewCorners(usize),
+}
+
+/// An error while reading an OBJ file, with the (1-based) line it occurred on
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjError {
+    pub line: usize,
+    pub kind: ObjErrorKind,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ObjErrorKind::InvalidNumber(s) => write!(f, "'{s}' is not a number"),
+            ObjErrorKind::MissingCoordinates => write!(f, "a vertex needs x, y and z coordinates"),
+            ObjErrorKind::InvalidIndex(s) => write!(f, "'{s}' is not a vertex reference"),
+            ObjErrorKind::ZeroIndex => write!(f, "vertex indices start at 1"),
+            ObjErrorKind::IndexOutOfRange(i) => write!(f, "vertex {i} has not been defined"),
+            ObjErrorKind::TooFewCorners(n) => {
+                write!(f, "a face needs at least 3 corners, but this one has {n}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+/// A named object (`o` statement) or group (`g` statement) from an OBJ file
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjObject {
+    /// The object's name, or an empty string for faces before the first `o` or `g` statement
+    pub name: String,
+    /// The object's faces, using only the vertices they reference
+    pub mesh: Polyhedron,
+}
+
+/// An object or group name with the faces (indices into the shared points) that belong to it
+type NamedFaces = (String, Vec<(usize, usize, usize)>);
+
+/// Everything read from an OBJ file
+struct ObjContents {
+    points: Vec<Vector3>,
+    objects: Vec<NamedFaces>,
+    groups: Vec<NamedFaces>,
+}
+
+/// Joins lines ending in a backslash with the line after them, keeping the number of the first line
+fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (i, raw) in text.lines().enumerate() {
+        let (number, mut line) = pending.take().unwrap_or((i + 1, String::new()));
+        match raw.strip_suffix('\\') {
+            Some(continued) => {
+                line.push_str(continued);
+                line.push(' ');
+                pending = Some((number, line));
+            }
+            None => {
+                line.push_str(raw);
+                lines.push((number, line));
+            }
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+fn parse_vertex_reference(
+    token: &str,
+    vertex_count: usize,
+    line: usize,
+) -> Result<usize, ObjError> {
+    // Only the position matters for a polyhedron, so v/vt/vn and v//vn are reduced to v
+    let position = token.split('/').next().unwrap_or("");
+    let index: i64 = position.parse().map_err(|_| ObjError {
+        line,
+        kind: ObjErrorKind::InvalidIndex(token.to_owned()),
+    })?;
+    let resolved = match index {
+        0 => {
+            return Err(ObjError {
+                line,
+                kind: ObjErrorKind::ZeroIndex,
+            });
+        }
+        // Negative indices count back from the most recently defined vertex
+        i if i < 0 => vertex_count as i64 + i,
+        i => i - 1,
+    };
+    if resolved < 0 || resolved >= vertex_count as i64 {
+        return Err(ObjError {
+            line,
+            kind: ObjErrorKind::IndexOutOfRange(index),
+        });
+    }
+    Ok(resolved as usize)
+}
+
+fn parse(text: &str) -> Result<ObjContents, ObjError> {
+    let mut points = Vec::new();
+    let mut objects: Vec<NamedFaces> = vec![(String::new(), Vec::new())];
+    let mut groups: Vec<NamedFaces> = vec![(String::new(), Vec::new())];
+    // A face belongs to every group named on the last `g` line
+    let mut current_groups = vec![0];
+    for (line, content) in logical_lines(text) {
+        let content = content.split('#').next().unwrap_or("");
+        let mut tokens = content.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let coords = tokens
+                    .take(3)
+                    .map(|t| {
+                        t.parse::<f64>().map_err(|_| ObjError {
+                            line,
+                            kind: ObjErrorKind::InvalidNumber(t.to_owned()),
+                        })
+                    })
+                    .collect::<Result<Vec<f64>, ObjError>>()?;
+                if coords.len() < 3 {
+                    return Err(ObjError {
+                        line,
+                        kind: ObjErrorKind::MissingCoordinates,
+                    });
+                }
+                points.push(Vector3::new(coords[0], coords[1], coords[2]));
+            }
+            Some("f") => {
+                let corners = tokens
+                    .map(|t| parse_vertex_reference(t, points.len(), line))
+                    .collect::<Result<Vec<usize>, ObjError>>()?;
+                if corners.len() < 3 {
+                    return Err(ObjError {
+                        line,
+                        kind: ObjErrorKind::TooFewCorners(corners.len()),
+                    });
+                }
+                let triangles = triangulate_face(&points, &corners);
+                for group in &current_groups {
+                    groups[*group].1.extend_from_slice(&triangles);
+                }
+                let faces = &mut objects.last_mut().expect("there is always an object").1;
+                faces.extend(triangles);
+            }
+            Some("o") => {
+                let name = tokens.collect::<Vec<&str>>().join(" ");
+                if objects.len() == 1 && objects[0].0.is_empty() && objects[0].1.is_empty() {
+                    objects[0].0 = name;
+                } else {
+                    objects.push((name, Vec::new()));
+                }
+            }
+            Some("g") => {
+                let names: Vec<&str> = tokens.collect();
+                let names = if names.is_empty() { vec![""] } else { names };
+                // Naming a group again adds to it, unlike objects
+                current_groups = names
+                    .into_iter()
+                    .map(|name| match groups.iter().position(|g| g.0 == name) {
+                        Some(i) => i,
+                        None => {
+                            groups.push((name.to_owned(), Vec::new()));
+                            groups.len() - 1
+                        }
+                    })
+                    .collect();
+            }
+            // Texture coordinates, normals, materials, smoothing groups, lines and points carry nothing a
+            // polyhedron can store
+            _ => {}
+        }
+    }
+    if objects.len() > 1 && objects[0].0.is_empty() && objects[0].1.is_empty() {
+        objects.remove(0);
+    }
+    if groups.len() > 1 && groups[0].1.is_empty() {
+        groups.remove(0);
+    }
+    Ok(ObjContents {
+        points,
+        objects,
+        groups,
+    })
+}
+
+/// Reads a Wavefront OBJ file as a single polyhedron, keeping every vertex in file order so that
+/// [Polyhedron::get_obj] round-trips. Polygons with more than three corners are ear clipped in their
+/// best-fit plane, so they may be concave, and the result is not validated, since OBJ files often
+/// hold open surfaces.
+///
+/// # Arguments
+///
+/// * `text`: The contents of the OBJ file
+///
+/// returns: Result<Polyhedron, ObjError>
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// use YetAnotherGeometryLibrary::mesh_io::obj::{read_obj, ObjErrorKind};
+/// let cube = Polyhedron::cube();
+/// assert_eq!(read_obj(&cube.get_obj()).unwrap(), cube);
+///
+/// // An arrowhead, which a fan from the first corner would cover wrongly
+/// let quad = "v 0 0 0\nv 2 1 0\nv 0 2 0\nv 1 1 0\nf 1/1/1 2/2/1 3/3/1 -1//1 # concave\n";
+/// let arrow = read_obj(quad).unwrap();
+/// assert_eq!(arrow.get_face_indices().len(), 2);
+/// assert!(!arrow.get_face_indices().contains(&(0, 1, 2)));
+///
+/// let err = read_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n").unwrap_err();
+/// assert_eq!(err.line, 3);
+/// assert_eq!(err.kind, ObjErrorKind::IndexOutOfRange(3));
+/// ```
+pub fn read_obj(text: &str) -> Result<Polyhedron, ObjError> {
+    let contents = parse(text)?;
+    let faces = contents
+        .objects
+        .into_iter()
+        .flat_map(|(_, faces)| faces)
+        .collect();
+    Ok(Polyhedron::new_unchecked(contents.points, faces))
+}
+
+/// Reads a Wavefront OBJ file, splitting it into one polyhedron per `o` statement. Each object only
+/// keeps the vertices its faces reference, renumbered in order of first use.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::mesh_io::obj::read_obj_objects;
+/// let text = "o first\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\no second\nv 0 0 1\nf 1 3 4\n";
+/// let objects = read_obj_objects(text).unwrap();
+/// assert_eq!(objects.len(), 2);
+/// assert_eq!(objects[1].name, "second");
+/// assert_eq!(objects[1].mesh.get_points().len(), 3);
+/// assert_eq!(objects[1].mesh.get_face_indices(), &[(0, 1, 2)]);
+/// ```
+pub fn read_obj_objects(text: &str) -> Result<Vec<ObjObject>, ObjError> {
+    let contents = parse(text)?;
+    Ok(split(&contents.points, contents.objects))
+}
+
+/// Reads a Wavefront OBJ file, splitting it into one polyhedron per group named by `g` statements,
+/// like [read_obj_objects]. A face in several groups is in each of their polyhedra, and faces before
+/// the first `g` statement (or after one with no names) are in a group with an empty name.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::mesh_io::obj::read_obj_groups;
+/// let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\n\
+///             g bottom all\nf 1 3 2\ng all\nf 1 2 4\n";
+/// let groups = read_obj_groups(text).unwrap();
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].name, "bottom");
+/// assert_eq!(groups[0].mesh.get_face_indices(), &[(0, 1, 2)]);
+/// assert_eq!(groups[1].name, "all");
+/// assert_eq!(groups[1].mesh.get_face_indices().len(), 2);
+/// ```
+pub fn read_obj_groups(text: &str) -> Result<Vec<ObjObject>, ObjError> {
+    let contents = parse(text)?;
+    Ok(split(&contents.points, contents.groups))
+}
+
+/// Makes a polyhedron for each set of faces, keeping only the points they reference
+fn split(all_points: &[Vector3], named: Vec<NamedFaces>) -> Vec<ObjObject> {
+    named
+        .into_iter()
+        .map(|(name, faces)| {
+            let mut new_index: Vec<Option<usize>> = vec![None; all_points.len()];
+            let mut points = Vec::new();
+            let mut remap = |idx: usize| {
+                *new_index[idx].get_or_insert_with(|| {
+                    points.push(all_points[idx]);
+                    points.len() - 1
+                })
+            };
+            let faces = faces
+                .iter()
+                .map(|f| (remap(f.0), remap(f.1), remap(f.2)))
+                .collect();
+            ObjObject {
+                name,
+                mesh: Polyhedron::new_unchecked(points, faces),
+            }
+        })
+        .collect()
+}
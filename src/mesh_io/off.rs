@@ -0,0 +1,163 @@
+use crate::hedron::Polyhedron;
+use crate::mesh_io::triangulate_face;
+use crate::vectors::Vector3;
+use std::fmt;
+
+/// An error while reading an OFF file, with the (1-based) line it occurred on
+#[derive(Debug, PartialEq, Clone)]
+pub struct OffError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for OffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for OffError {}
+
+/// Reads an Object File Format (OFF) file. The `COFF`, `NOFF` and `STOFF` variants are read too, with
+/// their per-vertex colours, normals and texture coordinates ignored, as is the `OFF<vertices>`
+/// header some datasets (like ModelNet) write with no space after the keyword. Faces with more than
+/// three corners are ear clipped in their best-fit plane, per-face colours are ignored, and the result
+/// is not validated.
+///
+/// # Arguments
+///
+/// * `text`: The contents of the OFF file
+///
+/// returns: Result<Polyhedron, OffError>
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// use YetAnotherGeometryLibrary::mesh_io::off::{read_off, write_off};
+/// let cube = Polyhedron::cube();
+/// assert_eq!(read_off(&write_off(&cube)).unwrap(), cube);
+///
+/// let square = "OFF\n# a unit square\n4 1 0\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3 255 0 0\n";
+/// assert_eq!(read_off(square).unwrap().get_face_indices().len(), 2);
+/// assert_eq!(read_off("OFF\n4 1 0\n0 0 0\n").unwrap_err().line, 3);
+///
+/// let coloured = "COFF3 1 0\n0 0 0 255 0 0\n1 0 0 0 255 0\n0 1 0 0 0 255\n3 0 1 2\n";
+/// assert_eq!(read_off(coloured).unwrap().get_face_indices(), &[(0, 1, 2)]);
+/// assert!(read_off("OFF\n99999999999999 0 0\n").is_err());
+/// ```
+pub fn read_off(text: &str) -> Result<Polyhedron, OffError> {
+    // Comments and blank lines can appear anywhere, so work with the meaningful lines only
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, l)| !l.is_empty());
+    let last_line = text.lines().count();
+    let (first_line, header) = lines.next().ok_or(OffError {
+        line: 1,
+        message: "the file is empty".to_owned(),
+    })?;
+    let mut header_tokens = header.split_whitespace();
+    let keyword = header_tokens.next().unwrap_or("");
+    // Only the extra vertex data differs between the variants, and it comes after the coordinates
+    let glued_count = ["ST", "C", "N"]
+        .iter()
+        .fold(keyword, |k, prefix| k.strip_prefix(prefix).unwrap_or(k))
+        .strip_prefix("OFF")
+        .ok_or(OffError {
+            line: first_line,
+            message: "an OFF file must start with 'OFF', 'COFF', 'NOFF' or 'STOFF'".to_owned(),
+        })?;
+    // The counts may share a line with the keyword (even the keyword itself) or follow on the next line
+    let remaining: Vec<&str> = Some(glued_count)
+        .filter(|c| !c.is_empty())
+        .into_iter()
+        .chain(header_tokens)
+        .collect();
+    let (count_line, counts) = if remaining.is_empty() {
+        let (line, text) = lines.next().ok_or(OffError {
+            line: last_line,
+            message: "the file has no vertex and face counts".to_owned(),
+        })?;
+        (line, text.split_whitespace().collect())
+    } else {
+        (first_line, remaining)
+    };
+    let counts = counts
+        .iter()
+        .map(|t| t.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .ok()
+        .filter(|c| c.len() >= 2)
+        .ok_or(OffError {
+            line: count_line,
+            message: "expected vertex, face and edge counts".to_owned(),
+        })?;
+    let (vertex_count, face_count) = (counts[0], counts[1]);
+
+    // The counts come from the file, so they aren't trusted to size allocations
+    let mut points = Vec::new();
+    for _ in 0..vertex_count {
+        let (line, text) = lines.next().ok_or(OffError {
+            line: last_line,
+            message: format!("expected {vertex_count} vertices, found {}", points.len()),
+        })?;
+        let coords = text
+            .split_whitespace()
+            .take(3)
+            .map(|t| t.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .ok()
+            .filter(|c| c.len() == 3)
+            .ok_or(OffError {
+                line,
+                message: "expected x, y and z coordinates".to_owned(),
+            })?;
+        points.push(Vector3::new(coords[0], coords[1], coords[2]));
+    }
+    let mut faces = Vec::new();
+    for i in 0..face_count {
+        let (line, text) = lines.next().ok_or(OffError {
+            line: last_line,
+            message: format!("expected {face_count} faces, found {i}"),
+        })?;
+        let error = |message: String| OffError { line, message };
+        let values: Vec<&str> = text.split_whitespace().collect();
+        let n: usize = values
+            .first()
+            .and_then(|t| t.parse().ok())
+            .ok_or(error("expected a corner count".to_owned()))?;
+        if n < 3 {
+            return Err(error(format!("a face needs at least 3 corners, found {n}")));
+        }
+        if values.len() < n + 1 {
+            return Err(error(format!(
+                "expected {n} corners, found {}",
+                values.len() - 1
+            )));
+        }
+        // Anything after the corners is a colour
+        let corners = values[1..=n]
+            .iter()
+            .map(|t| t.parse::<usize>().ok().filter(|i| *i < vertex_count))
+            .collect::<Option<Vec<usize>>>()
+            .ok_or(error("a corner is not a valid vertex index".to_owned()))?;
+        faces.extend(triangulate_face(&points, &corners));
+    }
+    Ok(Polyhedron::new_unchecked(points, faces))
+}
+
+/// Writes a polyhedron as an Object File Format (OFF) file
+pub fn write_off(hedron: &Polyhedron) -> String {
+    let points = hedron.get_points();
+    let faces = hedron.get_face_indices();
+    let mut ret = format!("OFF\n{} {} 0\n", points.len(), faces.len());
+    for pt in points {
+        ret += format!("{} {} {}\n", pt.x, pt.y, pt.z).as_str();
+    }
+    for f in faces {
+        ret += format!("3 {} {} {}\n", f.0, f.1, f.2).as_str();
+    }
+    ret
+}
@@ -0,0 +1,520 @@
+use crate::hedron::Polyhedron;
+use crate::mesh_io::triangulate_face;
+use crate::vectors::Vector3;
+use std::fmt;
+
+/// How the body of a PLY file is encoded
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// The scalar types a PLY property can have
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PlyType {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double,
+}
+
+impl PlyType {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(PlyType::Char),
+            "uchar" | "uint8" => Some(PlyType::UChar),
+            "short" | "int16" => Some(PlyType::Short),
+            "ushort" | "uint16" => Some(PlyType::UShort),
+            "int" | "int32" => Some(PlyType::Int),
+            "uint" | "uint32" => Some(PlyType::UInt),
+            "float" | "float32" => Some(PlyType::Float),
+            "double" | "float64" => Some(PlyType::Double),
+            _ => None,
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            PlyType::Char => "char",
+            PlyType::UChar => "uchar",
+            PlyType::Short => "short",
+            PlyType::UShort => "ushort",
+            PlyType::Int => "int",
+            PlyType::UInt => "uint",
+            PlyType::Float => "float",
+            PlyType::Double => "double",
+        }
+    }
+    fn size(&self) -> usize {
+        match self {
+            PlyType::Char | PlyType::UChar => 1,
+            PlyType::Short | PlyType::UShort => 2,
+            PlyType::Int | PlyType::UInt | PlyType::Float => 4,
+            PlyType::Double => 8,
+        }
+    }
+    fn is_integer(&self) -> bool {
+        !matches!(self, PlyType::Float | PlyType::Double)
+    }
+    /// Decodes one value. Every PLY scalar fits in an f64 without loss.
+    fn decode(&self, bytes: &[u8], format: PlyFormat) -> f64 {
+        macro_rules! read {
+            ($t:ty) => {{
+                let raw: [u8; std::mem::size_of::<$t>()] = bytes.try_into().unwrap();
+                if format == PlyFormat::BinaryBigEndian {
+                    <$t>::from_be_bytes(raw) as f64
+                } else {
+                    <$t>::from_le_bytes(raw) as f64
+                }
+            }};
+        }
+        match self {
+            PlyType::Char => read!(i8),
+            PlyType::UChar => read!(u8),
+            PlyType::Short => read!(i16),
+            PlyType::UShort => read!(u16),
+            PlyType::Int => read!(i32),
+            PlyType::UInt => read!(u32),
+            PlyType::Float => read!(f32),
+            PlyType::Double => read!(f64),
+        }
+    }
+    fn encode(&self, value: f64, format: PlyFormat, out: &mut Vec<u8>) {
+        macro_rules! write {
+            ($t:ty) => {{
+                let v = value as $t;
+                if format == PlyFormat::BinaryBigEndian {
+                    out.extend_from_slice(&v.to_be_bytes())
+                } else {
+                    out.extend_from_slice(&v.to_le_bytes())
+                }
+            }};
+        }
+        match self {
+            PlyType::Char => write!(i8),
+            PlyType::UChar => write!(u8),
+            PlyType::Short => write!(i16),
+            PlyType::UShort => write!(u16),
+            PlyType::Int => write!(i32),
+            PlyType::UInt => write!(u32),
+            PlyType::Float => write!(f32),
+            PlyType::Double => write!(f64),
+        }
+    }
+}
+
+/// A per-vertex property other than the position
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlyProperty {
+    pub name: String,
+    pub data_type: PlyType,
+}
+
+/// A polyhedron (or, with no faces, a point cloud) along with any extra per-vertex properties such
+/// as colours or normals
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlyMesh {
+    pub mesh: Polyhedron,
+    /// The vertex properties other than x, y and z, in file order
+    pub vertex_properties: Vec<PlyProperty>,
+    /// One row per vertex, holding the values of `vertex_properties` in the same order
+    pub vertex_values: Vec<Vec<f64>>,
+}
+
+impl PlyMesh {
+    /// Wraps a polyhedron with no extra vertex properties
+    pub fn new(mesh: Polyhedron) -> Self {
+        let vertex_values = vec![Vec::new(); mesh.get_points().len()];
+        Self {
+            mesh,
+            vertex_properties: Vec::new(),
+            vertex_values,
+        }
+    }
+}
+
+/// An error while reading or writing a PLY file
+#[derive(Debug, PartialEq, Clone)]
+pub enum PlyError {
+    /// The header is malformed on a (1-based) line
+    Header { line: usize, message: String },
+    /// The data after the header could not be read, or the vertex values to write don't match the
+    /// vertex properties
+    Data(String),
+    /// The file ended before all the elements declared in the header were read
+    UnexpectedEnd,
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlyError::Header { line, message } => write!(f, "header line {line}: {message}"),
+            PlyError::Data(message) => write!(f, "{message}"),
+            PlyError::UnexpectedEnd => write!(f, "the file ended before all elements were read"),
+        }
+    }
+}
+
+impl std::error::Error for PlyError {}
+
+#[derive(Debug, Clone)]
+enum PropertyKind {
+    Scalar(PlyType),
+    List { count: PlyType, item: PlyType },
+}
+
+#[derive(Debug, Clone)]
+struct ElementHeader {
+    name: String,
+    count: usize,
+    properties: Vec<(String, PropertyKind)>,
+}
+
+/// Hands out the values of the body one at a time, regardless of encoding
+struct ValueReader<'a> {
+    format: PlyFormat,
+    bytes: &'a [u8],
+    position: usize,
+    tokens: std::str::SplitAsciiWhitespace<'a>,
+}
+
+impl ValueReader<'_> {
+    fn next(&mut self, data_type: PlyType) -> Result<f64, PlyError> {
+        if self.format == PlyFormat::Ascii {
+            let token = self.tokens.next().ok_or(PlyError::UnexpectedEnd)?;
+            token
+                .parse::<f64>()
+                .map_err(|_| PlyError::Data(format!("'{token}' is not a number")))
+        } else {
+            let end = self.position + data_type.size();
+            let raw = self
+                .bytes
+                .get(self.position..end)
+                .ok_or(PlyError::UnexpectedEnd)?;
+            self.position = end;
+            Ok(data_type.decode(raw, self.format))
+        }
+    }
+}
+
+fn parse_header(bytes: &[u8]) -> Result<(PlyFormat, Vec<ElementHeader>, usize), PlyError> {
+    let mut format = None;
+    let mut elements: Vec<ElementHeader> = Vec::new();
+    let mut offset = 0;
+    let mut line = 0;
+    loop {
+        line += 1;
+        let header_error = |message: String| PlyError::Header { line, message };
+        let end = bytes[offset..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or(header_error("the header has no end_header line".to_owned()))?;
+        let text = std::str::from_utf8(&bytes[offset..offset + end])
+            .map_err(|_| header_error("the header is not valid text".to_owned()))?
+            .trim();
+        offset += end + 1;
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if line == 1 {
+            if text != "ply" {
+                return Err(header_error("a PLY file must start with 'ply'".to_owned()));
+            }
+            continue;
+        }
+        match tokens.as_slice() {
+            ["format", name, _version] => {
+                format = Some(match *name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    other => return Err(header_error(format!("unknown format '{other}'"))),
+                })
+            }
+            ["element", name, count] => elements.push(ElementHeader {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| header_error(format!("'{count}' is not an element count")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let kind = match (PlyType::parse(count), PlyType::parse(item)) {
+                    (Some(count), Some(item)) if count.is_integer() => {
+                        PropertyKind::List { count, item }
+                    }
+                    _ => return Err(header_error(format!("invalid list property '{name}'"))),
+                };
+                elements
+                    .last_mut()
+                    .ok_or(header_error("property before any element".to_owned()))?
+                    .properties
+                    .push((name.to_string(), kind));
+            }
+            ["property", data_type, name] => {
+                let data_type = PlyType::parse(data_type)
+                    .ok_or(header_error(format!("unknown property type '{data_type}'")))?;
+                elements
+                    .last_mut()
+                    .ok_or(header_error("property before any element".to_owned()))?
+                    .properties
+                    .push((name.to_string(), PropertyKind::Scalar(data_type)));
+            }
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(header_error(format!("unexpected header line '{text}'"))),
+        }
+    }
+    let format = format.ok_or(PlyError::Header {
+        line,
+        message: "the header has no format line".to_owned(),
+    })?;
+    Ok((format, elements, offset))
+}
+
+/// Reads an ASCII or binary PLY file. Scalar vertex properties other than x, y and z (colours,
+/// normals, confidences...) are kept in the returned [PlyMesh]; list vertex properties, and elements
+/// other than vertices and faces, are skipped. Faces with more than three corners are ear clipped in their best-fit plane, and the result
+/// is not validated, since PLY files often hold open surfaces or bare point clouds.
+///
+/// # Arguments
+///
+/// * `bytes`: The contents of the PLY file
+///
+/// returns: Result<PlyMesh, PlyError>
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::mesh_io::ply::{read_ply, PlyType};
+/// let text = "ply\nformat ascii 1.0\nelement vertex 4\nproperty float x\nproperty float y\n\
+///     property float z\nproperty uchar red\nelement face 1\nproperty list uchar int vertex_indices\n\
+///     end_header\n0 0 0 255\n1 0 0 0\n1 1 0 12\n0 1 0 7\n4 0 1 2 3\n";
+/// let ply = read_ply(text.as_bytes()).unwrap();
+/// assert_eq!(ply.mesh.get_face_indices().len(), 2);
+/// assert_eq!(ply.vertex_properties[0].name, "red");
+/// assert_eq!(ply.vertex_properties[0].data_type, PlyType::UChar);
+/// assert_eq!(ply.vertex_values[2], vec![12.0]);
+/// ```
+pub fn read_ply(bytes: &[u8]) -> Result<PlyMesh, PlyError> {
+    let (format, elements, body_start) = parse_header(bytes)?;
+    let body = &bytes[body_start..];
+    let mut reader = ValueReader {
+        format,
+        bytes: body,
+        position: 0,
+        tokens: if format == PlyFormat::Ascii {
+            std::str::from_utf8(body)
+                .map_err(|_| PlyError::Data("the body is not valid text".to_owned()))?
+                .split_ascii_whitespace()
+        } else {
+            "".split_ascii_whitespace()
+        },
+    };
+    let mut points = Vec::new();
+    let mut polygons = Vec::new();
+    let mut vertex_properties = Vec::new();
+    let mut vertex_values = Vec::new();
+    for element in &elements {
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+        if is_vertex {
+            for axis in ["x", "y", "z"] {
+                if !element.properties.iter().any(|(name, _)| name == axis) {
+                    return Err(PlyError::Data(format!(
+                        "vertices have no '{axis}' property"
+                    )));
+                }
+            }
+            vertex_properties = element
+                .properties
+                .iter()
+                .filter(|(name, _)| !["x", "y", "z"].contains(&name.as_str()))
+                .filter_map(|(name, kind)| match kind {
+                    PropertyKind::Scalar(data_type) => Some(PlyProperty {
+                        name: name.clone(),
+                        data_type: *data_type,
+                    }),
+                    PropertyKind::List { .. } => None,
+                })
+                .collect();
+        }
+        if element.properties.is_empty() {
+            // Nothing is stored for these, and the count alone isn't trusted to loop over
+            continue;
+        }
+        for _ in 0..element.count {
+            let mut position = [0.0; 3];
+            let mut extra = Vec::new();
+            for (name, kind) in &element.properties {
+                match kind {
+                    PropertyKind::Scalar(data_type) => {
+                        let value = reader.next(*data_type)?;
+                        match name.as_str() {
+                            "x" if is_vertex => position[0] = value,
+                            "y" if is_vertex => position[1] = value,
+                            "z" if is_vertex => position[2] = value,
+                            _ if is_vertex => extra.push(value),
+                            _ => {}
+                        }
+                    }
+                    PropertyKind::List { count, item } => {
+                        let n = reader.next(*count)?;
+                        if n < 0.0 || n.fract() != 0.0 {
+                            return Err(PlyError::Data(format!(
+                                "list '{name}' has a length of {n}"
+                            )));
+                        }
+                        // The length comes from the file, so it isn't trusted to size an allocation
+                        let mut items = Vec::new();
+                        for _ in 0..n as usize {
+                            items.push(reader.next(*item)?);
+                        }
+                        if is_face && (name == "vertex_indices" || name == "vertex_index") {
+                            if items.len() < 3 {
+                                return Err(PlyError::Data(format!(
+                                    "face {} has only {n} corners",
+                                    polygons.len()
+                                )));
+                            }
+                            if let Some(i) = items.iter().find(|i| **i < 0.0 || i.fract() != 0.0) {
+                                return Err(PlyError::Data(format!(
+                                    "face {} has the corner {i}, which is not a vertex index",
+                                    polygons.len()
+                                )));
+                            }
+                            polygons.push(items.iter().map(|i| *i as usize).collect::<Vec<_>>());
+                        }
+                    }
+                }
+            }
+            if is_vertex {
+                points.push(Vector3::new(position[0], position[1], position[2]));
+                vertex_values.push(extra);
+            }
+        }
+    }
+    // Faces can come before vertices, so their corners can only be checked once everything is read
+    if let Some(f) = polygons
+        .iter()
+        .position(|corners| corners.iter().any(|c| *c >= points.len()))
+    {
+        return Err(PlyError::Data(format!(
+            "face {f} references a vertex that does not exist"
+        )));
+    }
+    let faces = polygons
+        .iter()
+        .flat_map(|corners| triangulate_face(&points, corners))
+        .collect();
+    Ok(PlyMesh {
+        mesh: Polyhedron::new_unchecked(points, faces),
+        vertex_properties,
+        vertex_values,
+    })
+}
+
+/// Writes a PLY file. Positions are written as doubles so they round-trip exactly, and extra vertex
+/// properties are written with their own types.
+///
+/// # Arguments
+///
+/// * `ply`: The mesh and vertex properties to write
+/// * `format`: Whether to write text or binary data
+///
+/// returns: Result<Vec<u8>, PlyError>, which is an error if `vertex_values` doesn't have a row for
+/// every point, or a row doesn't have a value for every one of the `vertex_properties`
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// use YetAnotherGeometryLibrary::mesh_io::ply::{read_ply, write_ply, PlyFormat, PlyMesh, PlyProperty, PlyType};
+/// let mut ply = PlyMesh::new(Polyhedron::cube());
+/// ply.vertex_properties.push(PlyProperty { name: "confidence".to_owned(), data_type: PlyType::Float });
+/// for (i, values) in ply.vertex_values.iter_mut().enumerate() {
+///     values.push(i as f64 / 8.0);
+/// }
+/// for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian, PlyFormat::BinaryBigEndian] {
+///     assert_eq!(read_ply(&write_ply(&ply, format).unwrap()).unwrap(), ply);
+/// }
+/// ply.vertex_values[3].clear();
+/// assert!(write_ply(&ply, PlyFormat::Ascii).is_err());
+/// ```
+pub fn write_ply(ply: &PlyMesh, format: PlyFormat) -> Result<Vec<u8>, PlyError> {
+    let points = ply.mesh.get_points();
+    let faces = ply.mesh.get_face_indices();
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        PlyFormat::BinaryBigEndian => "binary_big_endian",
+    };
+    let mut header = format!(
+        "ply\nformat {format_name} 1.0\ncomment Automatically generated from polyhedron by YAGL\n\
+         element vertex {}\nproperty double x\nproperty double y\nproperty double z\n",
+        points.len()
+    );
+    for property in &ply.vertex_properties {
+        header += format!("property {} {}\n", property.data_type.name(), property.name).as_str();
+    }
+    header += format!(
+        "element face {}\nproperty list uchar int vertex_indices\nend_header\n",
+        faces.len()
+    )
+    .as_str();
+    if ply.vertex_values.len() != points.len() {
+        return Err(PlyError::Data(format!(
+            "there are {} rows of vertex values for {} points",
+            ply.vertex_values.len(),
+            points.len()
+        )));
+    }
+    if let Some(i) = ply
+        .vertex_values
+        .iter()
+        .position(|row| row.len() != ply.vertex_properties.len())
+    {
+        return Err(PlyError::Data(format!(
+            "vertex {i} has {} values, but there are {} vertex properties",
+            ply.vertex_values[i].len(),
+            ply.vertex_properties.len()
+        )));
+    }
+    let mut ret = header.into_bytes();
+    for (pt, values) in points.iter().zip(&ply.vertex_values) {
+        if format == PlyFormat::Ascii {
+            let mut line = format!("{} {} {}", pt.x, pt.y, pt.z);
+            for (property, value) in ply.vertex_properties.iter().zip(values) {
+                let value = if property.data_type.is_integer() {
+                    value.round()
+                } else {
+                    *value
+                };
+                line += format!(" {value}").as_str();
+            }
+            ret.extend_from_slice(line.as_bytes());
+            ret.push(b'\n');
+        } else {
+            for c in pt.as_array() {
+                PlyType::Double.encode(c, format, &mut ret);
+            }
+            for (property, value) in ply.vertex_properties.iter().zip(values) {
+                property.data_type.encode(*value, format, &mut ret);
+            }
+        }
+    }
+    for f in faces {
+        if format == PlyFormat::Ascii {
+            ret.extend_from_slice(format!("3 {} {} {}\n", f.0, f.1, f.2).as_bytes());
+        } else {
+            PlyType::UChar.encode(3.0, format, &mut ret);
+            for idx in [f.0, f.1, f.2] {
+                PlyType::Int.encode(idx as f64, format, &mut ret);
+            }
+        }
+    }
+    Ok(ret)
+}
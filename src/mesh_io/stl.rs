@@ -0,0 +1,253 @@
+use crate::hedron::{Polyhedron, weld_points};
+use crate::simple_tri::SimpleTriangle;
+use crate::vectors::Vector3;
+use std::fmt;
+
+/// An error while reading an STL file
+#[derive(Debug, PartialEq, Clone)]
+pub enum StlError {
+    /// A binary file is shorter than its triangle count says it should be
+    Truncated { expected: usize, actual: usize },
+    /// An ASCII file is not valid text
+    InvalidText,
+    /// An ASCII file has unexpected content on a (1-based) line
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StlError::Truncated { expected, actual } => write!(
+                f,
+                "binary STL should be {expected} bytes long, but is only {actual}"
+            ),
+            StlError::InvalidText => write!(f, "ASCII STL is not valid UTF-8"),
+            StlError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for StlError {}
+
+/// The unit normal of a face, or zero if it has no area (or so little that its direction is lost)
+fn facet_normal(tri: &SimpleTriangle) -> Vector3 {
+    let normal = tri.normal();
+    // Scaling first keeps the direction of tiny normals whose squared length underflows
+    let scale = normal.x.abs().max(normal.y.abs()).max(normal.z.abs());
+    if scale > 0.0 && scale.is_finite() {
+        (normal / scale).hat()
+    } else {
+        Vector3::zero()
+    }
+}
+
+/// Writes a polyhedron as an ASCII STL solid. Facet normals are the unit normals of the faces, or
+/// `0 0 0` for faces with no area.
+///
+/// # Arguments
+///
+/// * `hedron`: The polyhedron to write
+/// * `name`: The name of the solid
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// use YetAnotherGeometryLibrary::mesh_io::stl::write_stl_ascii;
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// let points = vec![
+///     Vector3::zero(),
+///     Vector3::new(1e-160, 0.0, 0.0),
+///     Vector3::new(0.0, 1e-160, 0.0),
+///     Vector3::new(2e-160, 0.0, 0.0),
+/// ];
+/// let slivers = Polyhedron::new_unchecked(points, vec![(0, 1, 2), (0, 1, 3)]);
+/// let text = write_stl_ascii(&slivers, "slivers");
+/// let normals: Vec<Vec<f64>> = text
+///     .lines()
+///     .filter_map(|line| line.trim().strip_prefix("facet normal"))
+///     .map(|n| n.split_whitespace().map(|c| c.parse().unwrap()).collect())
+///     .collect();
+/// assert_eq!(normals, [[0.0, 0.0, 1.0], [0.0, 0.0, 0.0]]);
+/// ```
+pub fn write_stl_ascii(hedron: &Polyhedron, name: &str) -> String {
+    let mut ret = format!("solid {name}\n");
+    for tri in hedron.get_faces() {
+        let n = facet_normal(&tri);
+        ret += format!("  facet normal {} {} {}\n    outer loop\n", n.x, n.y, n.z).as_str();
+        for pt in [tri.a, tri.b, tri.c] {
+            ret += format!("      vertex {} {} {}\n", pt.x, pt.y, pt.z).as_str();
+        }
+        ret += "    endloop\n  endfacet\n";
+    }
+    ret += format!("endsolid {name}\n").as_str();
+    ret
+}
+
+/// Writes a polyhedron as a binary STL file. Binary STL stores single precision floats, so
+/// coordinates are rounded to `f32`. Faces with no area get a zero normal.
+///
+/// returns: Vec<u8>
+pub fn write_stl_binary(hedron: &Polyhedron) -> Vec<u8> {
+    let faces = hedron.get_faces();
+    let mut ret = Vec::with_capacity(84 + 50 * faces.len());
+    let mut header = [0u8; 80];
+    let label = b"Automatically generated from polyhedron by YAGL";
+    header[..label.len()].copy_from_slice(label);
+    ret.extend_from_slice(&header);
+    ret.extend_from_slice(&(faces.len() as u32).to_le_bytes());
+    for tri in faces {
+        for v in [facet_normal(&tri), tri.a, tri.b, tri.c] {
+            for c in v.as_array() {
+                ret.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+        // Attribute byte count, which is unused
+        ret.extend_from_slice(&[0, 0]);
+    }
+    ret
+}
+
+/// Reads an ASCII or binary STL file. STL stores every triangle separately, so corners closer than
+/// `weld_tolerance` are merged into shared vertices (a tolerance of 0 only merges identical corners),
+/// and triangles that collapse as a result are dropped. Stored normals are ignored in favour of the
+/// winding order. The result is not validated, since STL files often hold open surfaces.
+///
+/// # Arguments
+///
+/// * `bytes`: The contents of the STL file
+/// * `weld_tolerance`: How close two corners need to be to be treated as the same vertex
+///
+/// returns: Result<Polyhedron, StlError>
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::hedron::Polyhedron;
+/// use YetAnotherGeometryLibrary::mesh_io::stl::{read_stl, write_stl_ascii, write_stl_binary};
+/// let cube = Polyhedron::cube();
+/// let from_ascii = read_stl(write_stl_ascii(&cube, "cube").as_bytes(), 0.0).unwrap();
+/// let from_binary = read_stl(&write_stl_binary(&cube), 0.0).unwrap();
+/// for hedron in [from_ascii, from_binary] {
+///     assert_eq!(hedron.get_points().len(), 8);
+///     assert_eq!(hedron.get_face_indices().len(), 12);
+///     assert!(Polyhedron::new(hedron.get_points().to_vec(), hedron.get_face_indices().to_vec()).is_ok());
+/// }
+/// ```
+pub fn read_stl(bytes: &[u8], weld_tolerance: f64) -> Result<Polyhedron, StlError> {
+    let corners = if is_binary(bytes) {
+        read_binary(bytes)?
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|_| StlError::InvalidText)?;
+        read_ascii(text)?
+    };
+    let representative = weld_points(&corners, weld_tolerance);
+    let mut new_index: Vec<Option<usize>> = vec![None; corners.len()];
+    let mut points = Vec::new();
+    let mut faces = Vec::new();
+    for tri in representative.chunks(3) {
+        if tri[0] == tri[1] || tri[1] == tri[2] || tri[2] == tri[0] {
+            continue;
+        }
+        let mut remap = |idx: usize| {
+            *new_index[idx].get_or_insert_with(|| {
+                points.push(corners[idx]);
+                points.len() - 1
+            })
+        };
+        faces.push((remap(tri[0]), remap(tri[1]), remap(tri[2])));
+    }
+    Ok(Polyhedron::new_unchecked(points, faces))
+}
+
+/// ASCII files start with `solid`, but so do some binary headers, so the length decides
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if bytes.len() == 84 + 50 * count {
+            return true;
+        }
+    }
+    !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+fn read_binary(bytes: &[u8]) -> Result<Vec<Vector3>, StlError> {
+    if bytes.len() < 84 {
+        return Err(StlError::Truncated {
+            expected: 84,
+            actual: bytes.len(),
+        });
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    let expected = 84 + 50 * count;
+    if bytes.len() < expected {
+        return Err(StlError::Truncated {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    let read_f32 = |at: usize| {
+        f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as f64
+    };
+    let mut corners = Vec::with_capacity(3 * count);
+    for i in 0..count {
+        // Skip the 12 byte normal at the start of each record
+        let record = 84 + 50 * i + 12;
+        for corner in 0..3 {
+            let at = record + 12 * corner;
+            corners.push(Vector3::new(
+                read_f32(at),
+                read_f32(at + 4),
+                read_f32(at + 8),
+            ));
+        }
+    }
+    Ok(corners)
+}
+
+fn read_ascii(text: &str) -> Result<Vec<Vector3>, StlError> {
+    let mut corners = Vec::new();
+    let mut in_facet = 0;
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let syntax = |message: String| StlError::Syntax { line, message };
+        let mut tokens = raw.split_whitespace();
+        match tokens.next() {
+            Some("vertex") => {
+                let coords = tokens
+                    .map(|t| {
+                        t.parse::<f64>()
+                            .map_err(|_| syntax(format!("'{t}' is not a number")))
+                    })
+                    .collect::<Result<Vec<f64>, StlError>>()?;
+                if coords.len() != 3 {
+                    return Err(syntax(format!(
+                        "a vertex needs 3 coordinates, but this one has {}",
+                        coords.len()
+                    )));
+                }
+                corners.push(Vector3::new(coords[0], coords[1], coords[2]));
+                in_facet += 1;
+            }
+            Some("endfacet") => {
+                if in_facet != 3 {
+                    return Err(syntax(format!(
+                        "a facet needs 3 vertices, but this one has {in_facet}"
+                    )));
+                }
+                in_facet = 0;
+            }
+            Some("solid" | "facet" | "outer" | "endloop" | "endsolid") | None => {}
+            Some(other) => return Err(syntax(format!("unexpected keyword '{other}'"))),
+        }
+    }
+    if in_facet != 0 {
+        return Err(StlError::Syntax {
+            line: text.lines().count(),
+            message: "the last facet is not closed".to_owned(),
+        });
+    }
+    Ok(corners)
+}
@@ -0,0 +1,176 @@
+use crate::gon::{MultiPolygon, Polygon};
+use crate::vectors::Vector2;
+
+/// How the offset boundary goes around a corner
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum JoinStyle {
+    /// Extends the neighbouring edges until they meet, unless the point where they meet is more than
+    /// `limit` times the offset distance from the corner, in which case it is cut off at that distance.
+    /// Limits below 1 are treated as 1.
+    Miter { limit: f64 },
+    /// Follows a circular arc around the corner, approximated by chords that stay within
+    /// `arc_tolerance` of the true arc
+    Round { arc_tolerance: f64 },
+    /// Cuts the corner off square, at the offset distance from it
+    Square,
+}
+
+/// The shape filling the wedge between the ends of two offset edges at `corner`. `side_in` and
+/// `side_out` are the unit normals of the incoming and outgoing edges on the side being offset, and
+/// `forward_in` and `forward_out` their unit directions.
+fn join_shape(
+    corner: Vector2,
+    forward_in: Vector2,
+    forward_out: Vector2,
+    side_in: Vector2,
+    side_out: Vector2,
+    distance: f64,
+    join: JoinStyle,
+) -> Polygon {
+    let start = corner + side_in * distance;
+    let end = corner + side_out * distance;
+    let cut_at = |cut_distance: f64| {
+        let bisector = (side_in + side_out).hat();
+        let half_angle_cos = bisector.dot(&side_in);
+        if half_angle_cos * cut_distance >= distance {
+            // The edges meet before the cut
+            return vec![
+                corner,
+                start,
+                corner + bisector * (distance / half_angle_cos),
+                end,
+            ];
+        }
+        // Slide along each offset edge until reaching the cut line across the bisector
+        let t_in = (cut_distance - distance * side_in.dot(&bisector)) / forward_in.dot(&bisector);
+        let t_out =
+            (distance * side_out.dot(&bisector) - cut_distance) / forward_out.dot(&bisector);
+        vec![
+            corner,
+            start,
+            start + forward_in * t_in,
+            end - forward_out * t_out,
+            end,
+        ]
+    };
+    let points = match join {
+        JoinStyle::Miter { limit } => cut_at(limit.max(1.0) * distance),
+        JoinStyle::Square => cut_at(distance),
+        JoinStyle::Round { arc_tolerance } => {
+            let tolerance = arc_tolerance.clamp(distance * 1E-6, distance);
+            let step = 2.0 * (1.0 - tolerance / distance).acos();
+            let sweep = side_in.cross(&side_out).atan2(side_in.dot(&side_out));
+            let segments = ((sweep.abs() / step).ceil() as usize).max(1);
+            let mut points = vec![corner];
+            for k in 0..=segments {
+                let angle = sweep * k as f64 / segments as f64;
+                let (sin, cos) = angle.sin_cos();
+                let dir = Vector2::new(
+                    side_in.x * cos - side_in.y * sin,
+                    side_in.x * sin + side_in.y * cos,
+                );
+                points.push(corner + dir * distance);
+            }
+            points
+        }
+    };
+    Polygon::new(points)
+}
+
+/// Grows a region outward by `distance`, or shrinks it inward if `distance` is negative. The region
+/// is swept by a band of the given width along each edge plus a join at each corner, and the sweep is
+/// added to (or cut from) the region with [crate::clipping::boolean_op], so the loops and overlaps a
+/// naive offset would produce are resolved, pieces that shrink away disappear, and pieces that grow
+/// together merge.
+///
+/// # Arguments
+///
+/// * `region`: The region to offset
+/// * `distance`: How far to move the boundary, positive outward and negative inward
+/// * `join`: How to treat corners the boundary moves away from
+///
+/// returns: MultiPolygon
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::gon::{MultiPolygon, Polygon};
+/// use YetAnotherGeometryLibrary::offset::{offset, JoinStyle};
+/// use YetAnotherGeometryLibrary::vectors::Vector2;
+/// let square = MultiPolygon::from(Polygon::new(vec![
+///     Vector2::new(0.0, 0.0),
+///     Vector2::new(2.0, 0.0),
+///     Vector2::new(2.0, 2.0),
+///     Vector2::new(0.0, 2.0),
+/// ]));
+/// assert_eq!(offset(&square, 1.0, JoinStyle::Miter { limit: 2.0 }).area(), 16.0);
+/// let square_join = offset(&square, 1.0, JoinStyle::Square).area();
+/// assert!((square_join - (16.0 - 4.0 * (3.0 - 2.0 * 2f64.sqrt()))).abs() < 1E-9);
+/// let round = offset(&square, 1.0, JoinStyle::Round { arc_tolerance: 1E-4 }).area();
+/// assert!((round - (12.0 + std::f64::consts::PI)).abs() < 1E-2);
+/// assert_eq!(offset(&square, -0.5, JoinStyle::Square).area(), 1.0);
+/// assert!(offset(&square, -1.5, JoinStyle::Square).get_polygons().is_empty());
+/// ```
+pub fn offset(region: &MultiPolygon, distance: f64, join: JoinStyle) -> MultiPolygon {
+    if distance == 0.0 {
+        return region.clone();
+    }
+    let width = distance.abs();
+    let outward = distance > 0.0;
+    let mut sweep = Vec::new();
+    for polygon in region.get_polygons() {
+        // Every ring is walked with the region on its left, so holes run clockwise
+        let mut rings = vec![polygon.get_outer().get_points().to_vec()];
+        for hole in polygon.get_holes() {
+            rings.push(hole.get_points().iter().rev().copied().collect());
+        }
+        for mut ring in rings {
+            // A repeated point has no edge direction to offset along, and would hide the corner it
+            // repeats from the join below
+            ring.dedup();
+            while ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            let n = ring.len();
+            for i in 0..n {
+                let prev = ring[(i + n - 1) % n];
+                let corner = ring[i];
+                let next = ring[(i + 1) % n];
+                let forward_out = (next - corner).hat();
+                // The unit normal pointing out of the region
+                let normal_out = Vector2::new(forward_out.y, -forward_out.x);
+                sweep.push(Polygon::new(vec![
+                    corner + normal_out * width,
+                    next + normal_out * width,
+                    next - normal_out * width,
+                    corner - normal_out * width,
+                ]));
+                let forward_in = (corner - prev).hat();
+                let normal_in = Vector2::new(forward_in.y, -forward_in.x);
+                let turn = forward_in.cross(&forward_out);
+                // Growing opens a gap between the bands at convex corners, shrinking at reflex ones
+                if (outward && turn < 0.0) || (!outward && turn > 0.0) {
+                    continue;
+                }
+                let side = if outward { 1.0 } else { -1.0 };
+                if turn != 0.0 {
+                    sweep.push(join_shape(
+                        corner,
+                        forward_in,
+                        forward_out,
+                        normal_in * side,
+                        normal_out * side,
+                        width,
+                        join,
+                    ));
+                }
+            }
+        }
+    }
+    let sweep = MultiPolygon::union_all(sweep);
+    if outward {
+        region.union(&sweep)
+    } else {
+        region.difference(&sweep)
+    }
+}
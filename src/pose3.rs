@@ -1,5 +1,7 @@
-use crate::rotation3::Rotation3;
-use crate::vectors::Vector3;
+use crate::approx::impl_approx_eq;
+use crate::rotation3::Rot3;
+use crate::scalar::Float;
+use crate::vectors::Vec3;
 use core::ops;
 
 /// A pose (position plus orientation) in 3d
@@ -28,52 +30,71 @@ use core::ops;
 /// assert_ne!(fwd1r90+fwd1r90,fwd1r90*2.0);
 /// ```
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct Pose3 {
-    pub position: Vector3,
-    pub orientation: Rotation3,
+pub struct Pose<T: Float> {
+    pub position: Vec3<T>,
+    pub orientation: Rot3<T>,
 }
-impl ops::Add<Pose3> for Pose3 {
-    type Output = Pose3;
-    fn add(self, rhs: Pose3) -> Pose3 {
+pub type Pose3 = Pose<f64>;
+pub type Pose3f = Pose<f32>;
+impl_approx_eq!(<T> Pose<T>, position, orientation);
+impl From<Pose3f> for Pose3 {
+    fn from(p: Pose3f) -> Self {
+        p.cast()
+    }
+}
+/// `a + b` is the pose reached by moving by `a`, then by `b` measured in the frame `a` ends up in.
+/// Both the position and the orientation of `b` are taken relative to `a`, so the orientation is
+/// `b.orientation` applied first and `a.orientation` after it, which makes `(a + b) - a == b`.
+///
+/// This is a change from earlier releases, which applied `a.orientation` first. The results only
+/// differ when the two rotations don't commute, for example turns about different axes.
+impl<T: Float> ops::Add<Pose<T>> for Pose<T> {
+    type Output = Pose<T>;
+    fn add(self, rhs: Pose<T>) -> Pose<T> {
         Self::new(
             self.position + self.orientation.rotate_vector(rhs.position),
-            self.orientation + rhs.orientation,
+            // `rhs` turns within the frame of `self`, so its rotation is applied first
+            rhs.orientation + self.orientation,
         )
     }
 }
-impl ops::Neg for Pose3 {
-    type Output = Pose3;
-    fn neg(self) -> Pose3 {
-        Pose3::new(
+impl<T: Float> ops::Neg for Pose<T> {
+    type Output = Pose<T>;
+    fn neg(self) -> Pose<T> {
+        Pose::new(
             (-self.orientation).rotate_vector(-self.position),
             -self.orientation,
         )
     }
 }
-impl ops::Sub<Pose3> for Pose3 {
-    type Output = Pose3;
-    fn sub(self, rhs: Pose3) -> Pose3 {
+impl<T: Float> ops::Sub<Pose<T>> for Pose<T> {
+    type Output = Pose<T>;
+    fn sub(self, rhs: Pose<T>) -> Pose<T> {
         Self::new(
             (-rhs.orientation).rotate_vector(self.position - rhs.position),
             self.orientation - rhs.orientation,
         )
     }
 }
-impl ops::Mul<f64> for Pose3 {
-    type Output = Pose3;
-    fn mul(self, rhs: f64) -> Pose3 {
+impl<T: Float> ops::Mul<T> for Pose<T> {
+    type Output = Pose<T>;
+    fn mul(self, rhs: T) -> Pose<T> {
         Self::new(self.position * rhs, self.orientation * rhs)
     }
 }
 
-impl Pose3 {
-    pub fn new(position: Vector3, orientation: Rotation3) -> Self {
+impl<T: Float> Pose<T> {
+    pub fn new(position: Vec3<T>, orientation: Rot3<T>) -> Self {
         Self {
             position,
             orientation,
         }
     }
     pub fn identity() -> Self {
-        Self::new(Vector3::zero(), Rotation3::identity())
+        Self::new(Vec3::zero(), Rot3::identity())
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Pose<U> {
+        Pose::new(self.position.cast(), self.orientation.cast())
     }
 }
@@ -0,0 +1,359 @@
+//! Geometric predicates whose signs are always correct, following Shewchuk's "Adaptive Precision
+//! Floating-Point Arithmetic and Fast Robust Geometric Predicates". Each is first evaluated in plain
+//! floating point, and only if the result is too close to zero to trust its sign is it evaluated again
+//! exactly, using expansions: sums of non-overlapping floats, smallest first, which represent a value
+//! without rounding.
+use crate::vectors::{Vector2, Vector3};
+
+/// Half the gap between 1 and the next float, which bounds the relative error of one operation
+const UNIT_ROUNDOFF: f64 = f64::EPSILON / 2.0;
+const ORIENT2D_BOUND: f64 = (3.0 + 16.0 * UNIT_ROUNDOFF) * UNIT_ROUNDOFF;
+const ORIENT3D_BOUND: f64 = (7.0 + 56.0 * UNIT_ROUNDOFF) * UNIT_ROUNDOFF;
+const INCIRCLE_BOUND: f64 = (10.0 + 96.0 * UNIT_ROUNDOFF) * UNIT_ROUNDOFF;
+const INSPHERE_BOUND: f64 = (16.0 + 224.0 * UNIT_ROUNDOFF) * UNIT_ROUNDOFF;
+const DOT3_BOUND: f64 = (4.0 + 32.0 * UNIT_ROUNDOFF) * UNIT_ROUNDOFF;
+
+/// The rounded sum of two floats and the error of rounding it
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    (x, (a - a_virtual) + (b - b_virtual))
+}
+
+/// The rounded product of two floats and the error of rounding it
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+/// Adds a float to an expansion
+fn grow(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &component in e {
+        let (sum, error) = two_sum(q, component);
+        if error != 0.0 {
+            result.push(error);
+        }
+        q = sum;
+    }
+    if q != 0.0 {
+        result.push(q);
+    }
+    result
+}
+
+fn sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter()
+        .fold(e.to_vec(), |total, &component| grow(&total, component))
+}
+
+fn negate(e: &[f64]) -> Vec<f64> {
+    e.iter().map(|c| -c).collect()
+}
+
+fn scale(e: &[f64], b: f64) -> Vec<f64> {
+    let mut result = Vec::new();
+    for &component in e {
+        let (product, error) = two_product(component, b);
+        result = grow(&grow(&result, error), product);
+    }
+    result
+}
+
+fn product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(Vec::new(), |total, &component| {
+        sum(&total, &scale(e, component))
+    })
+}
+
+/// The exact difference of two floats
+fn difference(a: f64, b: f64) -> Vec<f64> {
+    grow(&[a], -b)
+}
+
+/// An expansion's approximate value, which has the same sign as its exact value
+fn estimate(e: &[f64]) -> f64 {
+    e.iter().sum()
+}
+
+/// `ax * by - ay * bx`, exactly
+fn cross2(ax: &[f64], ay: &[f64], bx: &[f64], by: &[f64]) -> Vec<f64> {
+    sum(&product(ax, by), &negate(&product(ay, bx)))
+}
+
+/// The squared length of a vector given by exact components
+fn lift(components: &[&[f64]]) -> Vec<f64> {
+    components
+        .iter()
+        .fold(Vec::new(), |total, c| sum(&total, &product(c, c)))
+}
+
+/// Determines which side of the line through `a` and `b` the point `c` is on.
+///
+/// returns: f64, positive if `a`, `b`, `c` are counterclockwise, negative if they are clockwise, and
+/// exactly zero if they are collinear. Its magnitude approximates twice the area of the triangle.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::predicates::orient2d;
+/// use YetAnotherGeometryLibrary::vectors::Vector2;
+/// assert_eq!(orient2d(Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(0.0, 1.0)), 1.0);
+/// // Rounding makes the plain cross product zero here, though `a` is just right of the line
+/// let a = Vector2::new(0.5000000000000001, 0.5);
+/// let (b, c) = (Vector2::new(12.0, 12.0), Vector2::new(24.0, 24.0));
+/// assert_eq!((b - a).cross(&(c - a)), 0.0);
+/// assert!(orient2d(a, b, c) < 0.0);
+/// ```
+pub fn orient2d(a: Vector2, b: Vector2, c: Vector2) -> f64 {
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+    if det.abs() >= ORIENT2D_BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+    let (acx, acy) = (difference(a.x, c.x), difference(a.y, c.y));
+    let (bcx, bcy) = (difference(b.x, c.x), difference(b.y, c.y));
+    estimate(&cross2(&acx, &acy, &bcx, &bcy))
+}
+
+/// Determines which side of the plane through `a`, `b` and `c` the point `d` is on.
+///
+/// returns: f64, positive if `d` is above the plane, where `a`, `b`, `c` look counterclockwise from
+/// above, negative if it is below, and exactly zero if the points are coplanar. This matches the sign of
+/// [crate::hedron::Tetrahedron::volume], and its magnitude approximates six times that volume.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::predicates::orient3d;
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// let (a, b, c) = (Vector3::zero(), Vector3::i_hat(), Vector3::j_hat());
+/// assert_eq!(orient3d(a, b, c, Vector3::k_hat()), 1.0);
+/// assert_eq!(orient3d(a, b, c, Vector3::new(5.0, -3.0, 0.0)), 0.0);
+/// ```
+pub fn orient3d(a: Vector3, b: Vector3, c: Vector3, d: Vector3) -> f64 {
+    let (adx, ady, adz) = (a.x - d.x, a.y - d.y, a.z - d.z);
+    let (bdx, bdy, bdz) = (b.x - d.x, b.y - d.y, b.z - d.z);
+    let (cdx, cdy, cdz) = (c.x - d.x, c.y - d.y, c.z - d.z);
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    // This is below the plane in Shewchuk's convention, so it is negated at the end
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    if det.abs() >= ORIENT3D_BOUND * permanent {
+        return -det;
+    }
+    let [adx, ady, adz] = [(a.x, d.x), (a.y, d.y), (a.z, d.z)].map(|(p, q)| difference(p, q));
+    let [bdx, bdy, bdz] = [(b.x, d.x), (b.y, d.y), (b.z, d.z)].map(|(p, q)| difference(p, q));
+    let [cdx, cdy, cdz] = [(c.x, d.x), (c.y, d.y), (c.z, d.z)].map(|(p, q)| difference(p, q));
+    let exact = sum(
+        &sum(
+            &product(&adz, &cross2(&bdx, &bdy, &cdx, &cdy)),
+            &product(&bdz, &cross2(&cdx, &cdy, &adx, &ady)),
+        ),
+        &product(&cdz, &cross2(&adx, &ady, &bdx, &bdy)),
+    );
+    -estimate(&exact)
+}
+
+/// Determines whether `d` is inside the circle through `a`, `b` and `c`, which must be counterclockwise.
+///
+/// returns: f64, positive if `d` is inside the circle, negative if it is outside, and exactly zero if
+/// it is on it. The signs are reversed if `a`, `b`, `c` are clockwise.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::predicates::incircle;
+/// use YetAnotherGeometryLibrary::vectors::Vector2;
+/// let (a, b, c) = (Vector2::new(1.0, 0.0), Vector2::new(0.0, 1.0), Vector2::new(-1.0, 0.0));
+/// assert!(incircle(a, b, c, Vector2::new(0.5, 0.5)) > 0.0);
+/// assert_eq!(incircle(a, b, c, Vector2::new(0.0, -1.0)), 0.0);
+/// assert!(incircle(a, b, c, Vector2::new(1.0, 1.0)) < 0.0);
+/// ```
+pub fn incircle(a: Vector2, b: Vector2, c: Vector2, d: Vector2) -> f64 {
+    let (adx, ady) = (a.x - d.x, a.y - d.y);
+    let (bdx, bdy) = (b.x - d.x, b.y - d.y);
+    let (cdx, cdy) = (c.x - d.x, c.y - d.y);
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() >= INCIRCLE_BOUND * permanent {
+        return det;
+    }
+    let [adx, ady, bdx, bdy, cdx, cdy] = [
+        (a.x, d.x),
+        (a.y, d.y),
+        (b.x, d.x),
+        (b.y, d.y),
+        (c.x, d.x),
+        (c.y, d.y),
+    ]
+    .map(|(p, q)| difference(p, q));
+    let exact = sum(
+        &sum(
+            &product(&lift(&[&adx, &ady]), &cross2(&bdx, &bdy, &cdx, &cdy)),
+            &product(&lift(&[&bdx, &bdy]), &cross2(&cdx, &cdy, &adx, &ady)),
+        ),
+        &product(&lift(&[&cdx, &cdy]), &cross2(&adx, &ady, &bdx, &bdy)),
+    );
+    estimate(&exact)
+}
+
+/// Determines whether `e` is inside the sphere through `a`, `b`, `c` and `d`, which must be positively
+/// oriented, so that [orient3d] is positive for them.
+///
+/// returns: f64, positive if `e` is inside the sphere, negative if it is outside, and exactly zero if it
+/// is on it. The signs are reversed if the first four points are negatively oriented.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::predicates::{insphere, orient3d};
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// let (a, b, c, d) = (Vector3::i_hat(), Vector3::j_hat(), -Vector3::i_hat(), Vector3::k_hat());
+/// assert!(orient3d(a, b, c, d) > 0.0);
+/// assert!(insphere(a, b, c, d, Vector3::new(0.0, 0.0, 0.5)) > 0.0);
+/// assert_eq!(insphere(a, b, c, d, -Vector3::k_hat()), 0.0);
+/// assert!(insphere(a, b, c, d, Vector3::new(0.0, 0.0, 2.0)) < 0.0);
+/// ```
+pub fn insphere(a: Vector3, b: Vector3, c: Vector3, d: Vector3, e: Vector3) -> f64 {
+    let [aex, aey, aez] = [a.x - e.x, a.y - e.y, a.z - e.z];
+    let [bex, bey, bez] = [b.x - e.x, b.y - e.y, b.z - e.z];
+    let [cex, cey, cez] = [c.x - e.x, c.y - e.y, c.z - e.z];
+    let [dex, dey, dez] = [d.x - e.x, d.y - e.y, d.z - e.z];
+    let (aexbey, bexaey) = (aex * bey, bex * aey);
+    let (bexcey, cexbey) = (bex * cey, cex * bey);
+    let (cexdey, dexcey) = (cex * dey, dex * cey);
+    let (dexaey, aexdey) = (dex * aey, aex * dey);
+    let (aexcey, cexaey) = (aex * cey, cex * aey);
+    let (bexdey, dexbey) = (bex * dey, dex * bey);
+    let (ab, bc, cd) = (aexbey - bexaey, bexcey - cexbey, cexdey - dexcey);
+    let (da, ac, bd) = (dexaey - aexdey, aexcey - cexaey, bexdey - dexbey);
+    let abc = aez * bc - bez * ac + cez * ab;
+    let bcd = bez * cd - cez * bd + dez * bc;
+    let cda = cez * da + dez * ac + aez * cd;
+    let dab = dez * ab + aez * bd + bez * da;
+    let alift = aex * aex + aey * aey + aez * aez;
+    let blift = bex * bex + bey * bey + bez * bez;
+    let clift = cex * cex + cey * cey + cez * cez;
+    let dlift = dex * dex + dey * dey + dez * dez;
+    // Positive inside in Shewchuk's convention, where the orientation is reversed
+    let det = (dlift * abc - clift * dab) + (blift * cda - alift * bcd);
+    let (ab, bc, cd) = (
+        aexbey.abs() + bexaey.abs(),
+        bexcey.abs() + cexbey.abs(),
+        cexdey.abs() + dexcey.abs(),
+    );
+    let (da, ac, bd) = (
+        dexaey.abs() + aexdey.abs(),
+        aexcey.abs() + cexaey.abs(),
+        bexdey.abs() + dexbey.abs(),
+    );
+    let (aez, bez, cez, dez) = (aez.abs(), bez.abs(), cez.abs(), dez.abs());
+    let permanent = ((cd * bez + bd * cez + bc * dez) * alift
+        + (da * cez + ac * dez + cd * aez) * blift)
+        + ((ab * dez + bd * aez + da * bez) * clift + (bc * aez + ac * bez + ab * cez) * dlift);
+    if det.abs() >= INSPHERE_BOUND * permanent {
+        return -det;
+    }
+    let [aex, aey, aez] = [(a.x, e.x), (a.y, e.y), (a.z, e.z)].map(|(p, q)| difference(p, q));
+    let [bex, bey, bez] = [(b.x, e.x), (b.y, e.y), (b.z, e.z)].map(|(p, q)| difference(p, q));
+    let [cex, cey, cez] = [(c.x, e.x), (c.y, e.y), (c.z, e.z)].map(|(p, q)| difference(p, q));
+    let [dex, dey, dez] = [(d.x, e.x), (d.y, e.y), (d.z, e.z)].map(|(p, q)| difference(p, q));
+    let ab = cross2(&aex, &aey, &bex, &bey);
+    let bc = cross2(&bex, &bey, &cex, &cey);
+    let cd = cross2(&cex, &cey, &dex, &dey);
+    let da = cross2(&dex, &dey, &aex, &aey);
+    let ac = cross2(&aex, &aey, &cex, &cey);
+    let bd = cross2(&bex, &bey, &dex, &dey);
+    let abc = sum(
+        &sum(&product(&aez, &bc), &negate(&product(&bez, &ac))),
+        &product(&cez, &ab),
+    );
+    let bcd = sum(
+        &sum(&product(&bez, &cd), &negate(&product(&cez, &bd))),
+        &product(&dez, &bc),
+    );
+    let cda = sum(
+        &sum(&product(&cez, &da), &product(&dez, &ac)),
+        &product(&aez, &cd),
+    );
+    let dab = sum(
+        &sum(&product(&dez, &ab), &product(&aez, &bd)),
+        &product(&bez, &da),
+    );
+    let exact = sum(
+        &sum(
+            &product(&lift(&[&dex, &dey, &dez]), &abc),
+            &negate(&product(&lift(&[&cex, &cey, &cez]), &dab)),
+        ),
+        &sum(
+            &product(&lift(&[&bex, &bey, &bez]), &cda),
+            &negate(&product(&lift(&[&aex, &aey, &aez]), &bcd)),
+        ),
+    );
+    -estimate(&exact)
+}
+
+/// Determines which side of the plane through `origin` with normal `normal` the point `pt` is on.
+///
+/// returns: f64, the sign of `normal.dot(&(pt - origin))` computed without rounding, so it is exactly
+/// zero only if the point is on the plane as given
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::predicates::plane_side;
+/// use YetAnotherGeometryLibrary::vectors::Vector3;
+/// let normal = Vector3::new(1.0, 1.0, 0.0);
+/// assert_eq!(plane_side(Vector3::zero(), normal, Vector3::new(0.1, -0.1, 7.0)), 0.0);
+/// assert!(plane_side(Vector3::zero(), normal, Vector3::new(0.1, -0.1 + 1E-17, 7.0)) > 0.0);
+/// ```
+pub fn plane_side(origin: Vector3, normal: Vector3, pt: Vector3) -> f64 {
+    let (dx, dy, dz) = (pt.x - origin.x, pt.y - origin.y, pt.z - origin.z);
+    let (px, py, pz) = (normal.x * dx, normal.y * dy, normal.z * dz);
+    let det = px + py + pz;
+    if det.abs() >= DOT3_BOUND * (px.abs() + py.abs() + pz.abs()) {
+        return det;
+    }
+    let exact = sum(
+        &sum(
+            &scale(&difference(pt.x, origin.x), normal.x),
+            &scale(&difference(pt.y, origin.y), normal.y),
+        ),
+        &scale(&difference(pt.z, origin.z), normal.z),
+    );
+    estimate(&exact)
+}
+
+/// Determines which way `direction` points relative to the plane through `a`, `b` and `c`, like
+/// [orient3d] with a direction in place of the fourth point.
+///
+/// returns: f64, positive if it points above the plane (towards the side the counterclockwise face
+/// `a`, `b`, `c` faces), negative if it points below, and exactly zero if it is parallel to it
+pub fn orient_direction(a: Vector3, b: Vector3, c: Vector3, direction: Vector3) -> f64 {
+    let [ux, uy, uz] = [(b.x, a.x), (b.y, a.y), (b.z, a.z)].map(|(p, q)| difference(p, q));
+    let [vx, vy, vz] = [(c.x, a.x), (c.y, a.y), (c.z, a.z)].map(|(p, q)| difference(p, q));
+    let exact = sum(
+        &sum(
+            &scale(&cross2(&uy, &uz, &vy, &vz), direction.x),
+            &scale(&cross2(&uz, &ux, &vz, &vx), direction.y),
+        ),
+        &scale(&cross2(&ux, &uy, &vx, &vy), direction.z),
+    );
+    estimate(&exact)
+}
@@ -1,39 +1,52 @@
-use crate::vectors::Vector3;
+use crate::approx::impl_approx_eq;
+use crate::scalar::Float;
+use crate::vectors::Vec3;
 use core::ops;
 
 #[derive(Debug, Copy, Clone)]
-pub struct Quaternion {
-    pub w: f64,
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+pub struct Quat<T> {
+    pub w: T,
+    pub x: T,
+    pub y: T,
+    pub z: T,
 }
 
-impl PartialEq for Quaternion {
+pub type Quaternion = Quat<f64>;
+pub type Quaternionf = Quat<f32>;
+
+impl_approx_eq!(<T> Quat<T>, w, x, y, z);
+
+impl From<Quaternionf> for Quaternion {
+    fn from(q: Quaternionf) -> Self {
+        q.cast()
+    }
+}
+
+impl<T: Float> PartialEq for Quat<T> {
     fn eq(&self, other: &Self) -> bool {
         (self.w - other.w).abs()
             + (self.x - other.x).abs()
             + (self.y - other.y).abs()
             + (self.z - other.z).abs()
-            < 1e-12
+            < T::NEAR
     }
 }
 
-impl Default for Quaternion {
-    fn default() -> Quaternion {
-        Quaternion {
-            w: 1.0,
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
+impl<T: Float> Default for Quat<T> {
+    fn default() -> Quat<T> {
+        Quat {
+            w: T::ONE,
+            x: T::ZERO,
+            y: T::ZERO,
+            z: T::ZERO,
         }
     }
 }
 
-impl ops::Add<Quaternion> for Quaternion {
-    type Output = Quaternion;
-    fn add(self, rhs: Quaternion) -> Self::Output {
-        Quaternion {
+impl<T: Float> ops::Add<Quat<T>> for Quat<T> {
+    type Output = Quat<T>;
+    fn add(self, rhs: Quat<T>) -> Self::Output {
+        Quat {
             w: self.w + rhs.w,
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -41,10 +54,10 @@ impl ops::Add<Quaternion> for Quaternion {
         }
     }
 }
-impl ops::Sub<Quaternion> for Quaternion {
-    type Output = Quaternion;
-    fn sub(self, rhs: Quaternion) -> Self::Output {
-        Quaternion {
+impl<T: Float> ops::Sub<Quat<T>> for Quat<T> {
+    type Output = Quat<T>;
+    fn sub(self, rhs: Quat<T>) -> Self::Output {
+        Quat {
             w: self.w - rhs.w,
             x: self.x - rhs.x,
             y: self.y - rhs.y,
@@ -53,10 +66,10 @@ impl ops::Sub<Quaternion> for Quaternion {
     }
 }
 
-impl ops::Div<f64> for Quaternion {
-    type Output = Quaternion;
-    fn div(self, rhs: f64) -> Self::Output {
-        Quaternion {
+impl<T: Float> ops::Div<T> for Quat<T> {
+    type Output = Quat<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Quat {
             w: self.w / rhs,
             x: self.x / rhs,
             y: self.y / rhs,
@@ -64,10 +77,10 @@ impl ops::Div<f64> for Quaternion {
         }
     }
 }
-impl ops::Mul<f64> for Quaternion {
-    type Output = Quaternion;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Quaternion {
+impl<T: Float> ops::Mul<T> for Quat<T> {
+    type Output = Quat<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Quat {
             w: self.w * rhs,
             x: self.x * rhs,
             y: self.y * rhs,
@@ -76,13 +89,13 @@ impl ops::Mul<f64> for Quaternion {
     }
 }
 
-impl ops::Mul<Quaternion> for Quaternion {
-    type Output = Quaternion;
-    fn mul(self, rhs: Quaternion) -> Self::Output {
+impl<T: Float> ops::Mul<Quat<T>> for Quat<T> {
+    type Output = Quat<T>;
+    fn mul(self, rhs: Quat<T>) -> Self::Output {
         let v = rhs.get_vector() * self.get_scalar()
             + self.get_vector() * rhs.get_scalar()
             + self.get_vector().cross(&rhs.get_vector());
-        Quaternion {
+        Quat {
             w: self.get_scalar() * rhs.get_scalar() - self.get_vector().dot(&rhs.get_vector()),
             x: v.x,
             y: v.y,
@@ -91,60 +104,70 @@ impl ops::Mul<Quaternion> for Quaternion {
     }
 }
 
-impl Quaternion {
-    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+impl<T: Float> Quat<T> {
+    pub fn new(w: T, x: T, y: T, z: T) -> Self {
         Self { w, x, y, z }
     }
-    pub fn from_rotation_vector(rvec: Vector3) -> Self {
+    pub fn from_rotation_vector(rvec: Vec3<T>) -> Self {
         let theta = rvec.magnitude();
-        if theta.abs() < 1E-9 {
-            Quaternion::default()
+        let half = theta / T::from_f64(2.0);
+        if theta.abs() < T::from_f64(1E-9) {
+            Quat::default()
         } else {
-            Self::from_scalar_vector((theta / 2.0).cos(), rvec.hat() * (theta / 2.0).sin())
+            Self::from_scalar_vector(half.cos(), rvec.hat() * half.sin())
         }
     }
-    pub(crate) fn from_scalar_vector(scalar: f64, vector: Vector3) -> Self {
-        Quaternion::new(scalar, vector.x, vector.y, vector.z)
+    pub(crate) fn from_scalar_vector(scalar: T, vector: Vec3<T>) -> Self {
+        Quat::new(scalar, vector.x, vector.y, vector.z)
     }
-    fn get_scalar(&self) -> f64 {
+    fn get_scalar(&self) -> T {
         self.w
     }
-    pub(crate) fn get_vector(&self) -> Vector3 {
-        Vector3::new(self.x, self.y, self.z)
+    pub(crate) fn get_vector(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, self.z)
     }
-    pub fn dot(&self, rhs: &Quaternion) -> f64 {
+    pub fn dot(&self, rhs: &Quat<T>) -> T {
         self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
     pub fn conjugate(&self) -> Self {
-        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+        Quat::new(self.w, -self.x, -self.y, -self.z)
     }
     pub fn inverse(&self) -> Self {
         self.conjugate() / self.dot(self)
     }
-    pub fn norm(&self) -> f64 {
+    pub fn norm(&self) -> T {
         self.dot(self).sqrt()
     }
     pub fn hat(&self) -> Self {
         let norm = self.norm();
-        if norm == 0.0 {
-            Quaternion::default()
+        if norm == T::ZERO {
+            Quat::default()
         } else {
-            Quaternion::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
+            Quat::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
         }
     }
     pub fn exp(&self) -> Self {
         let scalar_exp = self.get_scalar().exp();
-        let axial_scalar = if self.get_vector().magnitude() < 1E-9 {
+        let axial_scalar = if self.get_vector().magnitude() < T::from_f64(1E-9) {
             let axial_mag2 = self.get_vector().magnitude().powi(2);
             let axial_mag4 = axial_mag2 * axial_mag2;
-            1.0 - (axial_mag2 / 6.0) + (axial_mag4 / 120.0)
+            T::ONE - (axial_mag2 / T::from_f64(6.0)) + (axial_mag4 / T::from_f64(120.0))
         } else {
             let axial_mag = self.get_vector().magnitude();
             axial_mag.sin() / axial_mag
         };
-        Quaternion::from_scalar_vector(
+        Quat::from_scalar_vector(
             self.get_vector().magnitude().cos() * scalar_exp,
             self.get_vector() * axial_scalar * scalar_exp,
         )
     }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Quat<U> {
+        Quat::new(
+            U::from_f64(self.w.to_f64()),
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+        )
+    }
 }
@@ -1,14 +1,21 @@
-use crate::vectors::Vector3;
+use crate::approx::impl_approx_eq;
+use crate::predicates::plane_side;
+use crate::scalar::Float;
+use crate::vectors::Vec3;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct Ray {
-    pub origin: Vector3,
-    pub direction: Vector3,
+pub struct RayOf<T: Float> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
 }
+pub type Ray = RayOf<f64>;
+pub type Rayf = RayOf<f32>;
 
-impl Ray {
-    pub fn new(origin: Vector3, direction: Vector3) -> Ray {
-        Ray { origin, direction }
+impl_approx_eq!(<T> RayOf<T>, origin, direction);
+
+impl<T: Float> RayOf<T> {
+    pub fn new(origin: Vec3<T>, direction: Vec3<T>) -> RayOf<T> {
+        RayOf { origin, direction }
     }
     /// Determines if this ray points towards or away from a point.
     ///
@@ -31,15 +38,18 @@ impl Ray {
     /// assert_eq!(Ray::new(Vector3::new(3.0,2.0,6.0),Vector3::new(-1.0,0.0,0.0)).points_away(pt),-1);
     /// assert_eq!(Ray::new(Vector3::new(3.0,2.0,6.0),Vector3::new(-1.0,1.0,0.0)).points_away(pt),0);
     /// ```
-    pub fn points_away(&self, point: Vector3) -> i8 {
-        let adj_pt = point - self.origin;
-        let dt = adj_pt.dot(&self.direction);
+    pub fn points_away(&self, point: Vec3<T>) -> i8 {
+        let dt = plane_side(self.origin.cast(), self.direction.cast(), point.cast());
         if dt == 0.0 {
             0
         } else if dt > 0.0 {
-            return -1;
+            -1
         } else {
-            return 1;
+            1
         }
     }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> RayOf<U> {
+        RayOf::new(self.origin.cast(), self.direction.cast())
+    }
 }
@@ -1,83 +1,125 @@
-use crate::quaternion::Quaternion;
-use crate::vectors::Vector3;
+use crate::approx::ApproxEq;
+use crate::quaternion::Quat;
+use crate::scalar::Float;
+use crate::utils::matrix::Mat3;
+use crate::vectors::Vec3;
 use core::ops;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct Rotation3 {
-    pub q: Quaternion,
+pub struct Rot3<T: Float> {
+    pub q: Quat<T>,
 }
 
-impl ops::Neg for Rotation3 {
-    type Output = Rotation3;
+pub type Rotation3 = Rot3<f64>;
+pub type Rotation3f = Rot3<f32>;
+
+impl From<Rotation3f> for Rotation3 {
+    fn from(r: Rotation3f) -> Self {
+        r.cast()
+    }
+}
+
+/// `q` and `-q` are the same rotation, so rotations are equal if either is close
+impl<T: Float> ApproxEq for Rot3<T> {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.q.abs_diff_eq(&other.q, epsilon) || self.q.abs_diff_eq(&(other.q * -T::ONE), epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.q.relative_eq(&other.q, epsilon, max_relative)
+            || self
+                .q
+                .relative_eq(&(other.q * -T::ONE), epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.q.ulps_eq(&other.q, epsilon, max_ulps)
+            || self.q.ulps_eq(&(other.q * -T::ONE), epsilon, max_ulps)
+    }
+}
+
+impl<T: Float> ops::Neg for Rot3<T> {
+    type Output = Rot3<T>;
     fn neg(self) -> Self::Output {
-        Rotation3 {
+        Rot3 {
             q: self.q.inverse(),
         }
     }
 }
 
-impl ops::Add<Rotation3> for Rotation3 {
-    type Output = Rotation3;
-    fn add(self, other: Rotation3) -> Self::Output {
-        Rotation3 {
+impl<T: Float> ops::Add<Rot3<T>> for Rot3<T> {
+    type Output = Rot3<T>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Rot3<T>) -> Self::Output {
+        Rot3 {
             q: other.q * self.q,
         }
     }
 }
 
-impl ops::Sub<Rotation3> for Rotation3 {
-    type Output = Rotation3;
-    fn sub(self, other: Rotation3) -> Self::Output {
-        Rotation3 {
+impl<T: Float> ops::Sub<Rot3<T>> for Rot3<T> {
+    type Output = Rot3<T>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Rot3<T>) -> Self::Output {
+        Rot3 {
             q: other.q.inverse() * self.q,
         }
     }
 }
 
-impl ops::Mul<f64> for Rotation3 {
-    type Output = Rotation3;
-    fn mul(self, other: f64) -> Self::Output {
-        if self.q.w >= 0.0 {
-            Rotation3::from_axis_angle(
+impl<T: Float> ops::Mul<T> for Rot3<T> {
+    type Output = Rot3<T>;
+    fn mul(self, other: T) -> Self::Output {
+        let two = T::from_f64(2.0);
+        if self.q.w >= T::ZERO {
+            Rot3::from_axis_angle(
                 self.q
                     .get_vector()
-                    .with_magnitude(2.0 * other * self.q.w.acos()),
+                    .with_magnitude(two * other * self.q.w.acos()),
             )
         } else {
-            Rotation3::from_axis_angle(
+            Rot3::from_axis_angle(
                 -self
                     .q
                     .get_vector()
-                    .with_magnitude(2.0 * other * (-self.q.w).acos()),
+                    .with_magnitude(two * other * (-self.q.w).acos()),
             )
         }
     }
 }
-impl ops::Div<f64> for Rotation3 {
-    type Output = Rotation3;
-    fn div(self, other: f64) -> Self::Output {
-        self * (1.0 / other)
+impl<T: Float> ops::Div<T> for Rot3<T> {
+    type Output = Rot3<T>;
+    fn div(self, other: T) -> Self::Output {
+        self * (T::ONE / other)
     }
 }
 
-impl Rotation3 {
-    pub fn identity() -> Rotation3 {
-        Rotation3 {
-            q: Quaternion::default(),
-        }
+impl<T: Float> Rot3<T> {
+    pub fn identity() -> Rot3<T> {
+        Rot3 { q: Quat::default() }
     }
-    pub fn new(q: Quaternion) -> Rotation3 {
-        Rotation3 { q }
+    pub fn new(q: Quat<T>) -> Rot3<T> {
+        Rot3 { q }
     }
-    pub fn rotate_vector(&self, vector: Vector3) -> Vector3 {
+    pub fn rotate_vector(&self, vector: Vec3<T>) -> Vec3<T> {
         // https://en.wikipedia.org/wiki/Quaternions_and_spatial_rotation#Using_quaternions_as_rotations
         // Accessed 2025/5/9
-        let other_as_quaternion = Quaternion::from_scalar_vector(0.0, vector);
-        (self.q.clone() * other_as_quaternion * (self.q.inverse())).get_vector()
+        let other_as_quaternion = Quat::from_scalar_vector(T::ZERO, vector);
+        (self.q * other_as_quaternion * (self.q.inverse())).get_vector()
     }
-    pub fn from_axis_angle(v: Vector3) -> Rotation3 {
-        Rotation3 {
-            q: Quaternion::from_rotation_vector(v),
+    pub fn from_axis_angle(v: Vec3<T>) -> Rot3<T> {
+        Rot3 {
+            q: Quat::from_rotation_vector(v),
         }
     }
+    /// Gets the rotation matrix, whose columns are where the rotation takes each axis
+    pub fn to_matrix(&self) -> Mat3<T> {
+        Mat3::from_columns([
+            self.rotate_vector(Vec3::i_hat()),
+            self.rotate_vector(Vec3::j_hat()),
+            self.rotate_vector(Vec3::k_hat()),
+        ])
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Rot3<U> {
+        Rot3 { q: self.q.cast() }
+    }
 }
@@ -0,0 +1,133 @@
+//! The floating point types that vectors, quaternions, matrices and the shapes built on them can be made
+//! of. Every generic type has a generic name and an alias for each precision, and the `f64` alias keeps
+//! the name the type had before it was generic, with an `f` added for `f32`:
+//! [Vec3](crate::vectors::Vec3) has [Vector3](crate::vectors::Vector3) and
+//! [Vector3f](crate::vectors::Vector3f), [Pose](crate::pose3::Pose) has `Pose3` and `Pose3f`, and the
+//! shapes add `Of`, so [LineOf](crate::line::LineOf) has [Line](crate::line::Line) and
+//! [Linef](crate::line::Linef), and [RayOf](crate::ray::RayOf) has `Ray` and `Rayf`.
+//!
+//! Exact predicates, polygons, meshes and triangulations always work in `f64`. Converting `f32` to
+//! `f64` is lossless, so `f32` data gives exactly the same answers once widened with `From` or `cast`.
+use crate::approx::ApproxEq;
+use core::fmt::Debug;
+use core::iter::Sum;
+use core::ops;
+
+/// A floating point scalar, implemented for `f32` and `f64`
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::scalar::Float;
+/// fn hypotenuse<T: Float>(a: T, b: T) -> T {
+///     (a * a + b * b).sqrt()
+/// }
+/// assert_eq!(hypotenuse(3.0f32, 4.0), 5.0);
+/// assert_eq!(hypotenuse(3.0f64, 4.0), 5.0);
+/// assert_eq!(f32::from_f64(0.1).to_f64(), 0.10000000149011612);
+/// ```
+pub trait Float:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + PartialOrd
+    + ApproxEq
+    + Sum
+    + ops::Add<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::Mul<Output = Self>
+    + ops::Div<Output = Self>
+    + ops::Neg<Output = Self>
+    + ops::AddAssign
+    + ops::SubAssign
+    + ops::MulAssign
+    + ops::DivAssign
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    /// The gap between 1 and the next float
+    const EPSILON: Self;
+    /// The absolute threshold `PartialEq` allows on vectors and quaternions, `1E-12` for `f64` and
+    /// `1E-5` for `f32`
+    const NEAR: Self;
+    /// Converts from `f64`, rounding to the nearest value if there isn't an exact one
+    fn from_f64(value: f64) -> Self;
+    /// Converts to `f64`, which is always exact
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn exp(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    /// Orders every value, including NaN, so it can be used for sorting and picking pivots without
+    /// panicking
+    fn total_cmp(&self, other: &Self) -> std::cmp::Ordering;
+}
+
+macro_rules! impl_float {
+    ($type:ident, $near:expr) => {
+        impl Float for $type {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const EPSILON: Self = $type::EPSILON;
+            const NEAR: Self = $near;
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn sqrt(self) -> Self {
+                $type::sqrt(self)
+            }
+            fn abs(self) -> Self {
+                $type::abs(self)
+            }
+            fn sin(self) -> Self {
+                $type::sin(self)
+            }
+            fn cos(self) -> Self {
+                $type::cos(self)
+            }
+            fn acos(self) -> Self {
+                $type::acos(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                $type::atan2(self, other)
+            }
+            fn exp(self) -> Self {
+                $type::exp(self)
+            }
+            fn powi(self, n: i32) -> Self {
+                $type::powi(self, n)
+            }
+            fn max(self, other: Self) -> Self {
+                $type::max(self, other)
+            }
+            fn min(self, other: Self) -> Self {
+                $type::min(self, other)
+            }
+            fn is_nan(self) -> bool {
+                $type::is_nan(self)
+            }
+            fn is_finite(self) -> bool {
+                $type::is_finite(self)
+            }
+            fn total_cmp(&self, other: &Self) -> std::cmp::Ordering {
+                $type::total_cmp(self, other)
+            }
+        }
+    };
+}
+
+impl_float!(f32, 1E-5);
+impl_float!(f64, 1E-12);
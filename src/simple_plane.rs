@@ -1,70 +1,233 @@
-use crate::line::{Line, LineSegment};
-use crate::ray::Ray;
-use crate::utils::matrix::Matrix3;
-use crate::vectors::Vector3;
+use crate::approx::{Tolerance, impl_approx_eq};
+use crate::line::{LineOf, LineSegmentOf};
+use crate::predicates::plane_side;
+use crate::ray::RayOf;
+use crate::scalar::Float;
+use crate::utils::matrix::Mat3;
+use crate::utils::matrix_n::MatN;
+use crate::vectors::Vec3;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub enum Intersection {
-    Once(Vector3),
-    Edge(Vector3),
+pub enum IntersectionOf<T: Float> {
+    Once(Vec3<T>),
+    Edge(Vec3<T>),
     LiesOn,
     Never,
 }
+pub type Intersection = IntersectionOf<f64>;
+pub type Intersectionf = IntersectionOf<f32>;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct SimplePlane {
-    pub origin: Vector3,
-    pub normal: Vector3,
+pub struct SimplePlaneOf<T: Float> {
+    pub origin: Vec3<T>,
+    pub normal: Vec3<T>,
 }
+pub type SimplePlane = SimplePlaneOf<f64>;
+pub type SimplePlanef = SimplePlaneOf<f32>;
 
-impl SimplePlane {
-    pub fn new(origin: Vector3, normal2: Vector3) -> Self {
+impl_approx_eq!(<T> SimplePlaneOf<T>, origin, normal);
+
+/// A plane fitted to points by [SimplePlane::fit_weighted]
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlaneFitOf<T: Float> {
+    pub plane: SimplePlaneOf<T>,
+    /// The root mean square distance of the points from the plane, weighted like the fit
+    pub rms: T,
+    /// The signed distance of each point from the plane, positive on the side the normal points to
+    pub residuals: Vec<T>,
+}
+pub type PlaneFit = PlaneFitOf<f64>;
+pub type PlaneFitf = PlaneFitOf<f32>;
+
+impl<T: Float> SimplePlaneOf<T> {
+    pub fn new(origin: Vec3<T>, normal2: Vec3<T>) -> Self {
         let normal = normal2.hat();
         Self { origin, normal }
     }
-    pub fn from_mxb(mx: f64, my: f64, c: f64) -> Self {
-        SimplePlane::new(Vector3::new(0.0, 0.0, c), Vector3::new(mx, my, -1.0))
-    }
-    pub fn regress(points: &Vec<Vector3>) -> (SimplePlane, f64) {
-        let mut sum_x = 0.0;
-        let mut sum_y = 0.0;
-        let mut sum_z = 0.0;
-        let mut sum_xy = 0.0;
-        let mut sum_xz = 0.0;
-        let mut sum_yz = 0.0;
-        let mut sum_x2 = 0.0;
-        let mut sum_y2 = 0.0;
-        for p in points {
-            sum_x += p.x;
-            sum_y += p.y;
-            sum_z += p.z;
-            sum_x2 += p.x * p.x;
-            sum_y2 += p.y * p.y;
-            sum_xy += p.x * p.y;
-            sum_xz += p.x * p.z;
-            sum_yz += p.y * p.z;
-        }
-        let forward_matrix = Matrix3::new([
-            [sum_x2, sum_xy, sum_x],
-            [sum_xy, sum_y2, sum_y],
-            [sum_x, sum_y, 1.0],
-        ]);
-        let reverse_matrix = forward_matrix.inverse().unwrap();
-        let v = Vector3::new(sum_xz, sum_yz, sum_z);
-        let param_vector = reverse_matrix * v;
-        let mx = param_vector.x;
-        let my = param_vector.y;
-        let c = param_vector.z;
-        let mut square_error = 0.0;
+    pub fn from_mxb(mx: T, my: T, c: T) -> Self {
+        SimplePlaneOf::new(Vec3::new(T::ZERO, T::ZERO, c), Vec3::new(mx, my, -T::ONE))
+    }
+    /// Fits `z = mx * x + my * y + c` to the points by least squares, see [MatN::least_squares]. This
+    /// measures errors along `z`, so it can't find a vertical plane; [SimplePlane::fit] measures them at
+    /// right angles to the plane.
+    ///
+    /// returns: (SimplePlane, T), the plane and the sum of the squared errors in `z`
+    ///
+    /// # Panics
+    ///
+    /// If the points don't determine a single fit, for example if they all lie on one vertical line
+    pub fn regress(points: &Vec<Vec3<T>>) -> (SimplePlaneOf<T>, T) {
+        let design = MatN::from_fn(points.len(), 3, |i, j| match j {
+            0 => points[i].x,
+            1 => points[i].y,
+            _ => T::ONE,
+        });
+        let heights: Vec<T> = points.iter().map(|p| p.z).collect();
+        let fit = design.least_squares(&heights).unwrap();
+        let (mx, my, c) = (fit[0], fit[1], fit[2]);
+        let mut square_error = T::ZERO;
         for pt in points {
             square_error += (pt.x * mx + pt.y * my + c - pt.z).powi(2);
         }
         (Self::from_mxb(mx, my, c), square_error)
     }
 
-    //TODO:test
-    pub fn point_intersects(&self, other: Vector3) -> bool {
-        self.normal.dot(&(self.origin - other)) == 0.0
+    /// Fits a plane to the points by minimizing the sum of their squared distances from it, see
+    /// [SimplePlane::fit_weighted]
+    pub fn fit(points: &[Vec3<T>]) -> Result<PlaneFitOf<T>, String> {
+        Self::fit_weighted(points, &vec![T::ONE; points.len()])
+    }
+    /// Fits a plane to the points by minimizing the weighted sum of their squared distances from it.
+    /// The plane passes through the weighted centroid, and its normal is the direction the points
+    /// spread least in, the eigenvector of the smallest eigenvalue of their covariance. The normal is
+    /// oriented so that its largest component is positive.
+    ///
+    /// # Arguments
+    ///
+    /// * `points`: The points to fit
+    /// * `weights`: How much each point counts, which must not be negative. A point with no weight is
+    ///   ignored by the fit, though it still gets a residual.
+    ///
+    /// returns: Result<PlaneFit, String>, which is an error if the weights don't match the points, or if
+    /// the points with weight are too few, not finite, or all on one line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::simple_plane::SimplePlane;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// // A vertical wall, x = 2, which SimplePlane::regress can't represent
+    /// let points = [
+    ///     Vector3::new(2.0, 0.0, 0.0),
+    ///     Vector3::new(2.0, 1.0, 0.0),
+    ///     Vector3::new(2.0, 0.0, 1.0),
+    ///     Vector3::new(2.1, 1.0, 1.0),
+    ///     Vector3::new(9.0, 9.0, 9.0),
+    /// ];
+    /// let fit = SimplePlane::fit_weighted(&points, &[1.0, 1.0, 1.0, 1.0, 0.0]).unwrap();
+    /// assert!(fit.plane.normal.dot(&Vector3::i_hat()) > 0.99);
+    /// assert!(fit.rms < 0.03);
+    /// assert_eq!(fit.residuals.len(), 5);
+    /// assert!(fit.residuals[4] > 6.0);
+    /// let line = [Vector3::zero(), Vector3::i_hat(), Vector3::i_hat() * 2.0];
+    /// assert!(SimplePlane::fit(&line).is_err());
+    /// ```
+    pub fn fit_weighted(points: &[Vec3<T>], weights: &[T]) -> Result<PlaneFitOf<T>, String> {
+        if points.len() != weights.len() {
+            return Err(format!(
+                "{} weights were given for {} points",
+                weights.len(),
+                points.len()
+            ));
+        }
+        if let Some(i) = weights.iter().position(|w| !w.is_finite() || *w < T::ZERO) {
+            return Err(format!(
+                "Weight {i} is {:?}, not a finite non-negative number",
+                weights[i]
+            ));
+        }
+        let weighted: Vec<(Vec3<T>, T)> = points
+            .iter()
+            .zip(weights)
+            .filter(|(_, w)| **w > T::ZERO)
+            .map(|(p, w)| (*p, *w))
+            .collect();
+        if weighted.len() < 3 {
+            return Err("A plane needs at least 3 points with weight".to_owned());
+        }
+        if let Some((p, _)) = weighted
+            .iter()
+            .find(|(p, _)| !(p.x.is_finite() && p.y.is_finite() && p.z.is_finite()))
+        {
+            return Err(format!("The point {p:?} is not finite"));
+        }
+        let total: T = weighted.iter().map(|(_, w)| *w).sum();
+        let centroid = weighted
+            .iter()
+            .fold(Vec3::zero(), |sum, (p, w)| sum + *p * *w)
+            / total;
+        let covariance = weighted.iter().fold(Mat3::zero(), |sum, (p, w)| {
+            sum + Mat3::outer(&(*p - centroid), &(*p - centroid)) * *w
+        });
+        let (spread, axes) = covariance.symmetric_eigen();
+        // The second axis must stand out from rounding in the first, or there is no single plane
+        if spread.y <= spread.x * T::EPSILON * T::from_f64(100.0) {
+            return Err(
+                "The points are all on one line or at one point, so they don't determine a plane"
+                    .to_owned(),
+            );
+        }
+        let mut normal = axes.column_vectors()[2];
+        let largest = normal.abs();
+        let lead = if largest.x >= largest.y.max(largest.z) {
+            normal.x
+        } else if largest.y >= largest.z {
+            normal.y
+        } else {
+            normal.z
+        };
+        if lead < T::ZERO {
+            normal = -normal;
+        }
+        let plane = SimplePlaneOf::new(centroid, normal);
+        let residuals: Vec<T> = points
+            .iter()
+            .map(|p| (*p - centroid).dot(&normal))
+            .collect();
+        let square_error: T = residuals
+            .iter()
+            .zip(weights)
+            .filter(|(_, w)| **w > T::ZERO)
+            .map(|(r, w)| *r * *r * *w)
+            .sum();
+        Ok(PlaneFitOf {
+            plane,
+            rms: (square_error / total).sqrt(),
+            residuals,
+        })
+    }
+
+    /// Determines if a point lies exactly on the plane, see [plane_side]
+    pub fn point_intersects(&self, other: Vec3<T>) -> bool {
+        self.point_intersects_within(other, Tolerance::EXACT)
+    }
+    /// Determines if a point lies on the plane, or within the tolerance of it. The relative part of the
+    /// tolerance scales with the size of the coordinates involved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::simple_plane::SimplePlane;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let plane = SimplePlane::new(Vector3::new(0.0, 0.0, 1.0), Vector3::k_hat());
+    /// let pt = Vector3::new(5.0, 5.0, 1.0 + 1E-10);
+    /// assert!(!plane.point_intersects(pt));
+    /// assert!(plane.point_intersects_within(pt, Tolerance::absolute(1E-9)));
+    /// ```
+    pub fn point_intersects_within(&self, other: Vec3<T>, tolerance: Tolerance) -> bool {
+        self.side_within(other, tolerance) == 0.0
+    }
+    /// Which side of the plane a point is on, as [plane_side] but zero within the tolerance
+    fn side_within(&self, pt: Vec3<T>, tolerance: Tolerance) -> f64 {
+        let side = plane_side(self.origin.cast(), self.normal.cast(), pt.cast());
+        let distance = self.normal.dot(&(pt - self.origin)).to_f64();
+        let scale = pt.magnitude().max(self.origin.magnitude()).to_f64();
+        if side != 0.0 && tolerance.is_zero(distance, scale) {
+            0.0
+        } else {
+            side
+        }
+    }
+    /// How fast a direction moves away from the plane, as [plane_side] but zero within the tolerance
+    fn approach_within(&self, direction: Vec3<T>, tolerance: Tolerance) -> f64 {
+        let approach = plane_side(Vec3::zero(), self.normal.cast(), direction.cast());
+        let speed = self.normal.dot(&direction).to_f64();
+        if approach != 0.0 && tolerance.is_zero(speed, direction.magnitude().to_f64()) {
+            0.0
+        } else {
+            approach
+        }
     }
     /// Determines if/where a ray will hit this plane.
     ///
@@ -88,25 +251,37 @@ impl SimplePlane {
     /// assert_eq!(plane.ray_intersects(ray2),Intersection::LiesOn);
     /// assert_eq!(plane.ray_intersects(ray3),Intersection::Once(Vector3::new(-5.0,5.0,2.0)));
     /// ```
-    pub fn ray_intersects(&self, other: Ray) -> Intersection {
-        let adjusted_ray = Ray::new(other.origin - self.origin, other.direction);
-        if adjusted_ray.direction.dot(&self.normal) == 0.0
-            && adjusted_ray.origin.dot(&self.normal) != 0.0
-        {
-            Intersection::Never
-        } else if adjusted_ray.direction.dot(&self.normal) == 0.0 {
-            Intersection::LiesOn
+    pub fn ray_intersects(&self, other: RayOf<T>) -> IntersectionOf<T> {
+        self.ray_intersects_within(other, Tolerance::EXACT)
+    }
+    /// Like [SimplePlane::ray_intersects], but treating an origin within the tolerance of the plane as on
+    /// it, and a direction within the tolerance of parallel to it as parallel
+    pub fn ray_intersects_within(
+        &self,
+        other: RayOf<T>,
+        tolerance: Tolerance,
+    ) -> IntersectionOf<T> {
+        let side = self.side_within(other.origin, tolerance);
+        let approach = self.approach_within(other.direction, tolerance);
+        if approach == 0.0 && side != 0.0 {
+            IntersectionOf::Never
+        } else if approach == 0.0 {
+            IntersectionOf::LiesOn
+        } else if side == 0.0 {
+            IntersectionOf::Once(other.origin)
+        } else if (side > 0.0) == (approach > 0.0) {
+            // Heading away from the plane
+            IntersectionOf::Never
         } else {
-            let no = self.normal.dot(&adjusted_ray.origin);
-            let nv = self.normal.dot(&adjusted_ray.direction);
-            if -no / nv < 0.0 {
-                return Intersection::Never;
-            }
-            return Intersection::Once(
-                adjusted_ray.origin + adjusted_ray.direction * (-no / nv) + self.origin,
-            );
+            IntersectionOf::Once(
+                other.origin + other.direction * self.parameter(other.origin, other.direction),
+            )
         }
     }
+    /// How far along `direction` from `origin` the plane is, in multiples of `direction`
+    fn parameter(&self, origin: Vec3<T>, direction: Vec3<T>) -> T {
+        -self.normal.dot(&(origin - self.origin)) / self.normal.dot(&direction)
+    }
     /// Determines if/where a segment will hit this plane.
     ///
     /// # Arguments
@@ -129,54 +304,102 @@ impl SimplePlane {
     /// assert_eq!(plane.ray_intersects(ray2),Intersection::LiesOn);
     /// assert_eq!(plane.ray_intersects(ray3),Intersection::Once(Vector3::new(-5.0,5.0,2.0)));
     /// ```
-    pub fn line_intersects(&self, other: Line) -> Intersection {
-        let adjusted_line = Line::new(other.origin - self.origin, other.direction);
-        if adjusted_line.direction.dot(&self.normal) == 0.0
-            && adjusted_line.origin.dot(&self.normal) != 0.0
-        {
-            Intersection::Never
-        } else if adjusted_line.direction.dot(&self.normal) == 0.0 {
-            return Intersection::LiesOn;
+    pub fn line_intersects(&self, other: LineOf<T>) -> IntersectionOf<T> {
+        self.line_intersects_within(other, Tolerance::EXACT)
+    }
+    /// Like [SimplePlane::line_intersects], but treating a line within the tolerance of parallel to the
+    /// plane as parallel, and so lying on it if its origin is within the tolerance of it
+    pub fn line_intersects_within(
+        &self,
+        other: LineOf<T>,
+        tolerance: Tolerance,
+    ) -> IntersectionOf<T> {
+        let side = self.side_within(other.origin, tolerance);
+        if self.approach_within(other.direction, tolerance) != 0.0 {
+            IntersectionOf::Once(other.at(self.parameter(other.origin, other.direction)))
+        } else if side != 0.0 {
+            IntersectionOf::Never
         } else {
-            let no = self.normal.dot(&adjusted_line.origin);
-            let nv = self.normal.dot(&adjusted_line.direction);
-            return Intersection::Once(
-                adjusted_line.origin + adjusted_line.direction * (-no / nv) + self.origin,
-            );
+            IntersectionOf::LiesOn
         }
     }
-    //TODO:test
-    pub fn segment_intersects(&self, other: LineSegment) -> Intersection {
-        let adjusted_line = LineSegment::new(other.a - self.origin, other.b - self.origin);
-        if (adjusted_line.b - adjusted_line.a).dot(&self.normal) == 0.0
-            && adjusted_line.a.dot(&self.normal) != 0.0
-        {
-            Intersection::Never
-        } else if (adjusted_line.b - adjusted_line.a).dot(&self.normal) == 0.0 {
-            return Intersection::LiesOn;
+    /// Determines if/where a segment will hit this plane. A segment touching the plane at one end
+    /// gives [Intersection::Edge] at that end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::line::LineSegment;
+    /// use YetAnotherGeometryLibrary::simple_plane::{Intersection, SimplePlane};
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let plane = SimplePlane::new(Vector3::new(0.0, 0.0, 1.0), Vector3::k_hat());
+    /// let crossing = LineSegment::new(Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 4.0, 4.0));
+    /// assert_eq!(plane.segment_intersects(crossing), Intersection::Once(Vector3::new(2.0, 1.0, 1.0)));
+    /// let touching = LineSegment::new(Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 4.0, 1.0));
+    /// assert_eq!(plane.segment_intersects(touching), Intersection::Edge(Vector3::new(2.0, 4.0, 1.0)));
+    /// let short = LineSegment::new(Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 4.0, 0.5));
+    /// assert_eq!(plane.segment_intersects(short), Intersection::Never);
+    /// let flat = LineSegment::new(Vector3::new(2.0, 0.0, 1.0), Vector3::new(2.0, 4.0, 1.0));
+    /// assert_eq!(plane.segment_intersects(flat), Intersection::LiesOn);
+    /// ```
+    pub fn segment_intersects(&self, other: LineSegmentOf<T>) -> IntersectionOf<T> {
+        self.segment_intersects_within(other, Tolerance::EXACT)
+    }
+    /// Like [SimplePlane::segment_intersects], but treating ends within the tolerance of the plane as on
+    /// it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::line::LineSegment;
+    /// use YetAnotherGeometryLibrary::simple_plane::{Intersection, SimplePlane};
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let plane = SimplePlane::new(Vector3::new(0.0, 0.0, 1.0), Vector3::k_hat());
+    /// let nearly = LineSegment::new(Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 4.0, 1.0 - 1E-12));
+    /// assert_eq!(plane.segment_intersects(nearly), Intersection::Never);
+    /// assert_eq!(
+    ///     plane.segment_intersects_within(nearly, Tolerance::default()),
+    ///     Intersection::Edge(nearly.b)
+    /// );
+    /// ```
+    pub fn segment_intersects_within(
+        &self,
+        other: LineSegmentOf<T>,
+        tolerance: Tolerance,
+    ) -> IntersectionOf<T> {
+        let side_a = self.side_within(other.a, tolerance);
+        let side_b = self.side_within(other.b, tolerance);
+        if side_a == 0.0 && side_b == 0.0 {
+            IntersectionOf::LiesOn
+        } else if side_a == 0.0 {
+            IntersectionOf::Edge(other.a)
+        } else if side_b == 0.0 {
+            IntersectionOf::Edge(other.b)
+        } else if (side_a > 0.0) == (side_b > 0.0) {
+            IntersectionOf::Never
         } else {
-            let no = self.normal.dot(&adjusted_line.a);
-            let nv = self.normal.dot(&(adjusted_line.b - adjusted_line.a));
-            if 1.0 > (-no / nv) && (-no / nv) > 0.0 {
-                return Intersection::Once(adjusted_line.b * (-no / nv) + self.origin);
-            } else if 1.0 == (-no / nv) || (-no / nv) == 0.0 {
-                return Intersection::Edge(adjusted_line.b * (-no / nv) + self.origin);
-            }
-            return Intersection::Never;
+            let t = self
+                .parameter(other.a, other.b - other.a)
+                .max(T::ZERO)
+                .min(T::ONE);
+            IntersectionOf::Once(other.a + (other.b - other.a) * t)
         }
     }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct CoordinatePlane {
-    pub origin: Vector3,
-    pub x: Vector3,
-    pub y: Vector3,
+pub struct CoordinatePlaneOf<T: Float> {
+    pub origin: Vec3<T>,
+    pub x: Vec3<T>,
+    pub y: Vec3<T>,
 }
+pub type CoordinatePlane = CoordinatePlaneOf<f64>;
+pub type CoordinatePlanef = CoordinatePlaneOf<f32>;
 
-impl CoordinatePlane {
-    pub fn new(origin: Vector3, x: Vector3, y: Vector3) -> Result<Self, String> {
-        if x.dot(&y) == 0.0 {
+impl<T: Float> CoordinatePlaneOf<T> {
+    pub fn new(origin: Vec3<T>, x: Vec3<T>, y: Vec3<T>) -> Result<Self, String> {
+        if x.dot(&y) == T::ZERO {
             Ok(Self { origin, x, y })
         } else {
             Err("Non-orthagnal vectors cannot form a plane".to_owned())
@@ -1,17 +1,24 @@
-use crate::ray::Ray;
-use crate::simple_plane::Intersection;
-use crate::vectors::{Vector2, Vector3};
+use crate::approx::impl_approx_eq;
+use crate::predicates::{orient_direction, orient2d, orient3d};
+use crate::ray::RayOf;
+use crate::scalar::Float;
+use crate::simple_plane::IntersectionOf;
+use crate::vectors::{Vec3, Vector2};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct SimpleTriangle {
-    pub a: Vector3,
-    pub b: Vector3,
-    pub c: Vector3,
+pub struct SimpleTriangleOf<T: Float> {
+    pub a: Vec3<T>,
+    pub b: Vec3<T>,
+    pub c: Vec3<T>,
 }
+pub type SimpleTriangle = SimpleTriangleOf<f64>;
+pub type SimpleTrianglef = SimpleTriangleOf<f32>;
 
-impl SimpleTriangle {
-    pub fn new(a: Vector3, b: Vector3, c: Vector3) -> SimpleTriangle {
-        SimpleTriangle { a, b, c }
+impl_approx_eq!(<T> SimpleTriangleOf<T>, a, b, c);
+
+impl<T: Float> SimpleTriangleOf<T> {
+    pub fn new(a: Vec3<T>, b: Vec3<T>, c: Vec3<T>) -> SimpleTriangleOf<T> {
+        SimpleTriangleOf { a, b, c }
     }
 
     /// Computes the normal of the triangle. Normal is defined as `ABxAC`, or a vector with the length of the area of the triangle.
@@ -24,61 +31,122 @@ impl SimpleTriangle {
     ///
     /// ```
     ///     //TODO:test
-    pub fn normal(&self) -> Vector3 {
-        (self.b - self.a).cross(&(self.c - self.b)) / 2.0
+    pub fn normal(&self) -> Vec3<T> {
+        (self.b - self.a).cross(&(self.c - self.b)) / T::from_f64(2.0)
     }
-    pub fn center(&self) -> Vector3 {
-        (self.a + self.b + self.c) / 3.0
+    pub fn center(&self) -> Vec3<T> {
+        (self.a + self.b + self.c) / T::from_f64(3.0)
     }
-    pub fn normal_ray(&self) -> Ray {
-        Ray::new(self.center(), self.normal())
+    pub fn normal_ray(&self) -> RayOf<T> {
+        RayOf::new(self.center(), self.normal())
     }
-    pub fn area(&self) -> f64 {
+    pub fn area(&self) -> T {
         self.normal().magnitude()
     }
-    //TODO:test
-    pub fn point_intersects(&self, other: Vector3) -> Intersection {
-        let other_adj = other - self.a;
-        let b_adj = self.b - self.a;
-        let c_adj = self.c - self.a;
-        if other_adj.dot(&self.normal()) == 0.0 {
-            let coord = Vector2::new(
-                other_adj.dot(&b_adj) / (b_adj.dot(&b_adj)),
-                other_adj.dot(&c_adj) / (c_adj.dot(&c_adj)),
-            );
-            if (coord.x >= 0.0) && (coord.y >= 0.0) && (coord.x + coord.y <= 1.0) {
-                if coord.x == 0.0 || coord.y == 0.0 || coord.x + coord.y == 1.0 {
-                    Intersection::Edge(other)
-                } else {
-                    Intersection::Once(other)
-                }
+    /// Determines if a point lies on the triangle, giving [IntersectionOf::Edge] if it is on one of its
+    /// edges and [IntersectionOf::Once] if it is inside. The point must be exactly coplanar with the
+    /// corners, see [orient3d].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::simple_plane::Intersection;
+    /// use YetAnotherGeometryLibrary::simple_tri::SimpleTriangle;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let tri = SimpleTriangle::new(Vector3::zero(), Vector3::new(4.0, 0.0, 2.0), Vector3::new(4.0, 1.0, 2.0));
+    /// let inside = Vector3::new(3.0, 0.5, 1.5);
+    /// assert_eq!(tri.point_intersects(inside), Intersection::Once(inside));
+    /// let edge = Vector3::new(2.0, 0.0, 1.0);
+    /// assert_eq!(tri.point_intersects(edge), Intersection::Edge(edge));
+    /// assert_eq!(tri.point_intersects(Vector3::new(1.0, 0.5, 0.5)), Intersection::Never);
+    /// assert_eq!(tri.point_intersects(Vector3::new(3.0, 0.5, 1.6)), Intersection::Never);
+    /// ```
+    pub fn point_intersects(&self, other: Vec3<T>) -> IntersectionOf<T> {
+        if orient3d(self.a.cast(), self.b.cast(), self.c.cast(), other.cast()) != 0.0 {
+            return IntersectionOf::Never;
+        }
+        // Drop the coordinate the triangle is steepest along, which keeps its shape from collapsing
+        let normal = self.normal();
+        let flatten: fn(Vec3<T>) -> Vector2 =
+            if normal.x.abs() >= normal.y.abs().max(normal.z.abs()) {
+                |p| Vector2::new(p.y.to_f64(), p.z.to_f64())
+            } else if normal.y.abs() >= normal.z.abs() {
+                |p| Vector2::new(p.z.to_f64(), p.x.to_f64())
             } else {
-                Intersection::Never
-            }
+                |p| Vector2::new(p.x.to_f64(), p.y.to_f64())
+            };
+        let (a, b, c, pt) = (
+            flatten(self.a),
+            flatten(self.b),
+            flatten(self.c),
+            flatten(other),
+        );
+        let sides = [orient2d(a, b, pt), orient2d(b, c, pt), orient2d(c, a, pt)];
+        if sides.iter().all(|s| *s == 0.0) {
+            // A degenerate triangle
+            return IntersectionOf::Never;
+        }
+        if !sides.iter().all(|s| *s >= 0.0) && !sides.iter().all(|s| *s <= 0.0) {
+            IntersectionOf::Never
+        } else if sides.contains(&0.0) {
+            IntersectionOf::Edge(other)
         } else {
-            Intersection::Never
+            IntersectionOf::Once(other)
         }
     }
-    //TODO:test
-    pub fn ray_intersects(&self, other: Ray) -> Intersection {
-        let origin = self.a;
-        let adjusted_ray = Ray::new(other.origin - origin, other.direction);
-
-        if adjusted_ray.direction.dot(&self.normal()) == 0.0
-            && adjusted_ray.origin.dot(&self.normal()) != 0.0
-        {
-            Intersection::Never
-        } else if adjusted_ray.direction.dot(&self.normal()) == 0.0 {
-            return Intersection::LiesOn;
+    /// Determines if/where a ray hits the triangle. Whether it hits, and whether it hits an edge, is
+    /// decided exactly, though the point it hits is rounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::ray::Ray;
+    /// use YetAnotherGeometryLibrary::simple_plane::Intersection;
+    /// use YetAnotherGeometryLibrary::simple_tri::SimpleTriangle;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let tri = SimpleTriangle::new(Vector3::zero(), Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 2.0, 0.0));
+    /// let down = Vector3::new(0.0, 0.0, -1.0);
+    /// let hit = tri.ray_intersects(Ray::new(Vector3::new(0.5, 0.5, 3.0), down));
+    /// assert_eq!(hit, Intersection::Once(Vector3::new(0.5, 0.5, 0.0)));
+    /// let edge = tri.ray_intersects(Ray::new(Vector3::new(1.0, 1.0, 3.0), down));
+    /// assert_eq!(edge, Intersection::Edge(Vector3::new(1.0, 1.0, 0.0)));
+    /// assert_eq!(tri.ray_intersects(Ray::new(Vector3::new(1.5, 1.5, 3.0), down)), Intersection::Never);
+    /// assert_eq!(tri.ray_intersects(Ray::new(Vector3::new(0.5, 0.5, -3.0), down)), Intersection::Never);
+    /// ```
+    pub fn ray_intersects(&self, other: RayOf<T>) -> IntersectionOf<T> {
+        let (a, b, c) = (self.a.cast(), self.b.cast(), self.c.cast());
+        let side = orient3d(a, b, c, other.origin.cast());
+        let approach = orient_direction(a, b, c, other.direction.cast());
+        if approach == 0.0 && side != 0.0 {
+            return IntersectionOf::Never;
+        } else if approach == 0.0 {
+            return IntersectionOf::LiesOn;
+        } else if side == 0.0 {
+            return self.point_intersects(other.origin);
+        } else if (side > 0.0) == (approach > 0.0) {
+            return IntersectionOf::Never;
+        }
+        // Which side of each edge the ray passes, seen looking along it
+        let o = other.origin.cast();
+        let d = other.direction.cast();
+        let mut sides = [
+            orient_direction(o, a, b, d),
+            orient_direction(o, b, c, d),
+            orient_direction(o, c, a, d),
+        ];
+        if approach > 0.0 {
+            sides = sides.map(|s| -s);
+        }
+        if sides.iter().any(|s| *s > 0.0) {
+            return IntersectionOf::Never;
+        }
+        let normal = self.normal();
+        let RayOf { origin, direction } = other;
+        let pt = origin + direction * (-normal.dot(&(origin - self.a)) / normal.dot(&direction));
+        if sides.contains(&0.0) {
+            IntersectionOf::Edge(pt)
         } else {
-            let no = self.normal().dot(&adjusted_ray.origin);
-            let nv = self.normal().dot(&adjusted_ray.direction);
-            if -no / nv < 0.0 {
-                return Intersection::Never;
-            } else {
-                let pt = adjusted_ray.origin + adjusted_ray.direction * (-no / nv) + origin;
-                return self.point_intersects(pt);
-            }
+            IntersectionOf::Once(pt)
         }
     }
 }
@@ -1,12 +1,22 @@
-use crate::vectors::Vector3;
+use crate::approx::impl_approx_eq;
+use crate::scalar::Float;
+use crate::vectors::{Vec3, Vec4};
 use core::ops;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
-pub struct Matrix3 {
-    pub data: [[f64; 3]; 3],
+pub struct Mat3<T> {
+    pub data: [[T; 3]; 3],
 }
-impl ops::Neg for Matrix3 {
-    type Output = Matrix3;
+pub type Matrix3 = Mat3<f64>;
+pub type Matrix3f = Mat3<f32>;
+impl_approx_eq!(<T> Mat3<T>, data);
+impl From<Matrix3f> for Matrix3 {
+    fn from(m: Matrix3f) -> Self {
+        m.cast()
+    }
+}
+impl<T: Float> ops::Neg for Mat3<T> {
+    type Output = Mat3<T>;
     fn neg(self) -> Self::Output {
         Self::new([
             [-self.data[0][0], -self.data[0][1], -self.data[0][2]],
@@ -15,21 +25,45 @@ impl ops::Neg for Matrix3 {
         ])
     }
 }
-impl ops::Mul<Vector3> for Matrix3 {
-    type Output = Vector3;
-    fn mul(self, rhs: Vector3) -> Self::Output {
+impl<T: Float> ops::Add<Mat3<T>> for Mat3<T> {
+    type Output = Mat3<T>;
+    fn add(self, rhs: Mat3<T>) -> Self::Output {
+        Mat3::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.data[i][j] + rhs.data[i][j])
+        }))
+    }
+}
+impl<T: Float> ops::Sub<Mat3<T>> for Mat3<T> {
+    type Output = Mat3<T>;
+    fn sub(self, rhs: Mat3<T>) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl<T: Float> ops::Mul<Mat3<T>> for Mat3<T> {
+    type Output = Mat3<T>;
+    fn mul(self, rhs: Mat3<T>) -> Self::Output {
+        let columns = rhs.column_vectors();
+        let rows = self.row_vectors();
+        Mat3::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| rows[i].dot(&columns[j]))
+        }))
+    }
+}
+impl<T: Float> ops::Mul<Vec3<T>> for Mat3<T> {
+    type Output = Vec3<T>;
+    fn mul(self, rhs: Vec3<T>) -> Self::Output {
         let row_vecs = self.row_vectors();
-        Vector3::new(
+        Vec3::new(
             rhs.dot(&row_vecs[0]),
             rhs.dot(&row_vecs[1]),
             rhs.dot(&row_vecs[2]),
         )
     }
 }
-impl ops::Mul<f64> for Matrix3 {
-    type Output = Matrix3;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Matrix3::new([
+impl<T: Float> ops::Mul<T> for Mat3<T> {
+    type Output = Mat3<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Mat3::new([
             [
                 self.data[0][0] * rhs,
                 self.data[0][1] * rhs,
@@ -49,28 +83,82 @@ impl ops::Mul<f64> for Matrix3 {
     }
 }
 
-impl Matrix3 {
-    pub fn new(data: [[f64; 3]; 3]) -> Self {
+impl<T: Float> Mat3<T> {
+    pub fn new(data: [[T; 3]; 3]) -> Self {
         Self { data }
     }
     pub fn identity() -> Self {
-        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+        let (o, l) = (T::ZERO, T::ONE);
+        Self::new([[l, o, o], [o, l, o], [o, o, l]])
+    }
+    pub fn zero() -> Self {
+        Self::new([[T::ZERO; 3]; 3])
     }
-    fn from_flat(data: [f64; 9]) -> Self {
+    pub fn from_rows(rows: [Vec3<T>; 3]) -> Self {
+        Self::new(rows.map(|r| r.as_array()))
+    }
+    pub fn from_columns(columns: [Vec3<T>; 3]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+    /// Builds the outer product `a bᵀ`, the matrix that takes `v` to `a * b.dot(v)`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let a = Vector3::new(1.0, 2.0, 3.0);
+    /// let b = Vector3::new(0.0, 1.0, -1.0);
+    /// let v = Vector3::new(4.0, 5.0, 6.0);
+    /// assert_eq!(Matrix3::outer(&a, &b) * v, a * b.dot(&v));
+    /// assert_eq!(Matrix3::outer(&a, &b).trace(), a.dot(&b));
+    /// ```
+    pub fn outer(a: &Vec3<T>, b: &Vec3<T>) -> Self {
+        Self::from_rows([*b * a.x, *b * a.y, *b * a.z])
+    }
+    /// Builds the skew-symmetric matrix that takes `w` to `v.cross(w)`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let v = Vector3::new(1.0, 2.0, 3.0);
+    /// let w = Vector3::new(-2.0, 0.5, 4.0);
+    /// assert_eq!(Matrix3::skew(&v) * w, v.cross(&w));
+    /// assert_eq!(Matrix3::skew(&v).transpose(), -Matrix3::skew(&v));
+    /// ```
+    pub fn skew(v: &Vec3<T>) -> Self {
+        let o = T::ZERO;
+        Self::new([[o, -v.z, v.y], [v.z, o, -v.x], [-v.y, v.x, o]])
+    }
+    fn from_flat(data: [T; 9]) -> Self {
         Self::new([
             [data[0], data[1], data[2]],
             [data[3], data[4], data[5]],
             [data[6], data[7], data[8]],
         ])
     }
-    pub fn row_vectors(&self) -> Vec<Vector3> {
+    pub fn row_vectors(&self) -> Vec<Vec3<T>> {
         vec![
-            Vector3::new(self.data[0][0], self.data[0][1], self.data[0][2]),
-            Vector3::new(self.data[1][0], self.data[1][1], self.data[1][2]),
-            Vector3::new(self.data[2][0], self.data[2][1], self.data[2][2]),
+            Vec3::new(self.data[0][0], self.data[0][1], self.data[0][2]),
+            Vec3::new(self.data[1][0], self.data[1][1], self.data[1][2]),
+            Vec3::new(self.data[2][0], self.data[2][1], self.data[2][2]),
         ]
     }
-    fn flat_array(&self) -> [f64; 9] {
+    pub fn column_vectors(&self) -> Vec<Vec3<T>> {
+        self.transpose().row_vectors()
+    }
+    pub fn transpose(&self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.data[j][i])
+        }))
+    }
+    /// The sum of the diagonal
+    pub fn trace(&self) -> T {
+        self.data[0][0] + self.data[1][1] + self.data[2][2]
+    }
+    fn flat_array(&self) -> [T; 9] {
         [
             self.data[0][0],
             self.data[0][1],
@@ -83,7 +171,7 @@ impl Matrix3 {
             self.data[2][2],
         ]
     }
-    pub fn determinant(&self) -> f64 {
+    pub fn determinant(&self) -> T {
         self.data[0][0] * (self.data[1][1] * self.data[2][2] - self.data[1][2] * self.data[2][1])
             - self.data[0][1]
                 * (self.data[1][0] * self.data[2][2] - self.data[1][2] * self.data[2][0])
@@ -93,14 +181,14 @@ impl Matrix3 {
     pub fn inverse(&self) -> Option<Self> {
         let mat = self.flat_array();
         let det = self.determinant();
-        if det == 0.0 {
+        if det == T::ZERO {
             None
         } else {
             let cof = [
                 mat[4] * mat[8] - mat[5] * mat[7],
                 mat[5] * mat[6] - mat[3] * mat[8],
                 mat[3] * mat[7] - mat[4] * mat[6],
-                mat[2] * mat[7] - mat[1] * mat[6],
+                mat[2] * mat[7] - mat[1] * mat[8],
                 mat[0] * mat[8] - mat[2] * mat[6],
                 mat[1] * mat[6] - mat[0] * mat[7],
                 mat[1] * mat[5] - mat[2] * mat[4],
@@ -110,7 +198,397 @@ impl Matrix3 {
             let adj = [
                 cof[0], cof[3], cof[6], cof[1], cof[4], cof[7], cof[2], cof[5], cof[8],
             ];
-            Some(Self::from_flat(adj) * (1.0 / det))
+            Some(Self::from_flat(adj) * (T::ONE / det))
+        }
+    }
+    /// Solves `self * x = rhs` for `x` by Gaussian elimination with partial pivoting, which is both
+    /// faster and more accurate than multiplying by the inverse
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs`: The result of the product
+    ///
+    /// returns: Option<Vector3>, which is `None` if the matrix is singular. NaN entries in the matrix or
+    /// `rhs` give NaN in the solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let m = Matrix3::new([[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]]);
+    /// let x = Vector3::new(1.0, -2.0, 3.0);
+    /// assert_eq!(m.solve(m * x), Some(x));
+    /// let flat = Matrix3::new([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 1.0]]);
+    /// assert_eq!(flat.solve(x), None);
+    /// ```
+    pub fn solve(&self, rhs: Vec3<T>) -> Option<Vec3<T>> {
+        let mut rows = self.data;
+        let mut b = rhs.as_array();
+        for col in 0..3 {
+            let pivot = (col..3)
+                .max_by(|&i, &j| rows[i][col].abs().total_cmp(&rows[j][col].abs()))
+                .unwrap();
+            if rows[pivot][col] == T::ZERO {
+                return None;
+            }
+            rows.swap(col, pivot);
+            b.swap(col, pivot);
+            let pivot_row = rows[col];
+            for row in col + 1..3 {
+                let factor = rows[row][col] / pivot_row[col];
+                for (entry, above) in rows[row].iter_mut().zip(pivot_row).skip(col) {
+                    *entry -= factor * above;
+                }
+                b[row] -= factor * b[col];
+            }
         }
+        let mut x = [T::ZERO; 3];
+        for row in (0..3).rev() {
+            let known: T = (row + 1..3).map(|k| rows[row][k] * x[k]).sum();
+            x[row] = (b[row] - known) / rows[row][row];
+        }
+        Some(Vec3::new(x[0], x[1], x[2]))
+    }
+    /// Finds the eigenvalues and eigenvectors of a symmetric matrix by the cyclic Jacobi method, which
+    /// turns the matrix diagonal by a sequence of plane rotations. Only the upper triangle is read, so a
+    /// matrix that isn't quite symmetric through rounding is treated as if it were.
+    ///
+    /// returns: (Vector3, Matrix3), the eigenvalues from largest to smallest, and a rotation whose
+    /// columns are the matching unit eigenvectors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let m = Matrix3::new([[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 5.0]]);
+    /// let (values, vectors) = m.symmetric_eigen();
+    /// assert_eq!(values, Vector3::new(5.0, 3.0, 1.0));
+    /// for (value, vector) in values.as_array().iter().zip(vectors.column_vectors()) {
+    ///     assert_eq!(m * vector, vector * *value);
+    /// }
+    /// assert!((vectors.determinant() - 1.0).abs() < 1E-12);
+    /// ```
+    pub fn symmetric_eigen(&self) -> (Vec3<T>, Mat3<T>) {
+        let mut a: [[T; 3]; 3] =
+            std::array::from_fn(|i| std::array::from_fn(|j| self.data[i.min(j)][i.max(j)]));
+        let mut vectors = Self::identity().data;
+        let scale: T = a.iter().flatten().map(|x| *x * *x).sum();
+        for _ in 0..50 {
+            let off_diagonal = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+            if off_diagonal <= scale * T::EPSILON * T::EPSILON {
+                break;
+            }
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                if a[p][q] == T::ZERO {
+                    continue;
+                }
+                let (c, s) = jacobi_rotation(a[p][p], a[q][q], a[p][q]);
+                // Rotate rows p and q, then columns p and q, which zeroes a[p][q]
+                let (row_p, row_q) = (a[p], a[q]);
+                a[p] = std::array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+                a[q] = std::array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+                for row in a.iter_mut().chain(vectors.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+            }
+        }
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| a[j][j].total_cmp(&a[i][i]));
+        let columns = Self::new(vectors).column_vectors();
+        let mut sorted = order.map(|i| columns[i]);
+        if sorted[0].cross(&sorted[1]).dot(&sorted[2]) < T::ZERO {
+            sorted[2] = -sorted[2];
+        }
+        (
+            Vec3::new(
+                a[order[0]][order[0]],
+                a[order[1]][order[1]],
+                a[order[2]][order[2]],
+            ),
+            Self::from_columns(sorted),
+        )
+    }
+    /// Finds the singular value decomposition `self = u * diag(s) * vᵀ` by one-sided Jacobi rotations,
+    /// which orthogonalize the columns of the matrix directly rather than squaring it
+    ///
+    /// returns: (Matrix3, Vector3, Matrix3), `u`, the singular values `s` from largest to smallest, and
+    /// `v`. Both `u` and `v` are orthogonal, but either may mirror.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix3;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let m = Matrix3::new([[0.0, 3.0, 0.0], [-2.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+    /// let (u, s, v) = m.svd();
+    /// assert_eq!(s, Vector3::new(3.0, 2.0, 0.0));
+    /// let diagonal = Matrix3::new([[s.x, 0.0, 0.0], [0.0, s.y, 0.0], [0.0, 0.0, s.z]]);
+    /// assert_eq!(u * diagonal * v.transpose(), m);
+    /// assert_eq!(u.transpose() * u, Matrix3::identity());
+    /// ```
+    pub fn svd(&self) -> (Mat3<T>, Vec3<T>, Mat3<T>) {
+        let mut u = self.column_vectors();
+        let mut v = Self::identity().column_vectors();
+        for _ in 0..50 {
+            let mut rotated = false;
+            for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+                let (alpha, beta, gamma) = (u[p].dot(&u[p]), u[q].dot(&u[q]), u[p].dot(&u[q]));
+                if gamma == T::ZERO || gamma.abs() <= T::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let (c, s) = jacobi_rotation(alpha, beta, gamma);
+                for columns in [&mut u, &mut v] {
+                    let (cp, cq) = (columns[p], columns[q]);
+                    columns[p] = cp * c - cq * s;
+                    columns[q] = cp * s + cq * c;
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| u[j].magnitude().total_cmp(&u[i].magnitude()));
+        let values = order.map(|i| u[i].magnitude());
+        let (mut u, v) = (order.map(|i| u[i].hat()), order.map(|i| v[i]));
+        // Columns for zero singular values are noise, so replace them with ones that complete the basis
+        let negligible = values[0] * T::EPSILON * T::from_f64(8.0);
+        if values[0] == T::ZERO {
+            u = Self::identity().column_vectors().try_into().unwrap();
+        } else if values[1] <= negligible {
+            let axis = if u[0].x.abs() < u[0].y.abs().max(u[0].z.abs()) {
+                Vec3::i_hat()
+            } else {
+                Vec3::j_hat()
+            };
+            u[1] = u[0].cross(&axis).hat();
+            u[2] = u[0].cross(&u[1]);
+        } else if values[2] <= negligible {
+            u[2] = u[0].cross(&u[1]).hat();
+        }
+        (
+            Self::from_columns(u),
+            Vec3::new(values[0], values[1], values[2]),
+            Self::from_columns(v),
+        )
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Mat3<U> {
+        Mat3::new(self.data.map(|row| row.map(|x| U::from_f64(x.to_f64()))))
+    }
+}
+
+/// A 4x4 matrix, for projective transforms of homogeneous coordinates. See
+/// [Affine3](crate::affine3::Affine3) for transforms that keep points and directions apart.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Mat4<T> {
+    pub data: [[T; 4]; 4],
+}
+pub type Matrix4 = Mat4<f64>;
+pub type Matrix4f = Mat4<f32>;
+impl_approx_eq!(<T> Mat4<T>, data);
+impl From<Matrix4f> for Matrix4 {
+    fn from(m: Matrix4f) -> Self {
+        m.cast()
+    }
+}
+impl<T: Float> ops::Neg for Mat4<T> {
+    type Output = Mat4<T>;
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
     }
 }
+impl<T: Float> ops::Add<Mat4<T>> for Mat4<T> {
+    type Output = Mat4<T>;
+    fn add(self, rhs: Mat4<T>) -> Self::Output {
+        Mat4::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.data[i][j] + rhs.data[i][j])
+        }))
+    }
+}
+impl<T: Float> ops::Sub<Mat4<T>> for Mat4<T> {
+    type Output = Mat4<T>;
+    fn sub(self, rhs: Mat4<T>) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl<T: Float> ops::Mul<Mat4<T>> for Mat4<T> {
+    type Output = Mat4<T>;
+    fn mul(self, rhs: Mat4<T>) -> Self::Output {
+        let columns = rhs.column_vectors();
+        let rows = self.row_vectors();
+        Mat4::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| rows[i].dot(&columns[j]))
+        }))
+    }
+}
+impl<T: Float> ops::Mul<Vec4<T>> for Mat4<T> {
+    type Output = Vec4<T>;
+    fn mul(self, rhs: Vec4<T>) -> Self::Output {
+        let rows = self.row_vectors();
+        Vec4::new(
+            rhs.dot(&rows[0]),
+            rhs.dot(&rows[1]),
+            rhs.dot(&rows[2]),
+            rhs.dot(&rows[3]),
+        )
+    }
+}
+impl<T: Float> ops::Mul<T> for Mat4<T> {
+    type Output = Mat4<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Mat4::new(self.data.map(|row| row.map(|x| x * rhs)))
+    }
+}
+
+impl<T: Float> Mat4<T> {
+    pub fn new(data: [[T; 4]; 4]) -> Self {
+        Self { data }
+    }
+    pub fn identity() -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| if i == j { T::ONE } else { T::ZERO })
+        }))
+    }
+    pub fn zero() -> Self {
+        Self::new([[T::ZERO; 4]; 4])
+    }
+    pub fn from_rows(rows: [Vec4<T>; 4]) -> Self {
+        Self::new(rows.map(|r| r.as_array()))
+    }
+    pub fn from_columns(columns: [Vec4<T>; 4]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+    pub fn row_vectors(&self) -> Vec<Vec4<T>> {
+        self.data
+            .iter()
+            .map(|r| Vec4::new(r[0], r[1], r[2], r[3]))
+            .collect()
+    }
+    pub fn column_vectors(&self) -> Vec<Vec4<T>> {
+        self.transpose().row_vectors()
+    }
+    pub fn transpose(&self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.data[j][i])
+        }))
+    }
+    /// The sum of the diagonal
+    pub fn trace(&self) -> T {
+        (0..4).map(|i| self.data[i][i]).sum()
+    }
+    /// Reduces the matrix to upper triangular form by Gaussian elimination with partial pivoting,
+    /// applying the same row operations to `rhs`
+    ///
+    /// returns: Option<T>, the determinant, or `None` if the matrix is singular
+    fn eliminate<const N: usize>(rows: &mut [[T; 4]; 4], rhs: &mut [[T; N]; 4]) -> Option<T> {
+        let mut determinant = T::ONE;
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&i, &j| rows[i][col].abs().total_cmp(&rows[j][col].abs()))
+                .unwrap();
+            if rows[pivot][col] == T::ZERO {
+                return None;
+            }
+            if pivot != col {
+                rows.swap(col, pivot);
+                rhs.swap(col, pivot);
+                determinant = -determinant;
+            }
+            determinant *= rows[col][col];
+            let (pivot_row, pivot_rhs) = (rows[col], rhs[col]);
+            for row in col + 1..4 {
+                let factor = rows[row][col] / pivot_row[col];
+                for (entry, above) in rows[row].iter_mut().zip(pivot_row).skip(col) {
+                    *entry -= factor * above;
+                }
+                for (entry, above) in rhs[row].iter_mut().zip(pivot_rhs) {
+                    *entry -= factor * above;
+                }
+            }
+        }
+        Some(determinant)
+    }
+    pub fn determinant(&self) -> T {
+        let mut rows = self.data;
+        Self::eliminate::<0>(&mut rows, &mut [[]; 4]).unwrap_or(T::ZERO)
+    }
+    /// Inverts the matrix by Gaussian elimination with partial pivoting
+    ///
+    /// returns: Option<Matrix4>, which is `None` if the matrix is singular
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix4;
+    /// let m = Matrix4::new([
+    ///     [0.0, 2.0, 0.0, 1.0],
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 4.0, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ]);
+    /// assert_eq!(m * m.inverse().unwrap(), Matrix4::identity());
+    /// assert_eq!(m.determinant(), -8.0);
+    /// assert_eq!(Matrix4::zero().inverse(), None);
+    /// ```
+    pub fn inverse(&self) -> Option<Self> {
+        let mut rows = self.data;
+        let mut result = Self::identity().data;
+        Self::eliminate(&mut rows, &mut result)?;
+        for row in (0..4).rev() {
+            let mut solved = result[row];
+            for k in row + 1..4 {
+                for (entry, known) in solved.iter_mut().zip(result[k]) {
+                    *entry -= rows[row][k] * known;
+                }
+            }
+            result[row] = solved.map(|entry| entry / rows[row][row]);
+        }
+        Some(Self::new(result))
+    }
+    /// Transforms a point, dividing by the resulting `w` as a projection does
+    ///
+    /// returns: Option<Vector3>, which is `None` if the point is sent to infinity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix::Matrix4;
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// // A pinhole camera one unit from its image plane
+    /// let camera = Matrix4::new([
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    /// ]);
+    /// assert_eq!(camera.transform_point(Vector3::new(2.0, 4.0, 8.0)), Some(Vector3::new(0.25, 0.5, 1.0)));
+    /// assert_eq!(camera.transform_point(Vector3::new(2.0, 4.0, 0.0)), None);
+    /// ```
+    pub fn transform_point(&self, point: Vec3<T>) -> Option<Vec3<T>> {
+        (*self * point.to_homogeneous_point()).to_point()
+    }
+    /// Transforms a direction, which ignores the translation column
+    pub fn transform_direction(&self, direction: Vec3<T>) -> Vec3<T> {
+        (*self * direction.to_homogeneous_direction()).xyz()
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Mat4<U> {
+        Mat4::new(self.data.map(|row| row.map(|x| U::from_f64(x.to_f64()))))
+    }
+}
+
+/// The cosine and sine of the plane rotation that makes a symmetric 2x2 block `[[app, apq], [apq, aqq]]`
+/// diagonal, choosing the smaller of the two angles that do so
+fn jacobi_rotation<T: Float>(app: T, aqq: T, apq: T) -> (T, T) {
+    let theta = (aqq - app) / (T::from_f64(2.0) * apq);
+    let t = T::ONE / (theta.abs() + (theta * theta + T::ONE).sqrt());
+    let t = if theta < T::ZERO { -t } else { t };
+    let c = T::ONE / (t * t + T::ONE).sqrt();
+    (c, t * c)
+}
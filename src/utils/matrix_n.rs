@@ -0,0 +1,836 @@
+//! Dense matrices of any size, either sized at runtime ([MatrixN]) or by the type ([FixedMatrix]), with
+//! the factorizations needed to solve linear systems and to fit models by least squares. Both store
+//! their entries by row and share the same elimination code, so they give the same answers.
+use crate::approx::ApproxEq;
+use crate::scalar::Float;
+use crate::utils::matrix::{Mat3, Mat4};
+use core::ops;
+
+/// A matrix whose size is chosen at runtime. Operators panic if the sizes don't match.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::utils::matrix_n::MatrixN;
+/// let a = MatrixN::from_rows(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).unwrap();
+/// assert_eq!((a.get_rows(), a.get_cols()), (2, 3));
+/// assert_eq!(a[(1, 0)], 4.0);
+/// assert_eq!(&a * &a.transpose(), MatrixN::from_rows(&[[14.0, 32.0], [32.0, 77.0]]).unwrap());
+/// assert_eq!(a.mul_vector(&[1.0, 0.0, -1.0]), vec![-2.0, -2.0]);
+/// assert!(MatrixN::new(2, 2, vec![1.0, 2.0, 3.0]).is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct MatN<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+pub type MatrixN = MatN<f64>;
+pub type MatrixNf = MatN<f32>;
+
+impl<T: Float> ApproxEq for MatN<T> {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols)
+            && self.data.abs_diff_eq(&other.data, epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols)
+            && self.data.relative_eq(&other.data, epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols)
+            && self.data.ulps_eq(&other.data, epsilon, max_ulps)
+    }
+}
+impl From<MatrixNf> for MatrixN {
+    fn from(m: MatrixNf) -> Self {
+        m.cast()
+    }
+}
+impl<T: Float> From<Mat3<T>> for MatN<T> {
+    fn from(m: Mat3<T>) -> Self {
+        Self::from_rows(&m.data).unwrap()
+    }
+}
+impl<T: Float> From<Mat4<T>> for MatN<T> {
+    fn from(m: Mat4<T>) -> Self {
+        Self::from_rows(&m.data).unwrap()
+    }
+}
+impl<T: Float, const R: usize, const C: usize> From<FixedMat<T, R, C>> for MatN<T> {
+    fn from(m: FixedMat<T, R, C>) -> Self {
+        Self::from_rows(&m.data).unwrap()
+    }
+}
+
+impl<T> ops::Index<(usize, usize)> for MatN<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Index ({row}, {col}) is out of range for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &self.data[row * self.cols + col]
+    }
+}
+impl<T> ops::IndexMut<(usize, usize)> for MatN<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Index ({row}, {col}) is out of range for a {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+impl<T: Float> ops::Neg for MatN<T> {
+    type Output = MatN<T>;
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
+    }
+}
+impl<T: Float> ops::Add<&MatN<T>> for &MatN<T> {
+    type Output = MatN<T>;
+    fn add(self, rhs: &MatN<T>) -> Self::Output {
+        assert!(
+            (self.rows, self.cols) == (rhs.rows, rhs.cols),
+            "Cannot add a {}x{} matrix to a {}x{} matrix",
+            rhs.rows,
+            rhs.cols,
+            self.rows,
+            self.cols
+        );
+        let data = self.data.iter().zip(&rhs.data).map(|(a, b)| *a + *b);
+        MatN::from_data(self.rows, self.cols, data.collect())
+    }
+}
+impl<T: Float> ops::Add<MatN<T>> for MatN<T> {
+    type Output = MatN<T>;
+    fn add(self, rhs: MatN<T>) -> Self::Output {
+        &self + &rhs
+    }
+}
+impl<T: Float> ops::Sub<&MatN<T>> for &MatN<T> {
+    type Output = MatN<T>;
+    fn sub(self, rhs: &MatN<T>) -> Self::Output {
+        self + &(rhs.clone() * -T::ONE)
+    }
+}
+impl<T: Float> ops::Sub<MatN<T>> for MatN<T> {
+    type Output = MatN<T>;
+    fn sub(self, rhs: MatN<T>) -> Self::Output {
+        &self - &rhs
+    }
+}
+impl<T: Float> ops::Mul<&MatN<T>> for &MatN<T> {
+    type Output = MatN<T>;
+    fn mul(self, rhs: &MatN<T>) -> Self::Output {
+        assert!(
+            self.cols == rhs.rows,
+            "Cannot multiply a {}x{} matrix by a {}x{} matrix",
+            self.rows,
+            self.cols,
+            rhs.rows,
+            rhs.cols
+        );
+        MatN::from_fn(self.rows, rhs.cols, |i, j| {
+            (0..self.cols).map(|k| self[(i, k)] * rhs[(k, j)]).sum()
+        })
+    }
+}
+impl<T: Float> ops::Mul<MatN<T>> for MatN<T> {
+    type Output = MatN<T>;
+    fn mul(self, rhs: MatN<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+impl<T: Float> ops::Mul<T> for MatN<T> {
+    type Output = MatN<T>;
+    fn mul(mut self, rhs: T) -> Self::Output {
+        self.data.iter_mut().for_each(|x| *x *= rhs);
+        self
+    }
+}
+
+impl<T: Float> MatN<T> {
+    /// Builds a matrix from its entries, row by row
+    ///
+    /// # Arguments
+    ///
+    /// * `rows`: The number of rows
+    /// * `cols`: The number of columns
+    /// * `data`: The `rows * cols` entries, the first row first
+    ///
+    /// returns: Result<MatrixN, String>, which is an error if there are the wrong number of entries
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> Result<Self, String> {
+        if data.len() != rows * cols {
+            return Err(format!(
+                "A {rows}x{cols} matrix has {} entries, but {} were given",
+                rows * cols,
+                data.len()
+            ));
+        }
+        Ok(Self::from_data(rows, cols, data))
+    }
+    fn from_data(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        Self { rows, cols, data }
+    }
+    /// Builds a matrix from its rows, which must all be the same length
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Result<Self, String> {
+        let cols = rows.first().map_or(0, |row| row.as_ref().len());
+        if let Some(i) = rows.iter().position(|row| row.as_ref().len() != cols) {
+            return Err(format!(
+                "Row {i} has {} entries, but row 0 has {cols}",
+                rows[i].as_ref().len()
+            ));
+        }
+        let data = rows.iter().flat_map(|row| row.as_ref().to_vec()).collect();
+        Ok(Self::from_data(rows.len(), cols, data))
+    }
+    /// Builds a matrix by calling `f(row, col)` for each entry
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let data = (0..rows * cols).map(|k| f(k / cols, k % cols)).collect();
+        Self::from_data(rows, cols, data)
+    }
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self::from_data(rows, cols, vec![T::ZERO; rows * cols])
+    }
+    pub fn identity(size: usize) -> Self {
+        Self::from_fn(size, size, |i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+    pub fn get_cols(&self) -> usize {
+        self.cols
+    }
+    /// The entries, row by row
+    pub fn get_data(&self) -> &[T] {
+        &self.data
+    }
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |i, j| self[(j, i)])
+    }
+    /// Multiplies the matrix by a column vector, given as a slice with one entry per column
+    pub fn mul_vector(&self, v: &[T]) -> Vec<T> {
+        assert!(
+            v.len() == self.cols,
+            "Cannot multiply a {}x{} matrix by a vector of length {}",
+            self.rows,
+            self.cols,
+            v.len()
+        );
+        (0..self.rows)
+            .map(|i| self.row(i).iter().zip(v).map(|(a, b)| *a * *b).sum())
+            .collect()
+    }
+    /// Factors a square matrix into `P A = L U` by Gaussian elimination with partial pivoting
+    ///
+    /// returns: Option<Lu>, which is `None` if the matrix isn't square or is singular
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::utils::matrix_n::MatrixN;
+    /// let a = MatrixN::from_rows(&[[0.0, 2.0, 1.0], [1.0, 1.0, 0.0], [3.0, 0.0, 1.0]]).unwrap();
+    /// let lu = a.lu().unwrap();
+    /// assert_eq!(&lu.get_l() * &lu.get_u(), lu.get_p() * a.clone());
+    /// let x = vec![1.0, -2.0, 3.0];
+    /// assert!(Tolerance::default().approx_eq(&lu.solve(&a.mul_vector(&x)), &x));
+    /// assert_eq!(lu.determinant(), -5.0);
+    /// assert!(MatrixN::from_rows(&[[1.0, 2.0], [2.0, 4.0]]).unwrap().lu().is_none());
+    /// ```
+    pub fn lu(&self) -> Option<Lu<T>> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let mut lu = self.clone();
+        let mut permutation = vec![0; self.rows];
+        let sign = lu_in_place(&mut lu.data, self.rows, &mut permutation)?;
+        Some(Lu {
+            lu,
+            permutation,
+            sign,
+        })
+    }
+    /// Factors a matrix with at least as many rows as columns into `A = Q R` by Householder
+    /// reflections, where `Q` has orthonormal columns and `R` is square and upper triangular
+    ///
+    /// returns: Option<Qr>, which is `None` if there are more columns than rows
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::approx::Tolerance;
+    /// use YetAnotherGeometryLibrary::utils::matrix_n::MatrixN;
+    /// let a = MatrixN::from_rows(&[[3.0, 1.0], [4.0, 2.0], [0.0, 2.0]]).unwrap();
+    /// let qr = a.qr().unwrap();
+    /// let (q, r) = (qr.get_q(), qr.get_r());
+    /// assert!(Tolerance::default().approx_eq(&(&q * &r), &a));
+    /// assert!(Tolerance::default().approx_eq(&(&q.transpose() * &q), &MatrixN::identity(2)));
+    /// assert_eq!(r[(1, 0)], 0.0);
+    /// ```
+    pub fn qr(&self) -> Option<Qr<T>> {
+        if self.rows < self.cols {
+            return None;
+        }
+        let mut qr = self.clone();
+        let mut beta = vec![T::ZERO; self.cols];
+        let mut diagonal = vec![T::ZERO; self.cols];
+        qr_in_place(&mut qr.data, self.rows, self.cols, &mut beta, &mut diagonal);
+        Some(Qr { qr, beta, diagonal })
+    }
+    /// Factors a symmetric positive definite matrix into `A = L Lᵀ`. Only the lower triangle is read.
+    ///
+    /// returns: Option<Cholesky>, which is `None` if the matrix isn't square or positive definite
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix_n::MatrixN;
+    /// let a = MatrixN::from_rows(&[[4.0, 2.0], [2.0, 5.0]]).unwrap();
+    /// let cholesky = a.cholesky().unwrap();
+    /// let l = cholesky.get_l();
+    /// assert_eq!(l, MatrixN::from_rows(&[[2.0, 0.0], [1.0, 2.0]]).unwrap());
+    /// assert_eq!(&l * &l.transpose(), a);
+    /// assert_eq!(cholesky.solve(&[8.0, 9.0]), vec![1.375, 1.25]);
+    /// assert!(MatrixN::from_rows(&[[1.0, 2.0], [2.0, 1.0]]).unwrap().cholesky().is_none());
+    /// ```
+    pub fn cholesky(&self) -> Option<Cholesky<T>> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let mut l = self.clone();
+        if !cholesky_in_place(&mut l.data, self.rows) {
+            return None;
+        }
+        Some(Cholesky { l })
+    }
+    /// The determinant, which is zero for a singular matrix
+    ///
+    /// # Panics
+    ///
+    /// If the matrix isn't square
+    pub fn determinant(&self) -> T {
+        assert!(
+            self.rows == self.cols,
+            "A {}x{} matrix has no determinant",
+            self.rows,
+            self.cols
+        );
+        self.lu().map_or(T::ZERO, |lu| lu.determinant())
+    }
+    /// returns: Option<MatrixN>, which is `None` if the matrix isn't square or is singular
+    pub fn inverse(&self) -> Option<Self> {
+        self.lu().map(|lu| lu.inverse())
+    }
+    /// Solves `self * x = rhs` for `x`, see [MatN::lu]
+    ///
+    /// returns: Option<Vec<T>>, which is `None` if the matrix isn't square or is singular
+    pub fn solve(&self, rhs: &[T]) -> Option<Vec<T>> {
+        self.lu().map(|lu| lu.solve(rhs))
+    }
+    /// Finds the `x` that minimizes the squared length of `self * x - rhs`, see [Qr::least_squares].
+    /// Each row is one equation, so fitting a model to points takes a row per point and a column per
+    /// parameter.
+    ///
+    /// returns: Option<Vec<T>>, which is `None` if there are fewer equations than unknowns or the
+    /// columns aren't independent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix_n::MatrixN;
+    /// // Fit the circle x² + y² + dx + ey + f = 0 through points around (1, 2) with radius 3
+    /// let points = [(4.0, 2.0), (1.0, 5.0), (-2.0, 2.0), (1.0, -1.0), (3.0, 2.0 + 5f64.sqrt())];
+    /// let a = MatrixN::from_rows(&points.map(|(x, y)| [x, y, 1.0])).unwrap();
+    /// let rhs = points.map(|(x, y)| -x * x - y * y);
+    /// let fit = a.least_squares(&rhs).unwrap();
+    /// let center = (-fit[0] / 2.0, -fit[1] / 2.0);
+    /// let radius = (center.0 * center.0 + center.1 * center.1 - fit[2]).sqrt();
+    /// assert!((center.0 - 1.0).abs() < 1E-12 && (center.1 - 2.0).abs() < 1E-12);
+    /// assert!((radius - 3.0).abs() < 1E-12);
+    /// ```
+    pub fn least_squares(&self, rhs: &[T]) -> Option<Vec<T>> {
+        self.qr()?.least_squares(rhs)
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> MatN<U> {
+        let data = self.data.iter().map(|x| U::from_f64(x.to_f64()));
+        MatN::from_data(self.rows, self.cols, data.collect())
+    }
+}
+
+/// The factors of `P A = L U` for a square matrix `A`, made by [MatN::lu]
+#[derive(Debug, Clone)]
+pub struct Lu<T> {
+    /// `U` on and above the diagonal, and `L`, less its unit diagonal, below it
+    lu: MatN<T>,
+    permutation: Vec<usize>,
+    sign: T,
+}
+
+impl<T: Float> Lu<T> {
+    /// The unit lower triangular factor
+    pub fn get_l(&self) -> MatN<T> {
+        MatN::from_fn(self.lu.rows, self.lu.cols, |i, j| match i.cmp(&j) {
+            core::cmp::Ordering::Less => T::ZERO,
+            core::cmp::Ordering::Equal => T::ONE,
+            core::cmp::Ordering::Greater => self.lu[(i, j)],
+        })
+    }
+    /// The upper triangular factor
+    pub fn get_u(&self) -> MatN<T> {
+        MatN::from_fn(self.lu.rows, self.lu.cols, |i, j| {
+            if i <= j { self.lu[(i, j)] } else { T::ZERO }
+        })
+    }
+    /// The permutation matrix that reorders the rows of `A` into pivot order
+    pub fn get_p(&self) -> MatN<T> {
+        let size = self.permutation.len();
+        MatN::from_fn(size, size, |i, j| {
+            if self.permutation[i] == j {
+                T::ONE
+            } else {
+                T::ZERO
+            }
+        })
+    }
+    pub fn determinant(&self) -> T {
+        (0..self.lu.rows).fold(self.sign, |det, i| det * self.lu[(i, i)])
+    }
+    /// Solves `A x = rhs` for `x`
+    pub fn solve(&self, rhs: &[T]) -> Vec<T> {
+        let size = self.lu.rows;
+        assert!(
+            rhs.len() == size,
+            "Cannot solve a {size}x{size} system for a right hand side of length {}",
+            rhs.len()
+        );
+        let mut x = vec![T::ZERO; size];
+        lu_solve(&self.lu.data, size, &self.permutation, rhs, &mut x);
+        x
+    }
+    pub fn inverse(&self) -> MatN<T> {
+        let size = self.lu.rows;
+        let identity = MatN::identity(size);
+        let columns: Vec<Vec<T>> = (0..size).map(|j| self.solve(identity.row(j))).collect();
+        MatN::from_rows(&columns).unwrap().transpose()
+    }
+}
+
+/// The factors of `A = Q R` for a matrix `A` with at least as many rows as columns, made by [MatN::qr]
+#[derive(Debug, Clone)]
+pub struct Qr<T> {
+    /// The Householder vectors on and below the diagonal, and `R`, less its diagonal, above it
+    qr: MatN<T>,
+    beta: Vec<T>,
+    diagonal: Vec<T>,
+}
+
+impl<T: Float> Qr<T> {
+    /// The factor with orthonormal columns, the same shape as `A`
+    pub fn get_q(&self) -> MatN<T> {
+        let (rows, cols) = (self.qr.rows, self.qr.cols);
+        let mut q = MatN::identity(rows);
+        for row in 0..rows {
+            let slice = &mut q.data[row * rows..(row + 1) * rows];
+            apply_q_transpose(&self.qr.data, rows, cols, &self.beta, slice);
+        }
+        // Qᵀ takes each unit vector to a column of Qᵀ, which is a row of Q
+        MatN::from_fn(rows, cols, |i, j| q[(i, j)])
+    }
+    /// The square upper triangular factor
+    pub fn get_r(&self) -> MatN<T> {
+        let cols = self.qr.cols;
+        MatN::from_fn(cols, cols, |i, j| match i.cmp(&j) {
+            core::cmp::Ordering::Less => self.qr[(i, j)],
+            core::cmp::Ordering::Equal => self.diagonal[i],
+            core::cmp::Ordering::Greater => T::ZERO,
+        })
+    }
+    /// Whether the columns of `A` are independent, up to rounding relative to the largest of them
+    pub fn is_full_rank(&self) -> bool {
+        is_full_rank(&self.diagonal, self.qr.rows)
+    }
+    /// Finds the `x` that minimizes the squared length of `A x - rhs`. Unlike solving the normal
+    /// equations `AᵀA x = Aᵀ rhs`, this doesn't square the condition number of `A`.
+    ///
+    /// returns: Option<Vec<T>>, which is `None` if the columns of `A` aren't independent
+    pub fn least_squares(&self, rhs: &[T]) -> Option<Vec<T>> {
+        let (rows, cols) = (self.qr.rows, self.qr.cols);
+        assert!(
+            rhs.len() == rows,
+            "Cannot fit {rows} equations to a right hand side of length {}",
+            rhs.len()
+        );
+        let mut work = rhs.to_vec();
+        let mut x = vec![T::ZERO; cols];
+        qr_solve(
+            &self.qr.data,
+            rows,
+            cols,
+            &self.beta,
+            &self.diagonal,
+            &mut work,
+            &mut x,
+        )
+        .then_some(x)
+    }
+}
+
+/// The factor of `A = L Lᵀ` for a symmetric positive definite matrix `A`, made by [MatN::cholesky]
+#[derive(Debug, Clone)]
+pub struct Cholesky<T> {
+    l: MatN<T>,
+}
+
+impl<T: Float> Cholesky<T> {
+    /// The lower triangular factor
+    pub fn get_l(&self) -> MatN<T> {
+        self.l.clone()
+    }
+    /// Solves `A x = rhs` for `x`
+    pub fn solve(&self, rhs: &[T]) -> Vec<T> {
+        let size = self.l.rows;
+        assert!(
+            rhs.len() == size,
+            "Cannot solve a {size}x{size} system for a right hand side of length {}",
+            rhs.len()
+        );
+        let mut x = rhs.to_vec();
+        cholesky_solve(&self.l.data, size, &mut x);
+        x
+    }
+}
+
+/// A matrix whose size is part of its type, which lives on the stack and is `Copy`. It has the same
+/// factorizations as [MatrixN], without allocating.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::approx::Tolerance;
+/// use YetAnotherGeometryLibrary::utils::matrix_n::FixedMatrix;
+/// let a = FixedMatrix::new([[2.0, 1.0], [1.0, 3.0]]);
+/// let b: FixedMatrix<2, 3> = FixedMatrix::new([[1.0, 0.0, 2.0], [0.0, 1.0, -1.0]]);
+/// assert_eq!(a * b, FixedMatrix::new([[2.0, 1.0, 3.0], [1.0, 3.0, -1.0]]));
+/// assert_eq!(a.determinant(), 5.0);
+/// assert_eq!(a.solve([3.0, 4.0]), Some([1.0, 1.0]));
+/// assert!(Tolerance::default().approx_eq(&(a * a.inverse().unwrap()), &FixedMatrix::identity()));
+/// ```
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FixedMat<T, const R: usize, const C: usize> {
+    pub data: [[T; C]; R],
+}
+pub type FixedMatrix<const R: usize, const C: usize> = FixedMat<f64, R, C>;
+pub type FixedMatrixf<const R: usize, const C: usize> = FixedMat<f32, R, C>;
+
+impl<T: Float, const R: usize, const C: usize> ApproxEq for FixedMat<T, R, C> {
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.data.abs_diff_eq(&other.data, epsilon)
+    }
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.data.relative_eq(&other.data, epsilon, max_relative)
+    }
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.data.ulps_eq(&other.data, epsilon, max_ulps)
+    }
+}
+impl<const R: usize, const C: usize> From<FixedMatrixf<R, C>> for FixedMatrix<R, C> {
+    fn from(m: FixedMatrixf<R, C>) -> Self {
+        m.cast()
+    }
+}
+impl<T: Float, const R: usize, const C: usize> ops::Neg for FixedMat<T, R, C> {
+    type Output = FixedMat<T, R, C>;
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
+    }
+}
+impl<T: Float, const R: usize, const C: usize> ops::Add<FixedMat<T, R, C>> for FixedMat<T, R, C> {
+    type Output = FixedMat<T, R, C>;
+    fn add(self, rhs: FixedMat<T, R, C>) -> Self::Output {
+        Self::from_fn(|i, j| self.data[i][j] + rhs.data[i][j])
+    }
+}
+impl<T: Float, const R: usize, const C: usize> ops::Sub<FixedMat<T, R, C>> for FixedMat<T, R, C> {
+    type Output = FixedMat<T, R, C>;
+    fn sub(self, rhs: FixedMat<T, R, C>) -> Self::Output {
+        self + (-rhs)
+    }
+}
+impl<T: Float, const R: usize, const C: usize, const K: usize> ops::Mul<FixedMat<T, C, K>>
+    for FixedMat<T, R, C>
+{
+    type Output = FixedMat<T, R, K>;
+    fn mul(self, rhs: FixedMat<T, C, K>) -> Self::Output {
+        FixedMat::from_fn(|i, j| (0..C).map(|k| self.data[i][k] * rhs.data[k][j]).sum())
+    }
+}
+impl<T: Float, const R: usize, const C: usize> ops::Mul<[T; C]> for FixedMat<T, R, C> {
+    type Output = [T; R];
+    fn mul(self, rhs: [T; C]) -> Self::Output {
+        self.data
+            .map(|row| row.iter().zip(rhs).map(|(a, b)| *a * b).sum())
+    }
+}
+impl<T: Float, const R: usize, const C: usize> ops::Mul<T> for FixedMat<T, R, C> {
+    type Output = FixedMat<T, R, C>;
+    fn mul(self, rhs: T) -> Self::Output {
+        FixedMat::new(self.data.map(|row| row.map(|x| x * rhs)))
+    }
+}
+
+impl<T: Float, const R: usize, const C: usize> FixedMat<T, R, C> {
+    pub fn new(data: [[T; C]; R]) -> Self {
+        Self { data }
+    }
+    /// Builds a matrix by calling `f(row, col)` for each entry
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self::new(std::array::from_fn(|i| std::array::from_fn(|j| f(i, j))))
+    }
+    pub fn zero() -> Self {
+        Self::new([[T::ZERO; C]; R])
+    }
+    /// Ones on the diagonal and zeros elsewhere, which is the identity if the matrix is square
+    pub fn identity() -> Self {
+        Self::from_fn(|i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+    pub fn transpose(&self) -> FixedMat<T, C, R> {
+        FixedMat::from_fn(|i, j| self.data[j][i])
+    }
+    /// Finds the `x` that minimizes the squared length of `self * x - rhs`, see [MatN::least_squares]
+    ///
+    /// returns: Option<[T; C]>, which is `None` if there are fewer equations than unknowns or the
+    /// columns aren't independent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::utils::matrix_n::FixedMatrix;
+    /// // The line y = mx + c closest to three points
+    /// let a = FixedMatrix::new([[0.0, 1.0], [1.0, 1.0], [2.0, 1.0]]);
+    /// let fit = a.least_squares([1.0, 2.0, 4.0]).unwrap();
+    /// assert!((fit[0] - 1.5).abs() < 1E-12 && (fit[1] - 5.0 / 6.0).abs() < 1E-12);
+    /// ```
+    pub fn least_squares(&self, rhs: [T; R]) -> Option<[T; C]> {
+        if R < C {
+            return None;
+        }
+        let mut qr = self.data;
+        let mut beta = [T::ZERO; C];
+        let mut diagonal = [T::ZERO; C];
+        qr_in_place(qr.as_flattened_mut(), R, C, &mut beta, &mut diagonal);
+        let mut work = rhs;
+        let mut x = [T::ZERO; C];
+        qr_solve(qr.as_flattened(), R, C, &beta, &diagonal, &mut work, &mut x).then_some(x)
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> FixedMat<U, R, C> {
+        FixedMat::new(self.data.map(|row| row.map(|x| U::from_f64(x.to_f64()))))
+    }
+}
+
+impl<T: Float, const N: usize> FixedMat<T, N, N> {
+    /// Factors the matrix by Gaussian elimination with partial pivoting, see [MatN::lu]
+    fn lu(&self) -> Option<([[T; N]; N], [usize; N], T)> {
+        let mut lu = self.data;
+        let mut permutation = [0; N];
+        let sign = lu_in_place(lu.as_flattened_mut(), N, &mut permutation)?;
+        Some((lu, permutation, sign))
+    }
+    /// The determinant, which is zero for a singular matrix
+    pub fn determinant(&self) -> T {
+        self.lu().map_or(T::ZERO, |(lu, _, sign)| {
+            (0..N).fold(sign, |det, i| det * lu[i][i])
+        })
+    }
+    /// Solves `self * x = rhs` for `x`
+    ///
+    /// returns: Option<[T; N]>, which is `None` if the matrix is singular
+    pub fn solve(&self, rhs: [T; N]) -> Option<[T; N]> {
+        let (lu, permutation, _) = self.lu()?;
+        let mut x = [T::ZERO; N];
+        lu_solve(lu.as_flattened(), N, &permutation, &rhs, &mut x);
+        Some(x)
+    }
+    /// returns: Option<FixedMat>, which is `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let (lu, permutation, _) = self.lu()?;
+        let identity = Self::identity();
+        let columns = identity.data.map(|unit| {
+            let mut x = [T::ZERO; N];
+            lu_solve(lu.as_flattened(), N, &permutation, &unit, &mut x);
+            x
+        });
+        Some(Self::new(columns).transpose())
+    }
+    /// The lower triangular `L` with `self = L Lᵀ`, see [MatN::cholesky]
+    ///
+    /// returns: Option<FixedMat>, which is `None` if the matrix isn't positive definite
+    pub fn cholesky(&self) -> Option<Self> {
+        let mut l = self.data;
+        cholesky_in_place(l.as_flattened_mut(), N).then_some(Self::new(l))
+    }
+}
+
+/// Factors the `n` by `n` matrix `a`, stored by row, in place into `P a = L U`. `U` is left on and
+/// above the diagonal and the multipliers of `L` below it, and `permutation[i]` is the row of `a`
+/// that ends up as row `i`.
+///
+/// returns: Option<T>, the sign of the permutation, or `None` if the matrix is singular
+fn lu_in_place<T: Float>(a: &mut [T], n: usize, permutation: &mut [usize]) -> Option<T> {
+    permutation.iter_mut().enumerate().for_each(|(i, p)| *p = i);
+    let mut sign = T::ONE;
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap();
+        if a[pivot * n + col] == T::ZERO {
+            return None;
+        }
+        if pivot != col {
+            let (above, below) = a.split_at_mut(pivot * n);
+            above[col * n..(col + 1) * n].swap_with_slice(&mut below[..n]);
+            permutation.swap(col, pivot);
+            sign = -sign;
+        }
+        for row in col + 1..n {
+            let factor = a[row * n + col] / a[col * n + col];
+            a[row * n + col] = factor;
+            for k in col + 1..n {
+                let above = a[col * n + k];
+                a[row * n + k] -= factor * above;
+            }
+        }
+    }
+    Some(sign)
+}
+
+/// Solves `a x = rhs` given the factors left by [lu_in_place]
+fn lu_solve<T: Float>(lu: &[T], n: usize, permutation: &[usize], rhs: &[T], x: &mut [T]) {
+    for row in 0..n {
+        let known: T = (0..row).map(|k| lu[row * n + k] * x[k]).sum();
+        x[row] = rhs[permutation[row]] - known;
+    }
+    for row in (0..n).rev() {
+        let known: T = (row + 1..n).map(|k| lu[row * n + k] * x[k]).sum();
+        x[row] = (x[row] - known) / lu[row * n + row];
+    }
+}
+
+/// Factors the `rows` by `cols` matrix `a`, stored by row, in place into `Q R` by Householder
+/// reflections. The reflection of column `k` is `I - beta[k] v vᵀ`, with `v` left on and below the
+/// diagonal. `R` is left above the diagonal, with its diagonal in `diagonal`.
+fn qr_in_place<T: Float>(
+    a: &mut [T],
+    rows: usize,
+    cols: usize,
+    beta: &mut [T],
+    diagonal: &mut [T],
+) {
+    for k in 0..cols {
+        let norm = (k..rows).map(|i| a[i * cols + k].powi(2)).sum::<T>().sqrt();
+        if norm == T::ZERO {
+            // Nothing to reflect, so the column stays as it is
+            beta[k] = T::ZERO;
+            diagonal[k] = T::ZERO;
+            continue;
+        }
+        // Reflect onto the side away from the entry, so that forming `v` doesn't cancel
+        let lead = a[k * cols + k];
+        let alpha = if lead > T::ZERO { -norm } else { norm };
+        a[k * cols + k] -= alpha;
+        beta[k] = T::ONE / (norm * (norm + lead.abs()));
+        diagonal[k] = alpha;
+        for j in k + 1..cols {
+            let s = beta[k] * (k..rows).map(|i| a[i * cols + k] * a[i * cols + j]).sum();
+            for i in k..rows {
+                let v = a[i * cols + k];
+                a[i * cols + j] -= s * v;
+            }
+        }
+    }
+}
+
+/// Multiplies `b` by `Qᵀ`, given the reflections left by [qr_in_place]
+fn apply_q_transpose<T: Float>(qr: &[T], rows: usize, cols: usize, beta: &[T], b: &mut [T]) {
+    for k in 0..cols {
+        let s = beta[k] * (k..rows).map(|i| qr[i * cols + k] * b[i]).sum();
+        for (i, entry) in b.iter_mut().enumerate().skip(k) {
+            *entry -= s * qr[i * cols + k];
+        }
+    }
+}
+
+/// Whether none of the diagonal of `R` is negligible next to the largest of it
+fn is_full_rank<T: Float>(diagonal: &[T], rows: usize) -> bool {
+    let largest = diagonal.iter().fold(T::ZERO, |m, d| m.max(d.abs()));
+    let threshold = largest * T::EPSILON * T::from_f64(rows as f64);
+    largest > T::ZERO && diagonal.iter().all(|d| d.abs() > threshold)
+}
+
+/// Finds the least squares solution of `a x = b` given the factors left by [qr_in_place], using `b`
+/// as working space
+///
+/// returns: bool, which is false if the columns of `a` aren't independent
+fn qr_solve<T: Float>(
+    qr: &[T],
+    rows: usize,
+    cols: usize,
+    beta: &[T],
+    diagonal: &[T],
+    b: &mut [T],
+    x: &mut [T],
+) -> bool {
+    if !is_full_rank(diagonal, rows) {
+        return false;
+    }
+    apply_q_transpose(qr, rows, cols, beta, b);
+    for row in (0..cols).rev() {
+        let known: T = (row + 1..cols).map(|k| qr[row * cols + k] * x[k]).sum();
+        x[row] = (b[row] - known) / diagonal[row];
+    }
+    true
+}
+
+/// Factors the symmetric `n` by `n` matrix `a`, stored by row, in place into `L Lᵀ`, reading only its
+/// lower triangle and zeroing the upper one
+///
+/// returns: bool, which is false if the matrix isn't positive definite
+fn cholesky_in_place<T: Float>(a: &mut [T], n: usize) -> bool {
+    for j in 0..n {
+        let pivot = a[j * n + j] - (0..j).map(|k| a[j * n + k].powi(2)).sum();
+        if pivot <= T::ZERO || pivot.is_nan() {
+            return false;
+        }
+        let pivot = pivot.sqrt();
+        a[j * n + j] = pivot;
+        for i in j + 1..n {
+            let known: T = (0..j).map(|k| a[i * n + k] * a[j * n + k]).sum();
+            a[i * n + j] = (a[i * n + j] - known) / pivot;
+            a[j * n + i] = T::ZERO;
+        }
+    }
+    true
+}
+
+/// Solves `L Lᵀ x = b` in place given the factor left by [cholesky_in_place]
+fn cholesky_solve<T: Float>(l: &[T], n: usize, b: &mut [T]) {
+    for row in 0..n {
+        let known: T = (0..row).map(|k| l[row * n + k] * b[k]).sum();
+        b[row] = (b[row] - known) / l[row * n + row];
+    }
+    for row in (0..n).rev() {
+        let known: T = (row + 1..n).map(|k| l[k * n + row] * b[k]).sum();
+        b[row] = (b[row] - known) / l[row * n + row];
+    }
+}
@@ -1 +1,2 @@
 pub mod matrix;
+pub mod matrix_n;
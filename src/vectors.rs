@@ -1,116 +1,254 @@
-use crate::quaternion::Quaternion;
-use core::ops;
+use crate::approx::impl_approx_eq;
+use crate::quaternion::Quat;
+use crate::scalar::Float;
+use core::{iter, ops};
 
 #[derive(Debug, Copy, Clone)]
-pub struct Vector3 {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
 }
 
 #[derive(Debug, Copy, Clone)]
-pub struct Vector2 {
-    pub x: f64,
-    pub y: f64,
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
 }
 
-impl PartialEq for Vector2 {
+/// A 4d vector, used for homogeneous coordinates, where `w` is 1 for a point and 0 for a direction, and
+/// for treating quaternions as plain vectors
+#[derive(Debug, Copy, Clone)]
+pub struct Vec4<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
+}
+
+pub type Vector3 = Vec3<f64>;
+pub type Vector3f = Vec3<f32>;
+pub type Vector2 = Vec2<f64>;
+pub type Vector2f = Vec2<f32>;
+pub type Vector4 = Vec4<f64>;
+pub type Vector4f = Vec4<f32>;
+
+impl_approx_eq!(<T> Vec2<T>, x, y);
+impl_approx_eq!(<T> Vec3<T>, x, y, z);
+impl_approx_eq!(<T> Vec4<T>, x, y, z, w);
+
+impl<T: Float> PartialEq for Vec2<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist_to(other) < T::NEAR
+    }
+}
+
+impl<T: Float> PartialEq for Vec3<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.dist_to(other) < 1e-12
+        self.dist_to(other) < T::NEAR
     }
 }
 
-impl PartialEq for Vector3 {
+impl<T: Float> PartialEq for Vec4<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.dist_to(other) < 1e-12
+        self.dist_to(other) < T::NEAR
+    }
+}
+
+impl From<Vector4f> for Vector4 {
+    fn from(v: Vector4f) -> Self {
+        v.cast()
+    }
+}
+
+/// The vector part of the quaternion goes in `x`, `y` and `z` and the scalar part in `w`
+impl<T: Float> From<Quat<T>> for Vec4<T> {
+    fn from(q: Quat<T>) -> Self {
+        Vec4::new(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl<T: Float> From<Vec4<T>> for Quat<T> {
+    fn from(v: Vec4<T>) -> Self {
+        Quat::new(v.w, v.x, v.y, v.z)
+    }
+}
+
+impl From<Vector3f> for Vector3 {
+    fn from(v: Vector3f) -> Self {
+        v.cast()
+    }
+}
+
+impl From<Vector2f> for Vector2 {
+    fn from(v: Vector2f) -> Self {
+        v.cast()
     }
 }
 
-impl ops::Add<Vector3> for Vector3 {
-    type Output = Vector3;
-    fn add(self, rhs: Vector3) -> Self::Output {
-        Vector3 {
+impl<T: Float> ops::Add<Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn add(self, rhs: Vec3<T>) -> Self::Output {
+        Vec3 {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
             z: self.z + rhs.z,
         }
     }
 }
-impl ops::Sub<Vector3> for Vector3 {
-    type Output = Vector3;
-    fn sub(self, rhs: Vector3) -> Self::Output {
-        Vector3 {
+impl<T: Float> ops::Sub<Vec3<T>> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn sub(self, rhs: Vec3<T>) -> Self::Output {
+        Vec3 {
             x: self.x - rhs.x,
             y: self.y - rhs.y,
             z: self.z - rhs.z,
         }
     }
 }
-impl ops::Mul<f64> for Vector3 {
-    type Output = Vector3;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Vector3 {
+impl<T: Float> ops::Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec3 {
             x: self.x * rhs,
             y: self.y * rhs,
             z: self.z * rhs,
         }
     }
 }
-impl ops::Div<f64> for Vector3 {
-    type Output = Vector3;
-    fn div(self, rhs: f64) -> Self::Output {
-        Vector3 {
+impl<T: Float> ops::Div<T> for Vec3<T> {
+    type Output = Vec3<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Vec3 {
             x: self.x / rhs,
             y: self.y / rhs,
             z: self.z / rhs,
         }
     }
 }
-impl ops::Neg for Vector3 {
-    type Output = Vector3;
+impl<T: Float> ops::Neg for Vec3<T> {
+    type Output = Vec3<T>;
     fn neg(self) -> Self::Output {
-        Vector3 {
+        Vec3 {
             x: -self.x,
             y: -self.y,
             z: -self.z,
         }
     }
 }
-impl ops::Mul<Vector3> for Vector3 {
-    type Output = Quaternion;
-    fn mul(self, rhs: Vector3) -> Self::Output {
+impl<T: Float> ops::Mul<Vec3<T>> for Vec3<T> {
+    type Output = Quat<T>;
+    fn mul(self, rhs: Vec3<T>) -> Self::Output {
         // https://en.wikipedia.org/wiki/Quaternion#Quaternions_and_three-dimensional_geometry
-        Quaternion::from_scalar_vector(self.dot(&rhs), self.cross(&rhs))
+        Quat::from_scalar_vector(self.dot(&rhs), self.cross(&rhs))
+    }
+}
+impl<T: Float> ops::AddAssign<Vec3<T>> for Vec3<T> {
+    fn add_assign(&mut self, rhs: Vec3<T>) {
+        *self = *self + rhs;
+    }
+}
+impl<T: Float> ops::SubAssign<Vec3<T>> for Vec3<T> {
+    fn sub_assign(&mut self, rhs: Vec3<T>) {
+        *self = *self - rhs;
+    }
+}
+impl<T: Float> ops::MulAssign<T> for Vec3<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+impl<T: Float> ops::DivAssign<T> for Vec3<T> {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+impl<T: Float> ops::Index<usize> for Vec3<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Index {index} is out of range for a Vector3"),
+        }
+    }
+}
+impl<T: Float> ops::IndexMut<usize> for Vec3<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Index {index} is out of range for a Vector3"),
+        }
+    }
+}
+impl<T: Float> iter::Sum for Vec3<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Vec3::zero(), |a, b| a + b)
+    }
+}
+impl<'a, T: Float> iter::Sum<&'a Vec3<T>> for Vec3<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
     }
 }
 
-impl Vector3 {
-    pub fn new(x: f64, y: f64, z: f64) -> Vector3 {
-        Vector3 { x, y, z }
+/// Scaling with the scalar on the left, which needs an implementation for each scalar type
+macro_rules! impl_scalar_mul {
+    ($($type:ty),+) => {
+        $(
+            impl ops::Mul<Vec3<$type>> for $type {
+                type Output = Vec3<$type>;
+                fn mul(self, rhs: Vec3<$type>) -> Self::Output {
+                    rhs * self
+                }
+            }
+            impl ops::Mul<Vec2<$type>> for $type {
+                type Output = Vec2<$type>;
+                fn mul(self, rhs: Vec2<$type>) -> Self::Output {
+                    rhs * self
+                }
+            }
+            impl ops::Mul<Vec4<$type>> for $type {
+                type Output = Vec4<$type>;
+                fn mul(self, rhs: Vec4<$type>) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )+
+    };
+}
+impl_scalar_mul!(f32, f64);
+
+impl<T: Float> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vec3<T> {
+        Vec3 { x, y, z }
     }
-    pub fn i_hat() -> Vector3 {
-        Vector3::new(1.0, 0.0, 0.0)
+    pub fn i_hat() -> Vec3<T> {
+        Vec3::new(T::ONE, T::ZERO, T::ZERO)
     }
-    pub fn j_hat() -> Vector3 {
-        Vector3::new(0.0, 1.0, 0.0)
+    pub fn j_hat() -> Vec3<T> {
+        Vec3::new(T::ZERO, T::ONE, T::ZERO)
     }
-    pub fn k_hat() -> Vector3 {
-        Vector3::new(0.0, 0.0, 1.0)
+    pub fn k_hat() -> Vec3<T> {
+        Vec3::new(T::ZERO, T::ZERO, T::ONE)
     }
-    pub fn zero() -> Vector3 {
-        Vector3::new(0.0, 0.0, 0.0)
+    pub fn zero() -> Vec3<T> {
+        Vec3::new(T::ZERO, T::ZERO, T::ZERO)
     }
-    pub fn magnitude(&self) -> f64 {
+    pub fn magnitude(&self) -> T {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
-    pub fn dot(&self, rhs: &Vector3) -> f64 {
+    pub fn dot(&self, rhs: &Vec3<T>) -> T {
         self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
     }
-    pub fn as_array(&self) -> [f64; 3] {
+    pub fn as_array(&self) -> [T; 3] {
         [self.x, self.y, self.z]
     }
-    pub fn cross(&self, rhs: &Vector3) -> Vector3 {
-        Vector3 {
+    pub fn cross(&self, rhs: &Vec3<T>) -> Vec3<T> {
+        Vec3 {
             x: self.y * rhs.z - self.z * rhs.y,
             y: self.z * rhs.x - self.x * rhs.z,
             z: self.x * rhs.y - self.y * rhs.x,
@@ -137,7 +275,7 @@ impl Vector3 {
     /// assert!((x.angle_cosine(&xy)-std::f64::consts::FRAC_1_SQRT_2).abs()<1E-9f64);
     /// assert!(x.angle_cosine(&zero).is_nan());
     /// ```
-    pub fn angle_cosine(&self, rhs: &Vector3) -> f64 {
+    pub fn angle_cosine(&self, rhs: &Vec3<T>) -> T {
         self.dot(rhs) / (self.magnitude() * rhs.magnitude())
     }
     /// Gets the normalized vector
@@ -151,8 +289,8 @@ impl Vector3 {
     /// assert_eq!(Vector3::new(2.0,3.0,6.0).hat(),Vector3::new(2.0/7.0,3.0/7.0,6.0/7.0));
     /// assert_eq!(Vector3::zero().hat(),Vector3::zero());
     /// ```
-    pub fn hat(&self) -> Vector3 {
-        if self.x == 0.0 && self.y == 0.0 && self.z == 0.0 {
+    pub fn hat(&self) -> Vec3<T> {
+        if self.x == T::ZERO && self.y == T::ZERO && self.z == T::ZERO {
             *self
         } else {
             (*self) / self.magnitude()
@@ -170,97 +308,528 @@ impl Vector3 {
     /// assert_eq!(Vector3::zero().with_magnitude(100.0),Vector3::zero());
     /// assert_eq!(Vector3::i_hat().with_magnitude(0.0),Vector3::zero());
     /// ```
-    pub fn with_magnitude(&self, magnitude: f64) -> Vector3 {
-        if self.magnitude() == 0.0 {
-            Vector3::zero()
+    pub fn with_magnitude(&self, magnitude: T) -> Vec3<T> {
+        if self.magnitude() == T::ZERO {
+            Vec3::zero()
         } else {
             (*self) * magnitude / self.magnitude()
         }
     }
-    pub fn dist_to(&self, other: &Vector3) -> f64 {
+    pub fn dist_to(&self, other: &Vec3<T>) -> T {
         (*self - *other).magnitude()
     }
+    /// Multiplies each component by the matching component of another vector
+    pub fn component_mul(&self, rhs: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x * rhs.x, self.y * rhs.y, self.z * rhs.z)
+    }
+    /// Divides each component by the matching component of another vector
+    pub fn component_div(&self, rhs: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x / rhs.x, self.y / rhs.y, self.z / rhs.z)
+    }
+    /// Gets the angle between the vectors, from 0 to pi. Unlike taking the arccosine of
+    /// [Vec3::angle_cosine], this stays accurate for nearly parallel vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `rhs`: The vector to calculate the angle between
+    ///
+    /// returns: f64, which is 0 if either vector is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let x = Vector3::i_hat();
+    /// assert_eq!(x.angle_between(&Vector3::j_hat()), std::f64::consts::FRAC_PI_2);
+    /// assert_eq!(x.angle_between(&-x), std::f64::consts::PI);
+    /// assert_eq!(x.angle_between(&Vector3::new(1.0, 1E-9, 0.0)), 1E-9);
+    /// ```
+    pub fn angle_between(&self, rhs: &Vec3<T>) -> T {
+        self.cross(rhs).magnitude().atan2(self.dot(rhs))
+    }
+    /// Interpolates linearly, giving this vector at `t = 0` and `other` at `t = 1`
+    pub fn lerp(&self, other: &Vec3<T>, t: T) -> Vec3<T> {
+        *self + (*other - *self) * t
+    }
+    /// Gets the part of this vector along another
+    ///
+    /// returns: Vector3, which is zero if `onto` is zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::vectors::Vector3;
+    /// let v = Vector3::new(3.0, 4.0, 5.0);
+    /// let axis = Vector3::new(0.0, 2.0, 0.0);
+    /// assert_eq!(v.project_onto(&axis), Vector3::new(0.0, 4.0, 0.0));
+    /// assert_eq!(v.reject_from(&axis), Vector3::new(3.0, 0.0, 5.0));
+    /// assert_eq!(v.reflect(&axis), Vector3::new(3.0, -4.0, 5.0));
+    /// assert_eq!(v.project_onto(&Vector3::zero()), Vector3::zero());
+    /// ```
+    pub fn project_onto(&self, onto: &Vec3<T>) -> Vec3<T> {
+        let length_squared = onto.dot(onto);
+        if length_squared == T::ZERO {
+            Vec3::zero()
+        } else {
+            *onto * (self.dot(onto) / length_squared)
+        }
+    }
+    /// Gets the part of this vector perpendicular to another, see [Vec3::project_onto]
+    pub fn reject_from(&self, from: &Vec3<T>) -> Vec3<T> {
+        *self - self.project_onto(from)
+    }
+    /// Mirrors this vector in the plane through the origin with the given normal, which need not be
+    /// normalized
+    pub fn reflect(&self, normal: &Vec3<T>) -> Vec3<T> {
+        *self - self.project_onto(normal) * T::from_f64(2.0)
+    }
+    /// The smaller of each pair of components
+    pub fn min(&self, rhs: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x.min(rhs.x), self.y.min(rhs.y), self.z.min(rhs.z))
+    }
+    /// The larger of each pair of components
+    pub fn max(&self, rhs: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x.max(rhs.x), self.y.max(rhs.y), self.z.max(rhs.z))
+    }
+    /// Limits each component to the range given by the matching components of `min` and `max`
+    pub fn clamp(&self, min: &Vec3<T>, max: &Vec3<T>) -> Vec3<T> {
+        self.max(min).min(max)
+    }
+    /// The absolute value of each component
+    pub fn abs(&self) -> Vec3<T> {
+        Vec3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+    /// Gets the homogeneous coordinates of this vector as a point, which transforms move, with `w = 1`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::vectors::{Vector3, Vector4};
+    /// let v = Vector3::new(1.0, 2.0, 3.0);
+    /// assert_eq!(v.to_homogeneous_point(), Vector4::new(1.0, 2.0, 3.0, 1.0));
+    /// assert_eq!(v.to_homogeneous_direction(), Vector4::new(1.0, 2.0, 3.0, 0.0));
+    /// assert_eq!((v.to_homogeneous_point() * 2.0).to_point(), Some(v));
+    /// assert_eq!(v.to_homogeneous_direction().to_point(), None);
+    /// ```
+    pub fn to_homogeneous_point(&self) -> Vec4<T> {
+        Vec4::new(self.x, self.y, self.z, T::ONE)
+    }
+    /// Gets the homogeneous coordinates of this vector as a direction, which transforms only turn and
+    /// scale, with `w = 0`
+    pub fn to_homogeneous_direction(&self) -> Vec4<T> {
+        Vec4::new(self.x, self.y, self.z, T::ZERO)
+    }
+    /// Reads this vector as homogeneous coordinates of a 2d point, dividing by `z`
+    ///
+    /// returns: Option<Vector2>, which is `None` for a direction, where `z = 0`
+    pub fn to_point(&self) -> Option<Vec2<T>> {
+        if self.z == T::ZERO {
+            None
+        } else {
+            Some(Vec2::new(self.x / self.z, self.y / self.z))
+        }
+    }
+    /// Drops `z`
+    pub fn xy(&self) -> Vec2<T> {
+        Vec2::new(self.x, self.y)
+    }
+    /// Converts to another precision, rounding if it is narrower
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::vectors::{Vector3, Vector3f};
+    /// let v = Vector3::new(0.1, 2.0, -3.0);
+    /// let narrow: Vector3f = v.cast();
+    /// assert_eq!(narrow, Vector3f::new(0.1, 2.0, -3.0));
+    /// assert_eq!(Vector3::from(narrow), Vector3::new(0.10000000149011612, 2.0, -3.0));
+    /// ```
+    pub fn cast<U: Float>(&self) -> Vec3<U> {
+        Vec3::new(
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+        )
+    }
 }
 
-impl ops::Add<Vector2> for Vector2 {
-    type Output = Vector2;
-    fn add(self, rhs: Vector2) -> Self::Output {
-        Vector2 {
+impl<T: Float> ops::Add<Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
+    fn add(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2 {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
         }
     }
 }
-impl ops::Sub<Vector2> for Vector2 {
-    type Output = Vector2;
-    fn sub(self, rhs: Vector2) -> Self::Output {
-        Vector2 {
+impl<T: Float> ops::Sub<Vec2<T>> for Vec2<T> {
+    type Output = Vec2<T>;
+    fn sub(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2 {
             x: self.x - rhs.x,
             y: self.y - rhs.y,
         }
     }
 }
-impl ops::Mul<f64> for Vector2 {
-    type Output = Vector2;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Vector2 {
+impl<T: Float> ops::Mul<T> for Vec2<T> {
+    type Output = Vec2<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec2 {
             x: self.x * rhs,
             y: self.y * rhs,
         }
     }
 }
-impl ops::Div<f64> for Vector2 {
-    type Output = Vector2;
-    fn div(self, rhs: f64) -> Self::Output {
-        Vector2 {
+impl<T: Float> ops::Div<T> for Vec2<T> {
+    type Output = Vec2<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Vec2 {
             x: self.x / rhs,
             y: self.y / rhs,
         }
     }
 }
-impl ops::Neg for Vector2 {
-    type Output = Vector2;
+impl<T: Float> ops::Neg for Vec2<T> {
+    type Output = Vec2<T>;
     fn neg(self) -> Self::Output {
-        Vector2 {
+        Vec2 {
             x: -self.x,
             y: -self.y,
         }
     }
 }
 
-impl Vector2 {
-    pub fn new(x: f64, y: f64) -> Vector2 {
-        Vector2 { x, y }
+impl<T: Float> ops::AddAssign<Vec2<T>> for Vec2<T> {
+    fn add_assign(&mut self, rhs: Vec2<T>) {
+        *self = *self + rhs;
     }
-    pub fn i_hat() -> Vector2 {
-        Vector2::new(1.0, 0.0)
+}
+impl<T: Float> ops::SubAssign<Vec2<T>> for Vec2<T> {
+    fn sub_assign(&mut self, rhs: Vec2<T>) {
+        *self = *self - rhs;
     }
-    pub fn j_hat() -> Vector2 {
-        Vector2::new(0.0, 1.0)
+}
+impl<T: Float> ops::MulAssign<T> for Vec2<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+impl<T: Float> ops::DivAssign<T> for Vec2<T> {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+impl<T: Float> ops::Index<usize> for Vec2<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Index {index} is out of range for a Vector2"),
+        }
+    }
+}
+impl<T: Float> ops::IndexMut<usize> for Vec2<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Index {index} is out of range for a Vector2"),
+        }
+    }
+}
+impl<T: Float> iter::Sum for Vec2<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Vec2::zero(), |a, b| a + b)
+    }
+}
+impl<'a, T: Float> iter::Sum<&'a Vec2<T>> for Vec2<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<T: Float> Vec2<T> {
+    pub fn new(x: T, y: T) -> Vec2<T> {
+        Vec2 { x, y }
+    }
+    pub fn i_hat() -> Vec2<T> {
+        Vec2::new(T::ONE, T::ZERO)
+    }
+    pub fn j_hat() -> Vec2<T> {
+        Vec2::new(T::ZERO, T::ONE)
     }
-    pub fn zero() -> Vector2 {
-        Vector2::new(0.0, 0.0)
+    pub fn zero() -> Vec2<T> {
+        Vec2::new(T::ZERO, T::ZERO)
     }
-    fn magnitude(&self) -> f64 {
+    pub fn magnitude(&self) -> T {
         (self.x * self.x + self.y * self.y).sqrt()
     }
-    pub fn dot(&self, rhs: &Vector2) -> f64 {
+    pub fn dot(&self, rhs: &Vec2<T>) -> T {
         self.x * rhs.x + self.y * rhs.y
     }
-    pub fn cross(&self, rhs: &Vector2) -> f64 {
+    pub fn cross(&self, rhs: &Vec2<T>) -> T {
         self.x * rhs.y - self.y * rhs.x
     }
-    pub fn angle_cosine(&self, rhs: &Vector2) -> f64 {
+    pub fn angle_cosine(&self, rhs: &Vec2<T>) -> T {
         self.dot(rhs) / (self.magnitude() * rhs.magnitude())
     }
-    pub fn hat(&self) -> Vector2 {
-        if self.x == 0.0 && self.y == 0.0 {
+    pub fn hat(&self) -> Vec2<T> {
+        if self.x == T::ZERO && self.y == T::ZERO {
             *self
         } else {
             (*self) / self.magnitude()
         }
     }
-    pub fn dist_to(&self, other: &Vector2) -> f64 {
+    pub fn dist_to(&self, other: &Vec2<T>) -> T {
         (*self - *other).magnitude()
     }
+    pub fn as_array(&self) -> [T; 2] {
+        [self.x, self.y]
+    }
+    /// Returns a vector with a new magnitude, or zero if this vector is zero
+    pub fn with_magnitude(&self, magnitude: T) -> Vec2<T> {
+        if self.magnitude() == T::ZERO {
+            Vec2::zero()
+        } else {
+            (*self) * magnitude / self.magnitude()
+        }
+    }
+    /// Multiplies each component by the matching component of another vector
+    pub fn component_mul(&self, rhs: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x * rhs.x, self.y * rhs.y)
+    }
+    /// Divides each component by the matching component of another vector
+    pub fn component_div(&self, rhs: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x / rhs.x, self.y / rhs.y)
+    }
+    /// Gets the unsigned angle between the vectors, from 0 to pi, see [Vec3::angle_between]
+    pub fn angle_between(&self, rhs: &Vec2<T>) -> T {
+        self.cross(rhs).abs().atan2(self.dot(rhs))
+    }
+    /// Gets the angle to turn counterclockwise from this vector to another, from -pi to pi
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use YetAnotherGeometryLibrary::vectors::Vector2;
+    /// let x = Vector2::i_hat();
+    /// assert_eq!(x.signed_angle_to(&Vector2::j_hat()), std::f64::consts::FRAC_PI_2);
+    /// assert_eq!(Vector2::j_hat().signed_angle_to(&x), -std::f64::consts::FRAC_PI_2);
+    /// assert_eq!(x.angle_between(&Vector2::new(0.0, -3.0)), std::f64::consts::FRAC_PI_2);
+    /// ```
+    pub fn signed_angle_to(&self, rhs: &Vec2<T>) -> T {
+        self.cross(rhs).atan2(self.dot(rhs))
+    }
+    /// Interpolates linearly, giving this vector at `t = 0` and `other` at `t = 1`
+    pub fn lerp(&self, other: &Vec2<T>, t: T) -> Vec2<T> {
+        *self + (*other - *self) * t
+    }
+    /// Gets the part of this vector along another, or zero if `onto` is zero
+    pub fn project_onto(&self, onto: &Vec2<T>) -> Vec2<T> {
+        let length_squared = onto.dot(onto);
+        if length_squared == T::ZERO {
+            Vec2::zero()
+        } else {
+            *onto * (self.dot(onto) / length_squared)
+        }
+    }
+    /// Gets the part of this vector perpendicular to another, see [Vec2::project_onto]
+    pub fn reject_from(&self, from: &Vec2<T>) -> Vec2<T> {
+        *self - self.project_onto(from)
+    }
+    /// Mirrors this vector in the line through the origin with the given normal, which need not be
+    /// normalized
+    pub fn reflect(&self, normal: &Vec2<T>) -> Vec2<T> {
+        *self - self.project_onto(normal) * T::from_f64(2.0)
+    }
+    /// The smaller of each pair of components
+    pub fn min(&self, rhs: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x.min(rhs.x), self.y.min(rhs.y))
+    }
+    /// The larger of each pair of components
+    pub fn max(&self, rhs: &Vec2<T>) -> Vec2<T> {
+        Vec2::new(self.x.max(rhs.x), self.y.max(rhs.y))
+    }
+    /// Limits each component to the range given by the matching components of `min` and `max`
+    pub fn clamp(&self, min: &Vec2<T>, max: &Vec2<T>) -> Vec2<T> {
+        self.max(min).min(max)
+    }
+    /// The absolute value of each component
+    pub fn abs(&self) -> Vec2<T> {
+        Vec2::new(self.x.abs(), self.y.abs())
+    }
+    /// Gets the homogeneous coordinates of this vector as a point, with `z = 1`, see
+    /// [Vec3::to_homogeneous_point]
+    pub fn to_homogeneous_point(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, T::ONE)
+    }
+    /// Gets the homogeneous coordinates of this vector as a direction, with `z = 0`
+    pub fn to_homogeneous_direction(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, T::ZERO)
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Vec2<U> {
+        Vec2::new(U::from_f64(self.x.to_f64()), U::from_f64(self.y.to_f64()))
+    }
+}
+
+impl<T: Float> ops::Add<Vec4<T>> for Vec4<T> {
+    type Output = Vec4<T>;
+    fn add(self, rhs: Vec4<T>) -> Self::Output {
+        Vec4::new(
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+            self.w + rhs.w,
+        )
+    }
+}
+impl<T: Float> ops::Sub<Vec4<T>> for Vec4<T> {
+    type Output = Vec4<T>;
+    fn sub(self, rhs: Vec4<T>) -> Self::Output {
+        Vec4::new(
+            self.x - rhs.x,
+            self.y - rhs.y,
+            self.z - rhs.z,
+            self.w - rhs.w,
+        )
+    }
+}
+impl<T: Float> ops::Mul<T> for Vec4<T> {
+    type Output = Vec4<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec4::new(self.x * rhs, self.y * rhs, self.z * rhs, self.w * rhs)
+    }
+}
+impl<T: Float> ops::Div<T> for Vec4<T> {
+    type Output = Vec4<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Vec4::new(self.x / rhs, self.y / rhs, self.z / rhs, self.w / rhs)
+    }
+}
+impl<T: Float> ops::Neg for Vec4<T> {
+    type Output = Vec4<T>;
+    fn neg(self) -> Self::Output {
+        Vec4::new(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+impl<T: Float> ops::AddAssign<Vec4<T>> for Vec4<T> {
+    fn add_assign(&mut self, rhs: Vec4<T>) {
+        *self = *self + rhs;
+    }
+}
+impl<T: Float> ops::SubAssign<Vec4<T>> for Vec4<T> {
+    fn sub_assign(&mut self, rhs: Vec4<T>) {
+        *self = *self - rhs;
+    }
+}
+impl<T: Float> ops::MulAssign<T> for Vec4<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+impl<T: Float> ops::DivAssign<T> for Vec4<T> {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+impl<T: Float> ops::Index<usize> for Vec4<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            3 => &self.w,
+            _ => panic!("Index {index} is out of range for a Vector4"),
+        }
+    }
+}
+impl<T: Float> ops::IndexMut<usize> for Vec4<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            3 => &mut self.w,
+            _ => panic!("Index {index} is out of range for a Vector4"),
+        }
+    }
+}
+impl<T: Float> iter::Sum for Vec4<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Vec4::zero(), |a, b| a + b)
+    }
+}
+impl<'a, T: Float> iter::Sum<&'a Vec4<T>> for Vec4<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<T: Float> Vec4<T> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Vec4<T> {
+        Vec4 { x, y, z, w }
+    }
+    pub fn zero() -> Vec4<T> {
+        Vec4::new(T::ZERO, T::ZERO, T::ZERO, T::ZERO)
+    }
+    pub fn magnitude(&self) -> T {
+        self.dot(self).sqrt()
+    }
+    pub fn dot(&self, rhs: &Vec4<T>) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+    pub fn as_array(&self) -> [T; 4] {
+        [self.x, self.y, self.z, self.w]
+    }
+    /// Gets the normalized vector, or zero if this vector is zero
+    pub fn hat(&self) -> Vec4<T> {
+        let magnitude = self.magnitude();
+        if magnitude == T::ZERO {
+            *self
+        } else {
+            (*self) / magnitude
+        }
+    }
+    pub fn dist_to(&self, other: &Vec4<T>) -> T {
+        (*self - *other).magnitude()
+    }
+    /// Multiplies each component by the matching component of another vector
+    pub fn component_mul(&self, rhs: &Vec4<T>) -> Vec4<T> {
+        Vec4::new(
+            self.x * rhs.x,
+            self.y * rhs.y,
+            self.z * rhs.z,
+            self.w * rhs.w,
+        )
+    }
+    /// Interpolates linearly, giving this vector at `t = 0` and `other` at `t = 1`
+    pub fn lerp(&self, other: &Vec4<T>, t: T) -> Vec4<T> {
+        *self + (*other - *self) * t
+    }
+    /// Reads this vector as homogeneous coordinates of a point, dividing by `w`
+    ///
+    /// returns: Option<Vector3>, which is `None` for a direction, where `w = 0`
+    pub fn to_point(&self) -> Option<Vec3<T>> {
+        if self.w == T::ZERO {
+            None
+        } else {
+            Some(self.xyz() / self.w)
+        }
+    }
+    /// Drops `w`, which reads homogeneous coordinates of a direction as the direction
+    pub fn xyz(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+    /// Converts to another precision, rounding if it is narrower
+    pub fn cast<U: Float>(&self) -> Vec4<U> {
+        Vec4::new(
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+            U::from_f64(self.w.to_f64()),
+        )
+    }
 }
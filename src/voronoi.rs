@@ -0,0 +1,120 @@
+use crate::delaunay::Triangulation;
+use crate::gon::{MultiPolygon, Polygon};
+use crate::vectors::Vector2;
+use std::collections::{BTreeSet, HashMap};
+
+/// Cuts a convex polygon down to the half plane where `(x - point).dot(normal) <= 0`
+fn clip_half_plane(cell: &[Vector2], point: Vector2, normal: Vector2) -> Vec<Vector2> {
+    let mut clipped = Vec::new();
+    for i in 0..cell.len() {
+        let (p, q) = (cell[i], cell[(i + 1) % cell.len()]);
+        let (dp, dq) = ((p - point).dot(&normal), (q - point).dot(&normal));
+        if dp <= 0.0 {
+            clipped.push(p);
+        }
+        if (dp < 0.0 && dq > 0.0) || (dp > 0.0 && dq < 0.0) {
+            clipped.push(p + (q - p) * (dp / (dp - dq)));
+        }
+    }
+    clipped.dedup();
+    if clipped.len() > 1 && clipped[0] == clipped[clipped.len() - 1] {
+        clipped.pop();
+    }
+    clipped
+}
+
+/// Computes the Voronoi diagram of a set of sites, as the region of the bounds closer to each site than
+/// to any other. Each cell is cut from the bounds by the perpendicular bisectors between its site and its
+/// neighbours in the Delaunay triangulation of the sites.
+///
+/// # Arguments
+///
+/// * `sites`: The points to partition the bounds between. Sites at the same position share a cell.
+/// * `bounds`: The region to partition, which may be concave
+///
+/// returns: Vec<MultiPolygon>, the cell of each site in order. A cell is empty if none of the bounds are
+/// closest to its site, and has several pieces if concave bounds cut it apart.
+///
+/// # Examples
+///
+/// ```
+/// use YetAnotherGeometryLibrary::gon::{Containment, Polygon};
+/// use YetAnotherGeometryLibrary::vectors::Vector2;
+/// use YetAnotherGeometryLibrary::voronoi::voronoi;
+/// let field = Polygon::new(vec![
+///     Vector2::new(0.0, 0.0),
+///     Vector2::new(4.0, 0.0),
+///     Vector2::new(4.0, 2.0),
+///     Vector2::new(0.0, 2.0),
+/// ]);
+/// let stations = vec![Vector2::new(1.0, 1.0), Vector2::new(3.0, 1.0), Vector2::new(2.0, 10.0)];
+/// let cells = voronoi(&stations, &field);
+/// assert_eq!(cells[0].area(), 4.0);
+/// assert_eq!(cells[0].contains(Vector2::new(2.0, 1.0)), Containment::OnBoundary);
+/// assert_eq!(cells[1].get_polygons()[0].get_outer().get_points().len(), 4);
+/// assert!(cells[2].get_polygons().is_empty());
+/// ```
+pub fn voronoi(sites: &[Vector2], bounds: &Polygon) -> Vec<MultiPolygon> {
+    let mut first_copy: HashMap<(u64, u64), usize> = HashMap::new();
+    let canonical: Vec<usize> = sites
+        .iter()
+        .enumerate()
+        .map(|(i, pt)| {
+            *first_copy
+                .entry(((pt.x + 0.0).to_bits(), (pt.y + 0.0).to_bits()))
+                .or_insert(i)
+        })
+        .collect();
+    // Only Delaunay neighbours share cell edges, but with too few sites to triangulate every pair is tried
+    let mut neighbors = vec![BTreeSet::new(); sites.len()];
+    match Triangulation::delaunay(sites) {
+        Ok(tri) => {
+            for &(a, b, c) in tri.get_triangles() {
+                for (u, v) in [(a, b), (b, c), (c, a)] {
+                    neighbors[u].insert(v);
+                    neighbors[v].insert(u);
+                }
+            }
+        }
+        Err(_) => {
+            for i in 0..sites.len() {
+                neighbors[i]
+                    .extend((0..sites.len()).filter(|&j| canonical[j] == j && j != canonical[i]));
+            }
+        }
+    }
+    // Start from a box around the bounds, with room to spare so that its edges don't touch them
+    let Some(&first) = bounds.get_points().first() else {
+        return vec![MultiPolygon::new_unchecked(Vec::new()); sites.len()];
+    };
+    let (mut min, mut max) = (first, first);
+    for pt in bounds.get_points() {
+        min = Vector2::new(min.x.min(pt.x), min.y.min(pt.y));
+        max = Vector2::new(max.x.max(pt.x), max.y.max(pt.y));
+    }
+    let margin = max - min;
+    let (min, max) = (min - margin, max + margin);
+    let bounds = MultiPolygon::from(bounds.clone());
+    let mut cells: Vec<MultiPolygon> = Vec::with_capacity(sites.len());
+    for i in 0..sites.len() {
+        if canonical[i] != i {
+            cells.push(cells[canonical[i]].clone());
+            continue;
+        }
+        let mut cell = vec![
+            min,
+            Vector2::new(max.x, min.y),
+            max,
+            Vector2::new(min.x, max.y),
+        ];
+        for &j in &neighbors[i] {
+            cell = clip_half_plane(&cell, (sites[i] + sites[j]) / 2.0, sites[j] - sites[i]);
+        }
+        cells.push(if cell.len() < 3 {
+            MultiPolygon::new_unchecked(Vec::new())
+        } else {
+            bounds.intersection(&Polygon::new(cell).into())
+        });
+    }
+    cells
+}